@@ -0,0 +1,60 @@
+//! Compares lexing throughput when reading from a streaming
+//! `ResetIterator` against the in-memory `SliceCursor` and
+//! `StrCursor`.  Run with `cargo bench --bench cursor`.
+use std::time::{Duration,Instant};
+use lexington::{Any,Lexer,Matcher,Scanner,Unit,Within};
+use lexington::util::{SliceCursor,StrCursor};
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    LeftBrace,
+    RightBrace,
+    Identifier,
+    Number
+}
+
+fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    let whitespace = Any([' ','\n','\t']).one_or_more();
+    let number = Within('0'..='9').one_or_more();
+    let identifier = Within('a'..='z').or(Within('A'..='Z')).or('_')
+        .then(Within('0'..='9').or(Within('a'..='z')).or(Within('A'..='Z')).or('_').zero_or_more());
+    Unit(whitespace,Kind::WhiteSpace)
+        .or(Unit(number,Kind::Number))
+        .or(Unit(identifier,Kind::Identifier))
+        .or(Unit('(',Kind::LeftBrace))
+        .or(Unit(')',Kind::RightBrace))
+}
+
+/// Generate a large input consisting of nested S-expressions.
+fn generate(n: usize) -> String {
+    let mut s = String::new();
+    for i in 0..n {
+        s.push_str(&format!("(define (f{i} x) (add x {i}))\n"));
+    }
+    s
+}
+
+/// Run a given function repeatedly, reporting the best time taken.
+fn measure<F:FnMut() -> usize>(name: &str, bytes: usize, mut f: F) {
+    let mut best = Duration::MAX;
+    let mut count = 0;
+    for _ in 0..10 {
+        let now = Instant::now();
+        count = f();
+        best = best.min(now.elapsed());
+    }
+    let mbs = (bytes as f64 / (1024.0 * 1024.0)) / best.as_secs_f64();
+    println!("{name:<16} {count:>10} tokens {:>10.2?} {mbs:>8.1} MB/s",best);
+}
+
+fn main() {
+    for n in [1_000, 100_000] {
+        let input = generate(n);
+        let chars : Vec<char> = input.chars().collect();
+        println!("input of {} bytes:",input.len());
+        measure("ResetIterator",input.len(),|| Lexer::new(input.chars(),scanner()).count());
+        measure("SliceCursor",input.len(),|| Lexer::with_cursor(SliceCursor::new(&chars),scanner()).count());
+        measure("StrCursor",input.len(),|| Lexer::with_cursor(StrCursor::new(&input),scanner()).count());
+    }
+}
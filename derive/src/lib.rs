@@ -0,0 +1,340 @@
+mod pattern;
+mod syntax;
+
+use proc_macro::{Delimiter,Group,Ident,Literal,Punct,Spacing,Span,TokenStream,TokenTree};
+use pattern::Pattern;
+
+/// Derive `lexington::Lexington` for an enum of unit variants, whose
+/// rules are given by `#[token(..)]`, `#[pattern(..)]`, `#[skip]`,
+/// `#[priority(..)]` and `#[callback(..)]` attributes.  See the
+/// `Lexington` trait for details.
+#[proc_macro_derive(Lexington, attributes(token,pattern,skip,priority,callback))]
+pub fn derive_lexington(input: TokenStream) -> TokenStream {
+    match parse(input).and_then(|(name,variants)| generate(&name,&variants)) {
+        Ok(output) => output,
+        Err(Error(message,span)) => compile_error(&message,span)
+    }
+}
+
+/// An error message, along with where it arose.
+struct Error(String,Span);
+
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut bang = Punct::new('!',Spacing::Alone);
+    bang.set_span(span);
+    let mut group = Group::new(Delimiter::Parenthesis,TokenTree::Literal(literal).into());
+    group.set_span(span);
+    let mut semicolon = Punct::new(';',Spacing::Alone);
+    semicolon.set_span(span);
+    let tokens : [TokenTree;4] = [Ident::new("compile_error",span).into(),bang.into(),group.into(),semicolon.into()];
+    tokens.into_iter().collect()
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Describes how a variant is matched.
+enum Rule {
+    /// A fixed string, as given by `#[token(..)]`.
+    Token(String),
+    /// A pattern, as given by `#[pattern(..)]`.
+    Pattern(Pattern)
+}
+
+struct Variant {
+    name: Ident,
+    rule: Option<(Rule,Span)>,
+    skip: bool,
+    priority: Option<i64>,
+    callback: Option<TokenStream>
+}
+
+impl Variant {
+    fn priority(&self) -> i64 {
+        match (&self.priority,&self.rule) {
+            (Some(p),_) => *p,
+            (None,Some((Rule::Token(_),_))) => 1,
+            (None,_) => 0
+        }
+    }
+
+    /// Get the text of this variant's token, if it has one (and no
+    /// callback which could change its meaning).
+    fn token(&self) -> Option<&str> {
+        match &self.rule {
+            Some((Rule::Token(s),_)) if self.callback.is_none() => Some(s),
+            _ => None
+        }
+    }
+
+    /// Get this variant's pattern, if it has one (and no callback which
+    /// could change its meaning).
+    fn pattern(&self) -> Option<&Pattern> {
+        match &self.rule {
+            Some((Rule::Pattern(p),_)) if self.callback.is_none() => Some(p),
+            _ => None
+        }
+    }
+
+    /// Determine whether this variant's rule matches exactly the
+    /// given text.
+    fn matches(&self, text: &str) -> bool {
+        if self.callback.is_some() { return false; }
+        match &self.rule {
+            Some((Rule::Token(s),_)) => s == text,
+            Some((Rule::Pattern(p),_)) => {
+                let chars : Vec<char> = text.chars().collect();
+                p.run(&chars,0) == Some(chars.len())
+            }
+            None => false
+        }
+    }
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+fn parse(input: TokenStream) -> Result<(Ident,Vec<Variant>),Error> {
+    let mut tokens = input.into_iter().peekable();
+    // Skip attributes and visibility
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(i)) if i.to_string() == "enum" => break,
+            Some(TokenTree::Ident(i)) if i.to_string() == "struct" || i.to_string() == "union" => {
+                return Err(Error("Lexington can only be derived for enums".to_string(),i.span()));
+            }
+            Some(_) => {}
+            None => return Err(Error("expected enum".to_string(),Span::call_site()))
+        }
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(i)) => i,
+        _ => return Err(Error("expected enum name".to_string(),Span::call_site()))
+    };
+    match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+            let variants = parse_variants(g.stream())?;
+            Ok((name,variants))
+        }
+        Some(t) => Err(Error("Lexington cannot be derived for generic enums".to_string(),t.span())),
+        None => Err(Error("expected enum body".to_string(),name.span()))
+    }
+}
+
+fn parse_variants(body: TokenStream) -> Result<Vec<Variant>,Error> {
+    let mut variants = Vec::new();
+    let mut tokens = body.into_iter().peekable();
+    while tokens.peek().is_some() {
+        let mut variant = Variant{name: Ident::new("_",Span::call_site()), rule: None, skip: false, priority: None, callback: None};
+        // Attributes
+        while let Some(TokenTree::Punct(p)) = tokens.peek() {
+            if p.as_char() != '#' { break; }
+            tokens.next();
+            match tokens.next() {
+                Some(TokenTree::Group(g)) => parse_attribute(g,&mut variant)?,
+                _ => return Err(Error("expected attribute".to_string(),Span::call_site()))
+            }
+        }
+        variant.name = match tokens.next() {
+            Some(TokenTree::Ident(i)) => i,
+            Some(t) => return Err(Error("expected variant name".to_string(),t.span())),
+            None => return Err(Error("expected variant name".to_string(),Span::call_site()))
+        };
+        // Fields and discriminants
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+            None => {}
+            Some(TokenTree::Group(g)) => {
+                return Err(Error("Lexington variants cannot have fields".to_string(),g.span()));
+            }
+            Some(TokenTree::Punct(p)) if p.as_char() == '=' => {
+                // Skip the discriminant
+                for t in tokens.by_ref() {
+                    if matches!(&t,TokenTree::Punct(p) if p.as_char() == ',') { break; }
+                }
+            }
+            Some(t) => return Err(Error("unexpected token".to_string(),t.span()))
+        }
+        if variant.rule.is_none() && (variant.skip || variant.priority.is_some() || variant.callback.is_some()) {
+            return Err(Error("variant has no #[token] or #[pattern] attribute".to_string(),variant.name.span()));
+        }
+        variants.push(variant);
+    }
+    Ok(variants)
+}
+
+fn parse_attribute(group: Group, variant: &mut Variant) -> Result<(),Error> {
+    let mut tokens = group.stream().into_iter();
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(i)) => i,
+        _ => return Ok(())
+    };
+    let args = match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => Some(g),
+        _ => None
+    };
+    let span = name.span();
+    match (name.to_string().as_str(),args) {
+        ("token"|"pattern",_) if variant.rule.is_some() => {
+            Err(Error("variant already has a #[token] or #[pattern] attribute".to_string(),span))
+        }
+        ("token",Some(g)) => {
+            let (text,span) = string(g)?;
+            if text.is_empty() { return Err(Error("empty token".to_string(),span)); }
+            variant.rule = Some((Rule::Token(text),span));
+            Ok(())
+        }
+        ("pattern",Some(g)) => {
+            let (text,span) = string(g)?;
+            match Pattern::parse(&text) {
+                Ok(p) => {
+                    variant.rule = Some((Rule::Pattern(p),span));
+                    Ok(())
+                }
+                Err((message,offset)) => Err(Error(format!("invalid pattern: {message} (at offset {offset})"),span))
+            }
+        }
+        ("skip",None) => {
+            variant.skip = true;
+            Ok(())
+        }
+        ("priority",Some(g)) => {
+            let text = g.stream().to_string().replace(' ',"");
+            match text.parse() {
+                Ok(p) => {
+                    variant.priority = Some(p);
+                    Ok(())
+                }
+                Err(_) => Err(Error("expected integer priority".to_string(),g.span()))
+            }
+        }
+        ("callback",Some(g)) if !g.stream().is_empty() => {
+            variant.callback = Some(g.stream());
+            Ok(())
+        }
+        ("token"|"pattern"|"priority"|"callback",_) => Err(Error(format!("expected #[{name}(...)]"),span)),
+        ("skip",_) => Err(Error("expected #[skip]".to_string(),span)),
+        _ => Ok(())
+    }
+}
+
+/// Extract the contents of a string (or character) literal given as an
+/// attribute argument.
+fn string(group: Group) -> Result<(String,Span),Error> {
+    let mut tokens = group.stream().into_iter();
+    match (tokens.next(),tokens.next()) {
+        (Some(TokenTree::Literal(l)),None) => match unquote(&l.to_string()) {
+            Some(s) => Ok((s,l.span())),
+            None => Err(Error("expected string literal".to_string(),l.span()))
+        }
+        _ => Err(Error("expected string literal".to_string(),group.span()))
+    }
+}
+
+/// Decode the source text of a string or character literal.
+fn unquote(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = &raw[hashes..raw.len()-hashes];
+        return Some(body.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+    let body = source.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+        .or_else(|| source.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))?;
+    let mut s = String::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => s.push('\n'),
+            'r' => s.push('\r'),
+            't' => s.push('\t'),
+            '0' => s.push('\0'),
+            'x' => {
+                let hex : String = chars.by_ref().take(2).collect();
+                s.push(char::from(u8::from_str_radix(&hex,16).ok()?));
+            }
+            'u' => {
+                chars.next();
+                let hex : String = chars.by_ref().take_while(|c| *c != '}').collect();
+                s.push(char::from_u32(u32::from_str_radix(&hex.replace('_',""),16).ok()?)?);
+            }
+            '\n' => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            }
+            c => s.push(c)
+        }
+    }
+    Some(s)
+}
+
+// =============================================================================
+// Generation
+// =============================================================================
+
+fn generate(name: &Ident, variants: &[Variant]) -> Result<TokenStream,Error> {
+    check_ambiguity(variants)?;
+    let mut rules = String::new();
+    for v in variants {
+        let matcher = match &v.rule {
+            Some((Rule::Token(s),_)) if s.chars().count() == 1 => format!("{:?}",s.chars().next().unwrap()),
+            Some((Rule::Token(s),_)) => format!("::lexington::Text({s:?})"),
+            Some((Rule::Pattern(p),_)) => p.to_rust(),
+            None => continue
+        };
+        let priority = v.priority();
+        match &v.callback {
+            Some(f) => rules.push_str(&format!("longest.rule_with(input,{matcher},{priority},{f});\n")),
+            None => rules.push_str(&format!("longest.rule(input,{matcher},{priority},{name}::{});\n",v.name))
+        }
+    }
+    let skipped : Vec<String> = variants.iter().filter(|v| v.skip).map(|v| format!("{name}::{}",v.name)).collect();
+    let is_skipped = match skipped.is_empty() {
+        true => "false".to_string(),
+        false => format!("matches!(self,{})",skipped.join("|"))
+    };
+    let longest = if rules.is_empty() { "longest" } else { "mut longest" };
+    let source = format!("
+        impl ::lexington::Lexington for {name} {{
+            fn scan<C: ::lexington::util::Cursor<Item=char>>(input: &mut C) -> Option<Self> {{
+                let {longest} = ::lexington::Longest::new();
+                {rules}
+                longest.finish(input)
+            }}
+
+            fn is_skipped(self) -> bool {{
+                {is_skipped}
+            }}
+        }}");
+    source.parse().map_err(|_| Error("failed to generate lexer".to_string(),name.span()))
+}
+
+/// Check that no two rules of the same priority match exactly the same
+/// text, as far as can be determined from their tokens and from
+/// identical patterns.
+fn check_ambiguity(variants: &[Variant]) -> Result<(),Error> {
+    for (i,v) in variants.iter().enumerate() {
+        if let Some(text) = v.token() {
+            for (j,w) in variants.iter().enumerate() {
+                if i != j && v.priority() == w.priority() && w.matches(text) && (w.token().is_none() || i < j) {
+                    let span = v.rule.as_ref().unwrap().1;
+                    return Err(Error(format!("ambiguous rules: \"{text}\" is matched by both {} and {} (consider #[priority])",v.name,w.name),span));
+                }
+            }
+        } else if let Some(p) = v.pattern() {
+            for w in &variants[i+1..] {
+                if v.priority() == w.priority() && w.pattern() == Some(p) {
+                    let span = w.rule.as_ref().unwrap().1;
+                    return Err(Error(format!("ambiguous rules: {} and {} have the same pattern (consider #[priority])",v.name,w.name),span));
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// =============================================================================
+// Patterns
+// =============================================================================
+
+// NOTE: this module is shared between `lexington` (for specifications)
+// and `lexington-derive` (for `#[pattern(..)]`), which each include it
+// via `#[path]`, and so it must not depend on either.
+
+/// A parsed pattern, as given by `#[pattern(...)]` or by a rule in a
+/// specification (with any definitions expanded).
+#[derive(Clone,Debug,PartialEq)]
+pub enum Pattern {
+    /// A fixed string.
+    Text(String),
+    /// A (possibly negated) set of inclusive character ranges.
+    Class(Vec<(char,char)>,bool),
+    /// A sequence of patterns.
+    Then(Vec<Pattern>),
+    /// A choice between patterns, taking the first which matches.
+    Or(Vec<Pattern>),
+    ZeroOrMore(Box<Pattern>),
+    OneOrMore(Box<Pattern>),
+    Optional(Box<Pattern>)
+}
+
+impl Pattern {
+    /// Determine whether this pattern can match the empty string.
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Pattern::Text(s) => s.is_empty(),
+            Pattern::Class(..) => false,
+            Pattern::Then(ps) => ps.iter().all(Pattern::is_nullable),
+            Pattern::Or(ps) => ps.iter().any(Pattern::is_nullable),
+            Pattern::ZeroOrMore(_) | Pattern::Optional(_) => true,
+            Pattern::OneOrMore(p) => p.is_nullable()
+        }
+    }
+
+    /// Generate an expression constructing the equivalent matcher.
+    pub fn to_rust(&self) -> String {
+        match self {
+            Pattern::Text(s) if s.chars().count() == 1 => format!("{:?}",s.chars().next().unwrap()),
+            Pattern::Text(s) => format!("::lexington::Text({s:?})"),
+            Pattern::Class(ranges,negated) => {
+                let ranges : Vec<String> = ranges.iter().map(|(lo,hi)| format!("({lo:?},{hi:?})")).collect();
+                let name = if *negated { "NotClass" } else { "Class" };
+                format!("::lexington::{name}([{}])",ranges.join(","))
+            }
+            Pattern::Then(ps) => Self::fold(ps,"then"),
+            Pattern::Or(ps) => Self::fold(ps,"or"),
+            Pattern::ZeroOrMore(p) => format!("::lexington::Matcher::zero_or_more({})",p.to_rust()),
+            Pattern::OneOrMore(p) => format!("::lexington::Matcher::one_or_more({})",p.to_rust()),
+            Pattern::Optional(p) => format!("::lexington::Matcher::optional({})",p.to_rust())
+        }
+    }
+
+    fn fold(ps: &[Pattern], method: &str) -> String {
+        let (last,rest) = ps.split_last().unwrap();
+        rest.iter().rev().fold(last.to_rust(),|acc,p| format!("::lexington::Matcher::{method}({},{acc})",p.to_rust()))
+    }
+}
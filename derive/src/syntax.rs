@@ -0,0 +1,260 @@
+use crate::pattern::Pattern;
+
+// =============================================================================
+// Patterns
+// =============================================================================
+
+/// The surface syntax of `#[pattern(...)]`, along with an interpreter
+/// used to check for ambiguous rules.
+impl Pattern {
+    /// Parse a pattern, reporting the (character) offset of any error.
+    pub fn parse(text: &str) -> Result<Pattern,(String,usize)> {
+        let mut parser = Parser{chars: text.chars().collect(), index: 0};
+        let pattern = parser.alternatives()?;
+        match parser.peek() {
+            Some(')') => Err(parser.error("unmatched `)`")),
+            Some(_) => Err(parser.error("unexpected character")),
+            None if pattern.is_nullable() => Err(("pattern can match the empty string".to_string(),0)),
+            None => Ok(pattern)
+        }
+    }
+
+    /// Match this pattern against some text from a given position,
+    /// returning the end of the match (if any).  This follows the
+    /// semantics of the matcher combinators exactly, meaning that
+    /// repetitions never give back what they consumed.
+    pub fn run(&self, text: &[char], i: usize) -> Option<usize> {
+        match self {
+            Pattern::Text(s) => s.chars().try_fold(i,|j,c| (text.get(j) == Some(&c)).then_some(j+1)),
+            Pattern::Class(ranges,negated) => {
+                let c = text.get(i)?;
+                let within = ranges.iter().any(|(lo,hi)| lo <= c && c <= hi);
+                (within != *negated).then_some(i+1)
+            }
+            Pattern::Then(ps) => ps.iter().try_fold(i,|j,p| p.run(text,j)),
+            Pattern::Or(ps) => ps.iter().find_map(|p| p.run(text,i)),
+            Pattern::ZeroOrMore(p) => Some(Self::repeat(p,text,i)),
+            Pattern::OneOrMore(p) => p.run(text,i).map(|j| Self::repeat(p,text,j)),
+            Pattern::Optional(p) => Some(p.run(text,i).unwrap_or(i))
+        }
+    }
+
+    fn repeat(p: &Pattern, text: &[char], mut i: usize) -> usize {
+        while let Some(j) = p.run(text,i) { i = j; }
+        i
+    }
+
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() { self.index += 1; }
+        c
+    }
+
+    fn error(&self, message: &str) -> (String,usize) {
+        (message.to_string(),self.index)
+    }
+
+    fn alternatives(&mut self) -> Result<Pattern,(String,usize)> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.next();
+            alternatives.push(self.sequence()?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => Pattern::Or(alternatives)
+        })
+    }
+
+    fn sequence(&mut self) -> Result<Pattern,(String,usize)> {
+        let mut items = Vec::new();
+        while !matches!(self.peek(),None|Some('|')|Some(')')) {
+            let atom = self.atom()?;
+            let start = self.index;
+            let item = match self.peek() {
+                Some('*') => Pattern::ZeroOrMore(Box::new(atom)),
+                Some('+') => Pattern::OneOrMore(Box::new(atom)),
+                Some('?') => Pattern::Optional(Box::new(atom)),
+                _ => { items.push(atom); continue; }
+            };
+            self.next();
+            if matches!(item,Pattern::ZeroOrMore(ref p)|Pattern::OneOrMore(ref p) if p.is_nullable()) {
+                return Err(("repetition of a pattern which can match the empty string".to_string(),start));
+            }
+            items.push(item);
+        }
+        match items.len() {
+            0 => Err(self.error("empty pattern")),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(Pattern::Then(items))
+        }
+    }
+
+    fn atom(&mut self) -> Result<Pattern,(String,usize)> {
+        let start = self.index;
+        match self.next() {
+            Some('(') => {
+                let p = self.alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(("unclosed `(`".to_string(),start));
+                }
+                Ok(p)
+            }
+            Some('[') => self.class(start),
+            Some('.') => Ok(Pattern::Class(vec![('\n','\n')],true)),
+            Some('\\') => self.escape(start).map(Item::into_pattern),
+            Some(c@('*'|'+'|'?')) => Err((format!("nothing to repeat before `{c}`"),start)),
+            Some(c@('{'|'}'|'^'|'$')) => Err((format!("unsupported character `{c}` (use `\\{c}`)"),start)),
+            Some(c) => Ok(Pattern::Text(c.to_string())),
+            None => Err(self.error("unexpected end of pattern"))
+        }
+    }
+
+    /// Parse an escape sequence (with the backslash consumed).
+    fn escape(&mut self, start: usize) -> Result<Item,(String,usize)> {
+        match self.next() {
+            Some(c) => match Self::shorthand(c.to_ascii_lowercase()) {
+                Some(ranges) => Ok(Item::Class(ranges,c.is_ascii_uppercase())),
+                None => Ok(Item::Char(Self::literal(c,start)?))
+            }
+            None => Err(("incomplete escape sequence".to_string(),start))
+        }
+    }
+
+    /// Get the ranges for a shorthand class (e.g. `\d`).
+    fn shorthand(c: char) -> Option<Vec<(char,char)>> {
+        match c {
+            'd' => Some(vec![('0','9')]),
+            'w' => Some(vec![('a','z'),('A','Z'),('0','9'),('_','_')]),
+            's' => Some(vec![(' ',' '),('\t','\t'),('\n','\n'),('\r','\r')]),
+            _ => None
+        }
+    }
+
+    /// Decode an escaped character (e.g. `\n` or `\.`).
+    fn literal(c: char, start: usize) -> Result<char,(String,usize)> {
+        match c {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            c if c.is_ascii_punctuation() => Ok(c),
+            _ => Err((format!("unknown escape sequence `\\{c}`"),start))
+        }
+    }
+
+    /// Parse a character class (with the opening bracket consumed).
+    fn class(&mut self, start: usize) -> Result<Pattern,(String,usize)> {
+        let negated = self.peek() == Some('^');
+        if negated { self.next(); }
+        let mut ranges = Vec::new();
+        loop {
+            let lo = match self.class_char(start)? {
+                Some(Item::Class(rs,false)) => { ranges.extend(rs); continue; }
+                Some(Item::Char(c)) => c,
+                Some(_) => return Err(self.error("negated escape within class")),
+                None if ranges.is_empty() => return Err(("empty character class".to_string(),start)),
+                None => break
+            };
+            if self.peek() == Some('-') && self.chars.get(self.index+1) != Some(&']') {
+                self.next();
+                let at = self.index;
+                match self.class_char(start)? {
+                    Some(Item::Char(hi)) if lo <= hi => ranges.push((lo,hi)),
+                    Some(Item::Char(_)) => return Err(("invalid range (start exceeds end)".to_string(),at)),
+                    _ => return Err(("invalid range".to_string(),at))
+                }
+            } else {
+                ranges.push((lo,lo));
+            }
+        }
+        Ok(Pattern::Class(ranges,negated))
+    }
+
+    /// Parse the next character within a class, returning `None` at the
+    /// closing bracket.
+    fn class_char(&mut self, start: usize) -> Result<Option<Item>,(String,usize)> {
+        let at = self.index;
+        match self.next() {
+            Some(']') => Ok(None),
+            Some('\\') => self.escape(at).map(Some),
+            Some(c) => Ok(Some(Item::Char(c))),
+            None => Err(("unclosed `[`".to_string(),start))
+        }
+    }
+}
+
+/// A single character or escaped class (e.g. `\d`), which can appear
+/// both within a character class and on its own.
+enum Item {
+    Char(char),
+    Class(Vec<(char,char)>,bool)
+}
+
+impl Item {
+    fn into_pattern(self) -> Pattern {
+        match self {
+            Item::Char(c) => Pattern::Text(c.to_string()),
+            Item::Class(ranges,negated) => Pattern::Class(ranges,negated)
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    fn run(pattern: &str, text: &str) -> Option<usize> {
+        let chars : Vec<char> = text.chars().collect();
+        Pattern::parse(pattern).unwrap().run(&chars,0)
+    }
+
+    #[test]
+    fn test_01() {
+        assert_eq!(run("[a-z_]+","ab_c1"),Some(4));
+        assert_eq!(run("[^a-z]","a"),None);
+        assert_eq!(run(r"\d+(\.\d+)?","1.5x"),Some(3));
+        assert_eq!(run(r"\d+(\.\d+)?","1.x"),Some(1));
+        assert_eq!(run("ab|a","ac"),Some(1));
+        assert_eq!(run(r"\w\S.","a\tb"),None);
+    }
+
+    #[test]
+    fn test_02() {
+        // Repetitions never give back what they consumed
+        assert_eq!(run("a*a","aaa"),None);
+        assert_eq!(run("[a-]+","a-b"),Some(2));
+    }
+
+    #[test]
+    fn test_03() {
+        assert_eq!(Pattern::parse("a(b").unwrap_err().1,1);
+        assert_eq!(Pattern::parse("ab)").unwrap_err().1,2);
+        assert_eq!(Pattern::parse("a|").unwrap_err().1,2);
+        assert_eq!(Pattern::parse("[z-a]").unwrap_err().1,3);
+        assert_eq!(Pattern::parse("+").unwrap_err().1,0);
+        assert_eq!(Pattern::parse(r"\q").unwrap_err().1,0);
+        assert!(Pattern::parse("a?").is_err());
+        assert!(Pattern::parse("(a|b?)+").is_err());
+    }
+}
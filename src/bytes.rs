@@ -0,0 +1,194 @@
+use crate::Matcher;
+use crate::util::{Cursor};
+
+// =============================================================================
+// ASCII Classes
+// =============================================================================
+
+/// Define a matcher for a single byte from a given class of ASCII
+/// characters, as determined by the corresponding method on `u8`.
+macro_rules! ascii_class {
+    ($(#[$doc:meta])* $name:ident, $method:ident) => {
+        $(#[$doc])*
+        #[derive(Clone,Copy,Debug)]
+        pub struct $name;
+
+        impl Matcher for $name {
+            type Item = u8;
+
+            fn matches<C:Cursor<Item=u8>>(&self, input: &mut C) -> bool {
+                match input.next() {
+                    Some(b) if b.$method() => true,
+                    Some(_) => {
+                        input.backup(1);
+                        false
+                    }
+                    _ => false
+                }
+            }
+        }
+    }
+}
+
+ascii_class!(
+    /// Matches any ASCII alphabetic byte (i.e. `[a-zA-Z]`).
+    AsciiAlpha, is_ascii_alphabetic);
+ascii_class!(
+    /// Matches any ASCII alphanumeric byte (i.e. `[a-zA-Z0-9]`).
+    AsciiAlphanumeric, is_ascii_alphanumeric);
+ascii_class!(
+    /// Matches any ASCII decimal digit (i.e. `[0-9]`).
+    AsciiDigit, is_ascii_digit);
+ascii_class!(
+    /// Matches any ASCII hexadecimal digit (i.e. `[0-9a-fA-F]`).
+    AsciiHexDigit, is_ascii_hexdigit);
+ascii_class!(
+    /// Matches any ASCII lowercase byte (i.e. `[a-z]`).
+    AsciiLowercase, is_ascii_lowercase);
+ascii_class!(
+    /// Matches any ASCII uppercase byte (i.e. `[A-Z]`).
+    AsciiUppercase, is_ascii_uppercase);
+ascii_class!(
+    /// Matches any ASCII whitespace byte (space, tab, newline, form
+    /// feed or carriage return).
+    AsciiWhitespace, is_ascii_whitespace);
+ascii_class!(
+    /// Matches any ASCII punctuation byte.
+    AsciiPunctuation, is_ascii_punctuation);
+ascii_class!(
+    /// Matches any ASCII control byte.
+    AsciiControl, is_ascii_control);
+ascii_class!(
+    /// Matches any ASCII graphic byte (i.e. printable and not a space).
+    AsciiGraphic, is_ascii_graphic);
+
+// =============================================================================
+// Literals
+// =============================================================================
+
+/// Matches an exact sequence of bytes (e.g. `Literal(b"GET")`).
+#[derive(Clone,Copy,Debug)]
+pub struct Literal<'a>(pub &'a [u8]);
+
+impl Matcher for Literal<'_> {
+    type Item = u8;
+
+    fn matches<C:Cursor<Item=u8>>(&self, input: &mut C) -> bool {
+        match_literal(self.0, input, |a,b| a == b)
+    }
+}
+
+/// Matches an exact sequence of bytes, ignoring ASCII case (e.g.
+/// `IgnoreCase(b"content-length")`).
+#[derive(Clone,Copy,Debug)]
+pub struct IgnoreCase<'a>(pub &'a [u8]);
+
+impl Matcher for IgnoreCase<'_> {
+    type Item = u8;
+
+    fn matches<C:Cursor<Item=u8>>(&self, input: &mut C) -> bool {
+        match_literal(self.0, input, |a,b| a.eq_ignore_ascii_case(&b))
+    }
+}
+
+/// Match a sequence of bytes using a given comparison, backing up
+/// over anything consumed if the match fails.
+fn match_literal<C:Cursor<Item=u8>,F:Fn(u8,u8)->bool>(bytes: &[u8], input: &mut C, eq: F) -> bool {
+    for (i,b) in bytes.iter().enumerate() {
+        match input.next() {
+            Some(c) if eq(*b,c) => {}
+            Some(_) => {
+                input.backup(i+1);
+                return false;
+            }
+            None => {
+                input.backup(i);
+                return false;
+            }
+        }
+    }
+    true
+}
+
+// =============================================================================
+// Skipping
+// =============================================================================
+
+/// Matches zero or more bytes up to (but not including) a given
+/// delimiter, or the end of input.  When the input is held in memory,
+/// this searches a word at a time rather than byte-by-byte.
+#[derive(Clone,Copy,Debug)]
+pub struct Until(pub u8);
+
+impl Matcher for Until {
+    type Item = u8;
+
+    fn matches<C:Cursor<Item=u8>>(&self, input: &mut C) -> bool {
+        match input.remaining() {
+            Some(bytes) => {
+                // Fast path
+                let n = find_byte(self.0,bytes).unwrap_or(bytes.len());
+                input.advance(n);
+            }
+            None => {
+                // Slow path
+                while let Some(b) = input.next() {
+                    if b == self.0 {
+                        input.backup(1);
+                        break;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Matches zero or more bytes up to (but not including) any one of a
+/// given set of delimiters, or the end of input.
+#[derive(Clone,Copy,Debug)]
+pub struct UntilAny<const N:usize>(pub [u8;N]);
+
+impl<const N:usize> Matcher for UntilAny<N> {
+    type Item = u8;
+
+    fn matches<C:Cursor<Item=u8>>(&self, input: &mut C) -> bool {
+        match input.remaining() {
+            Some(bytes) => {
+                // Fast path
+                let n = bytes.iter().position(|b| self.0.contains(b)).unwrap_or(bytes.len());
+                input.advance(n);
+            }
+            None => {
+                // Slow path
+                while let Some(b) = input.next() {
+                    if self.0.contains(&b) {
+                        input.backup(1);
+                        break;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Find the index of the first occurrence of a given byte in a slice
+/// of bytes.  This examines eight bytes at a time using the standard
+/// "has zero byte" bit trick.
+pub fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const LO : u64 = 0x0101_0101_0101_0101;
+    const HI : u64 = 0x8080_8080_8080_8080;
+    let pattern = LO * (needle as u64);
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap()) ^ pattern;
+        if (word.wrapping_sub(LO) & !word & HI) != 0 {
+            // Some byte in this chunk matches
+            break;
+        }
+        offset += 8;
+    }
+    haystack[offset..].iter().position(|b| *b == needle).map(|i| offset + i)
+}
@@ -0,0 +1,192 @@
+use std::marker::PhantomData;
+use crate::{Lexer,Matcher,Scanner,Token};
+use crate::util::{Cursor,StrCursor};
+
+// =============================================================================
+// Lexington
+// =============================================================================
+
+/// A token enum whose rules are given by attributes on its variants,
+/// as generated by `#[derive(Lexington)]`.  Each variant can have one
+/// of:
+///
+/// * `#[token("==")]`, which matches a fixed string (or character).
+/// * `#[pattern("[a-z]+")]`, which matches a regular expression
+///   supporting literals, escapes (e.g. `\n`, `\.` and `\d`), `.`
+///   (any character except newline), classes (e.g. `[^a-z_]`), groups,
+///   `|`, `*`, `+` and `?`.  As for the matcher combinators,
+///   repetitions are greedy and never give back what they consumed.
+///
+/// Along with any of:
+///
+/// * `#[skip]`, meaning its tokens are dropped by `lex()`.
+/// * `#[priority(n)]`, which resolves ties (see below).
+/// * `#[callback(f)]`, where `f` has type `fn(&str) -> Option<Self>`
+///   and is given the text matched.  This can either reject the match
+///   (by returning `None`) or reclassify it.
+///
+/// At each position, all rules are tried and the longest match wins.
+/// Ties are broken by priority, which defaults to `1` for tokens and
+/// `0` for patterns (i.e. keywords beat identifiers).  A token which
+/// is also matched by another rule of the same priority is reported as
+/// ambiguous at compile time, as are two identical patterns of the same
+/// priority and invalid patterns.  Other ties between patterns (e.g.
+/// `[a-z]+` and `[a-c]+`) are not detected, and go to the earlier
+/// variant.  For example:
+///
+/// ```
+/// use lexington::Lexington;
+///
+/// #[derive(Clone,Copy,Debug,PartialEq,Lexington)]
+/// enum Kind {
+///     #[skip]
+///     #[pattern(r"\s+")]
+///     WhiteSpace,
+///     #[token("let")]
+///     Let,
+///     #[pattern("[a-z]+")]
+///     Identifier,
+///     #[token("=")]
+///     Equals,
+///     #[token("==")]
+///     EqualsEquals
+/// }
+///
+/// let tokens = Kind::lex("let x == y");
+/// assert_eq!(tokens,&[(Kind::Let,0..3),(Kind::Identifier,4..5),(Kind::EqualsEquals,6..8),(Kind::Identifier,9..10)]);
+/// ```
+///
+/// Whereas the following fails to compile:
+///
+/// ```compile_fail
+/// use lexington::Lexington;
+///
+/// #[derive(Clone,Copy,Lexington)]
+/// enum Kind {
+///     #[token("if")]
+///     If,
+///     #[pattern("[a-z]+")]
+///     #[priority(1)]
+///     Identifier
+/// }
+/// ```
+///
+/// As does this:
+///
+/// ```compile_fail
+/// use lexington::Lexington;
+///
+/// #[derive(Clone,Copy,Lexington)]
+/// enum Kind {
+///     #[pattern("[a-z]+")]
+///     Identifier,
+///     #[pattern("[a-z]+")]
+///     Keyword
+/// }
+/// ```
+pub trait Lexington : Copy {
+    /// Scan the next token (if any) from a given cursor.
+    fn scan<C:Cursor<Item=char>>(input: &mut C) -> Option<Self>;
+
+    /// Determine whether tokens of this kind are skipped.
+    fn is_skipped(self) -> bool;
+
+    /// Construct a scanner for this token type.
+    fn scanner() -> Rules<Self> {
+        Rules(PhantomData)
+    }
+
+    /// Construct a lexer over a given string.  Skipped tokens are still
+    /// produced, such that the input can be recovered in full.
+    fn lexer(input: &str) -> Lexer<StrCursor<'_>,Rules<Self>> {
+        Lexer::with_cursor(StrCursor::new(input),Self::scanner())
+    }
+
+    /// Split a string into tokens, dropping those which are skipped.
+    /// Lexing stops at the first character not matched by any rule.
+    fn lex(input: &str) -> Vec<Token<Self>> {
+        Self::lexer(input).filter(|t| !t.kind.is_skipped()).collect()
+    }
+}
+
+/// The scanner for a token type implementing `Lexington`.
+#[derive(Debug)]
+pub struct Rules<T>(PhantomData<T>);
+
+impl<T> Clone for Rules<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for Rules<T> {}
+
+impl<T:Lexington> Scanner for Rules<T> {
+    type Item = char;
+    type Token = T;
+
+    fn scan<C:Cursor<Item=char>>(&self, input: &mut C) -> Option<T> {
+        T::scan(input)
+    }
+}
+
+// =============================================================================
+// Longest Match
+// =============================================================================
+
+/// Selects the longest match from a number of rules tried at the same
+/// position, with ties broken by priority and then by order.  Empty
+/// matches are ignored.
+#[derive(Debug)]
+pub struct Longest<T> {
+    best: Option<(usize,i64,T)>
+}
+
+impl<T> Longest<T> {
+    pub fn new() -> Self {
+        Self{best: None}
+    }
+
+    /// Try a rule which produces a given token.
+    pub fn rule<C:Cursor<Item=char>,M:Matcher<Item=char>>(&mut self, input: &mut C, matcher: M, priority: i64, token: T) {
+        if let Some(n) = self.length(input,matcher,priority) {
+            self.best = Some((n,priority,token));
+        }
+    }
+
+    /// Try a rule whose token is determined from the text matched,
+    /// where `None` rejects the match.
+    pub fn rule_with<C,M,F>(&mut self, input: &mut C, matcher: M, priority: i64, callback: F)
+    where C:Cursor<Item=char>, M:Matcher<Item=char>, F:FnOnce(&str)->Option<T> {
+        if let Some(n) = self.length(input,matcher,priority) {
+            let text : String = input.by_ref().take(n).collect();
+            input.backup(n);
+            if let Some(t) = callback(&text) {
+                self.best = Some((n,priority,t));
+            }
+        }
+    }
+
+    /// Determine the length of a rule's match, provided it would beat
+    /// the best so far.  The input is left unchanged.
+    fn length<C:Cursor<Item=char>,M:Matcher<Item=char>>(&self, input: &mut C, matcher: M, priority: i64) -> Option<usize> {
+        let start = input.offset();
+        if !matcher.matches(input) { return None; }
+        let n = input.offset() - start;
+        input.backup(n);
+        let better = match &self.best {
+            Some((m,p,_)) => n > *m || (n == *m && priority > *p),
+            None => n > 0
+        };
+        if better { Some(n) } else { None }
+    }
+
+    /// Consume the winning match (if any) and return its token.
+    pub fn finish<C:Cursor<Item=char>>(self, input: &mut C) -> Option<T> {
+        let (n,_,t) = self.best?;
+        input.advance(n);
+        Some(t)
+    }
+}
+
+impl<T> Default for Longest<T> {
+    fn default() -> Self { Self::new() }
+}
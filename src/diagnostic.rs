@@ -0,0 +1,267 @@
+use std::fmt;
+use std::fmt::Write;
+use crate::util::{Encoding,LineIndex,OffsetKind,Region};
+
+/// Number of columns used to display a tab character.
+const TAB_WIDTH : usize = 4;
+
+/// Identifies how serious a diagnostic is.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord)]
+pub enum Severity {
+    Help,
+    Note,
+    Warning,
+    Error
+}
+
+impl Severity {
+    /// ANSI escape sequence used to highlight this severity.
+    fn colour(&self) -> &'static str {
+        match self {
+            Severity::Help => "\x1b[1;36m",
+            Severity::Note => "\x1b[1;32m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Error => "\x1b[1;31m"
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Help => write!(f,"help"),
+            Severity::Note => write!(f,"note"),
+            Severity::Warning => write!(f,"warning"),
+            Severity::Error => write!(f,"error")
+        }
+    }
+}
+
+/// Associates a message with a region of the source text.  Primary
+/// labels identify the cause of a diagnostic, whilst secondary labels
+/// provide additional context.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Label {
+    pub region: Region,
+    pub message: String,
+    pub primary: bool
+}
+
+/// A message about a source file (e.g. a syntax error), which can be
+/// rendered along with the relevant snippets of source text.  Regions
+/// are measured in chars.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>
+}
+
+impl Diagnostic {
+    pub fn new<S:Into<String>>(severity: Severity, message: S) -> Self {
+        Self{severity, message: message.into(), labels: Vec::new(), notes: Vec::new()}
+    }
+
+    /// Construct an error diagnostic.
+    pub fn error<S:Into<String>>(message: S) -> Self {
+        Self::new(Severity::Error,message)
+    }
+
+    /// Construct a warning diagnostic.
+    pub fn warning<S:Into<String>>(message: S) -> Self {
+        Self::new(Severity::Warning,message)
+    }
+
+    /// Add a primary label to this diagnostic.  A reversed region
+    /// (i.e. whose end precedes its start) is normalised.
+    pub fn with_label<S:Into<String>>(mut self, region: Region, message: S) -> Self {
+        self.labels.push(Label{region: normalise(region), message: message.into(), primary: true});
+        self
+    }
+
+    /// Add a secondary label to this diagnostic.  A reversed region is
+    /// normalised, as for `with_label()`.
+    pub fn with_secondary<S:Into<String>>(mut self, region: Region, message: S) -> Self {
+        self.labels.push(Label{region: normalise(region), message: message.into(), primary: false});
+        self
+    }
+
+    /// Add a note to be displayed after the source snippets.
+    pub fn with_note<S:Into<String>>(mut self, note: S) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Render this diagnostic as plain text.
+    pub fn render(&self, name: &str, source: &str) -> String {
+        Renderer{diagnostic: self, index: LineIndex::new(source,OffsetKind::Chars), colour: false}.render(name)
+    }
+
+    /// Render this diagnostic as text highlighted with ANSI colour
+    /// codes.
+    pub fn render_ansi(&self, name: &str, source: &str) -> String {
+        Renderer{diagnostic: self, index: LineIndex::new(source,OffsetKind::Chars), colour: true}.render(name)
+    }
+}
+
+// =============================================================================
+// Renderer
+// =============================================================================
+
+const BLUE : &str = "\x1b[1;34m";
+const BOLD : &str = "\x1b[1m";
+const RESET : &str = "\x1b[0m";
+
+/// A label whose region has been resolved into (zero-based) lines
+/// and display columns.
+struct Resolved<'a> {
+    label: &'a Label,
+    start: (usize,usize),
+    end: (usize,usize)
+}
+
+impl Resolved<'_> {
+    fn is_multiline(&self) -> bool {
+        self.start.0 != self.end.0
+    }
+}
+
+struct Renderer<'a> {
+    diagnostic: &'a Diagnostic,
+    index: LineIndex<'a>,
+    colour: bool
+}
+
+impl<'a> Renderer<'a> {
+    fn render(&self, name: &str) -> String {
+        let mut out = String::new();
+        let labels = self.resolve();
+        let severity = self.diagnostic.severity;
+        // Header
+        let _ = writeln!(out,"{}{severity}{}: {}{}",self.paint(severity.colour()),self.paint(BOLD),self.diagnostic.message,self.paint(RESET));
+        // Determine lines to display
+        let lines = self.lines(&labels);
+        let width = lines.last().map_or(1,|l| (l+1).to_string().len());
+        let pad = " ".repeat(width);
+        let gutter = format!("{}{pad} |{}",self.paint(BLUE),self.paint(RESET));
+        // Location
+        if let Some(l) = labels.iter().find(|l| l.label.primary).or(labels.first()) {
+            let pos = self.index.position(normalise(l.label.region).start,Encoding::Utf32);
+            let _ = writeln!(out,"{pad}{}-->{} {name}:{}:{}",self.paint(BLUE),self.paint(RESET),pos.line+1,pos.character+1);
+        }
+        if !lines.is_empty() {
+            let _ = writeln!(out,"{gutter}");
+        }
+        let multiline = labels.iter().any(|l| l.is_multiline());
+        let mut last = None;
+        for &line in &lines {
+            if let Some(l) = last {
+                if line > l + 1 { let _ = writeln!(out,"{}...{}",self.paint(BLUE),self.paint(RESET)); }
+            }
+            last = Some(line);
+            // Source line
+            let number = format!("{:>width$}",line+1);
+            let margin = if multiline { self.margin(&labels,line,false) } else { String::new() };
+            let text = format!("{margin}{}",expand(self.index.line(line)));
+            let text = text.trim_end();
+            let sep = if text.is_empty() { "" } else { " " };
+            let _ = writeln!(out,"{}{number} |{}{sep}{text}",self.paint(BLUE),self.paint(RESET));
+            // Underlines for labels starting or ending on this line
+            for l in labels.iter().filter(|l| l.start.0 == line || l.end.0 == line) {
+                let colour = if l.label.primary { severity.colour() } else { BLUE };
+                let mark = if l.label.primary { "^" } else { "-" };
+                let margin = if multiline { self.margin(&labels,line,true) } else { String::new() };
+                let marker = if !l.is_multiline() {
+                    let n = (l.end.1 - l.start.1).max(1);
+                    format!("{margin}{}{}"," ".repeat(l.start.1), mark.repeat(n))
+                } else if l.start.0 == line {
+                    format!(" {}{mark}","_".repeat(l.start.1 + 1))
+                } else {
+                    format!("|{}{mark}","_".repeat(l.end.1))
+                };
+                let message = if (l.is_multiline() && l.start.0 == line) || l.label.message.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}",l.label.message)
+                };
+                let _ = writeln!(out,"{gutter} {}{marker}{message}{}",self.paint(colour),self.paint(RESET));
+            }
+        }
+        if !lines.is_empty() {
+            let _ = writeln!(out,"{gutter}");
+        }
+        // Notes
+        for note in &self.diagnostic.notes {
+            let _ = writeln!(out,"{pad} {}={} {}note{}: {note}",self.paint(BLUE),self.paint(RESET),self.paint(BOLD),self.paint(RESET));
+        }
+        out
+    }
+
+    /// Resolve the regions of all labels into line and column pairs.
+    fn resolve(&self) -> Vec<Resolved<'a>> {
+        let mut labels = Vec::new();
+        for label in &self.diagnostic.labels {
+            // Labels can be constructed directly, so may be reversed
+            let region = normalise(label.region);
+            let start = self.column(region.start);
+            let mut end = self.column(region.end);
+            if end.0 > start.0 && end.1 == 0 {
+                // Region ends at the start of a line, so treat it as
+                // ending at the end of the previous line.
+                end = (end.0 - 1, expand(self.index.line(end.0 - 1)).len());
+            }
+            labels.push(Resolved{label,start,end});
+        }
+        labels
+    }
+
+    /// Determine the (zero-based) line and display column of an
+    /// offset, taking into account the expansion of tabs.
+    fn column(&self, offset: usize) -> (usize,usize) {
+        let pos = self.index.position(offset,Encoding::Utf32);
+        let line = self.index.line(pos.line as usize);
+        let prefix : String = line.chars().take(pos.character as usize).collect();
+        (pos.line as usize, expand(&prefix).chars().count())
+    }
+
+    /// Determine the lines to display.  For labels spanning many
+    /// lines, only the first and last few lines are shown.
+    fn lines(&self, labels: &[Resolved]) -> Vec<usize> {
+        let mut lines = Vec::new();
+        for l in labels {
+            if l.end.0 - l.start.0 <= 4 {
+                lines.extend(l.start.0..=l.end.0);
+            } else {
+                lines.extend([l.start.0,l.start.0+1,l.end.0-1,l.end.0]);
+            }
+        }
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    }
+
+    /// Construct the margin used for showing the extent of multi-line
+    /// labels on a given line.
+    fn margin(&self, labels: &[Resolved], line: usize, underline: bool) -> String {
+        let active = labels.iter().any(|l| {
+            l.is_multiline() && l.start.0 < line && (line < l.end.0 || (line == l.end.0 && !underline))
+        });
+        if active { "| ".to_string() } else { "  ".to_string() }
+    }
+
+    /// Return a given escape sequence, but only when colour is enabled.
+    fn paint(&self, code: &'static str) -> &'static str {
+        if self.colour { code } else { "" }
+    }
+}
+
+/// Swap the ends of a reversed region.
+fn normalise(region: Region) -> Region {
+    Region::new(region.start.min(region.end),region.start.max(region.end))
+}
+
+/// Expand tabs into spaces.
+fn expand(text: &str) -> String {
+    text.replace('\t',&" ".repeat(TAB_WIDTH))
+}
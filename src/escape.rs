@@ -0,0 +1,320 @@
+use std::fmt;
+use std::iter::{Enumerate,Peekable};
+use std::str::Chars;
+use crate::util::Region;
+
+/// Identifies the escape sequences recognised within string literals.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Dialect {
+    /// Simple escapes (e.g. `\n` and `\?`), octal escapes (e.g.
+    /// `\033`), hex escapes of any length (e.g. `\x1b`), universal
+    /// character names (e.g. `\u00e9` and `\U0001F600`) and line
+    /// splices.
+    C,
+    /// Simple escapes (e.g. `\n` and `\/`) and UTF-16 escapes (e.g.
+    /// `\u00e9`), including surrogate pairs.
+    Json,
+    /// Simple escapes (e.g. `\n` and `\0`), ASCII escapes (e.g.
+    /// `\x7F`), unicode escapes (e.g. `\u{1F600}`) and line
+    /// continuations, which also skip any leading whitespace on the
+    /// following line.
+    Rust,
+    /// Simple escapes (e.g. `\n` and `\a`), octal escapes (e.g.
+    /// `\033`), hex escapes (e.g. `\x1b`), unicode escapes (e.g.
+    /// `\u00e9` and `\U0001F600`) and line continuations.  As in
+    /// Python, unrecognised escapes (e.g. `\q`) are left as is.  Named
+    /// escapes (e.g. `\N{DASH}`) are not supported.
+    Python
+}
+
+/// Identifies an error arising whilst decoding escape sequences.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Error {
+    /// An unknown or malformed escape sequence, such as `\q` or `\x4`.
+    InvalidEscape(Region),
+    /// An escape sequence which does not denote a valid character,
+    /// such as `\u{110000}` (or `\x80` in Rust).
+    InvalidCodePoint(Region),
+    /// A UTF-16 surrogate escape which is not part of a pair, such as
+    /// `\uD83D` on its own (JSON only).
+    UnpairedSurrogate(Region)
+}
+
+impl Error {
+    /// Get the region where this error arose.
+    pub fn region(&self) -> Region {
+        match self {
+            Error::InvalidEscape(r) => *r,
+            Error::InvalidCodePoint(r) => *r,
+            Error::UnpairedSurrogate(r) => *r
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidEscape(_) => write!(f,"invalid escape sequence"),
+            Error::InvalidCodePoint(_) => write!(f,"invalid character in escape sequence"),
+            Error::UnpairedSurrogate(_) => write!(f,"unpaired surrogate")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Decode the escape sequences within the contents of a string
+/// literal (i.e. excluding its quotes) according to a given dialect.
+/// Here, `start` is the offset (in characters) of the contents within
+/// the original source, such that errors are reported relative to it.
+pub fn unescape(text: &str, start: usize, dialect: Dialect) -> Result<String,Error> {
+    let mut decoder = Decoder{chars: text.chars().enumerate().peekable(), start, len: text.chars().count()};
+    let mut s = String::new();
+    while let Some((i,c)) = decoder.chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+        match dialect {
+            Dialect::C => decoder.c(i,&mut s)?,
+            Dialect::Json => decoder.json(i,&mut s)?,
+            Dialect::Rust => decoder.rust(i,&mut s)?,
+            Dialect::Python => decoder.python(i,&mut s)?
+        }
+    }
+    Ok(s)
+}
+
+struct Decoder<'a> {
+    chars: Peekable<Enumerate<Chars<'a>>>,
+    /// Offset of the text within the original source.
+    start: usize,
+    /// Length of the text (in characters).
+    len: usize
+}
+
+impl Decoder<'_> {
+    /// Get the offset of the next character within the text.
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.len,|(i,_)| *i)
+    }
+
+    /// Get the region from a given offset within the text up to the
+    /// next character.
+    fn region(&mut self, from: usize) -> Region {
+        Region::new(self.start + from,self.start + self.offset())
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next().map(|(_,c)| c)
+    }
+
+    /// Consume the next character if it matches.
+    fn eat(&mut self, c: char) -> bool {
+        self.chars.next_if(|(_,d)| *d == c).is_some()
+    }
+
+    /// Read up to `max` digits in a given radix, returning their value
+    /// (if any were read).  Underscores are skipped when `separators`
+    /// holds.
+    fn digits(&mut self, radix: u32, max: usize, separators: bool) -> Option<u32> {
+        let mut value : Option<u32> = None;
+        let mut n = 0;
+        while n < max {
+            match self.chars.peek() {
+                Some((_,'_')) if separators && value.is_some() => {}
+                Some((_,c)) if c.is_digit(radix) => {
+                    let d = c.to_digit(radix).unwrap();
+                    // Saturate, since the result is out of range anyway
+                    value = Some(value.unwrap_or(0).saturating_mul(radix).saturating_add(d));
+                    n += 1;
+                }
+                _ => { break; }
+            }
+            self.chars.next();
+        }
+        value
+    }
+
+    /// Read exactly `n` digits in a given radix.
+    fn exactly(&mut self, radix: u32, n: usize, from: usize) -> Result<u32,Error> {
+        let before = self.offset();
+        let value = self.digits(radix,n,false);
+        match value {
+            Some(v) if self.offset() - before == n => Ok(v),
+            _ => Err(Error::InvalidEscape(self.region(from)))
+        }
+    }
+
+    /// Read an octal escape of up to three digits, given the first.
+    fn octal(&mut self, first: char) -> u32 {
+        let mut value = first.to_digit(8).unwrap();
+        for _ in 0..2 {
+            match self.digits(8,1,false) {
+                Some(d) => { value = value * 8 + d; }
+                None => { break; }
+            }
+        }
+        value
+    }
+
+    /// Convert a code point into a character.
+    fn char(&mut self, value: u32, from: usize) -> Result<char,Error> {
+        char::from_u32(value).ok_or_else(|| Error::InvalidCodePoint(self.region(from)))
+    }
+
+    /// Skip a line break following a backslash (`\r` has already been
+    /// consumed).
+    fn line_break(&mut self, c: char) {
+        if c == '\r' { self.eat('\n'); }
+    }
+
+    fn c(&mut self, from: usize, s: &mut String) -> Result<(),Error> {
+        let c = match self.next() {
+            Some('a') => '\u{07}',
+            Some('b') => '\u{08}',
+            Some('f') => '\u{0C}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('v') => '\u{0B}',
+            Some(c@('\\'|'\''|'"'|'?')) => c,
+            Some(c@'0'..='7') => {
+                let value = self.octal(c);
+                self.char(value,from)?
+            }
+            Some('x') => {
+                let value = self.digits(16,usize::MAX,false).ok_or_else(|| Error::InvalidEscape(self.region(from)))?;
+                self.char(value,from)?
+            }
+            Some('u') => {
+                let value = self.exactly(16,4,from)?;
+                self.char(value,from)?
+            }
+            Some('U') => {
+                let value = self.exactly(16,8,from)?;
+                self.char(value,from)?
+            }
+            Some(c@('\n'|'\r')) => {
+                self.line_break(c);
+                return Ok(());
+            }
+            _ => { return Err(Error::InvalidEscape(self.region(from))); }
+        };
+        s.push(c);
+        Ok(())
+    }
+
+    fn json(&mut self, from: usize, s: &mut String) -> Result<(),Error> {
+        let c = match self.next() {
+            Some('b') => '\u{08}',
+            Some('f') => '\u{0C}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some(c@('"'|'\\'|'/')) => c,
+            Some('u') => {
+                let high = self.exactly(16,4,from)?;
+                match high {
+                    0xD800..=0xDBFF => {
+                        // Must be followed by a low surrogate
+                        let region = self.region(from);
+                        let mark = self.offset();
+                        if !self.eat('\\') || !self.eat('u') {
+                            return Err(Error::UnpairedSurrogate(region));
+                        }
+                        match self.exactly(16,4,mark)? {
+                            low@0xDC00..=0xDFFF => {
+                                let value = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                                self.char(value,from)?
+                            }
+                            _ => { return Err(Error::UnpairedSurrogate(region)); }
+                        }
+                    }
+                    0xDC00..=0xDFFF => { return Err(Error::UnpairedSurrogate(self.region(from))); }
+                    _ => self.char(high,from)?
+                }
+            }
+            _ => { return Err(Error::InvalidEscape(self.region(from))); }
+        };
+        s.push(c);
+        Ok(())
+    }
+
+    fn rust(&mut self, from: usize, s: &mut String) -> Result<(),Error> {
+        let c = match self.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c@('\\'|'\''|'"')) => c,
+            Some('x') => {
+                let value = self.exactly(16,2,from)?;
+                if value > 0x7F { return Err(Error::InvalidCodePoint(self.region(from))); }
+                self.char(value,from)?
+            }
+            Some('u') => {
+                let value = match self.eat('{') { true => self.digits(16,6,true), false => None };
+                match value {
+                    Some(v) if self.eat('}') => self.char(v,from)?,
+                    _ => {
+                        // Include any excess digits in the error
+                        self.digits(16,usize::MAX,true);
+                        self.eat('}');
+                        return Err(Error::InvalidEscape(self.region(from)));
+                    }
+                }
+            }
+            Some(c@('\n'|'\r')) => {
+                self.line_break(c);
+                // Skip leading whitespace on the next line
+                while self.chars.next_if(|(_,c)| matches!(c,' '|'\t'|'\n'|'\r')).is_some() {}
+                return Ok(());
+            }
+            _ => { return Err(Error::InvalidEscape(self.region(from))); }
+        };
+        s.push(c);
+        Ok(())
+    }
+
+    fn python(&mut self, from: usize, s: &mut String) -> Result<(),Error> {
+        let c = match self.next() {
+            Some('a') => '\u{07}',
+            Some('b') => '\u{08}',
+            Some('f') => '\u{0C}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('v') => '\u{0B}',
+            Some(c@('\\'|'\''|'"')) => c,
+            Some(c@'0'..='7') => {
+                let value = self.octal(c);
+                self.char(value,from)?
+            }
+            Some('x') => {
+                let value = self.exactly(16,2,from)?;
+                self.char(value,from)?
+            }
+            Some('u') => {
+                let value = self.exactly(16,4,from)?;
+                self.char(value,from)?
+            }
+            Some('U') => {
+                let value = self.exactly(16,8,from)?;
+                self.char(value,from)?
+            }
+            Some(c@('\n'|'\r')) => {
+                self.line_break(c);
+                return Ok(());
+            }
+            Some('N') | None => { return Err(Error::InvalidEscape(self.region(from))); }
+            Some(c) => {
+                // Unrecognised escapes are retained
+                s.push('\\');
+                c
+            }
+        };
+        s.push(c);
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::Range;
+use crate::{Lexer,Scanner,Token};
+use crate::util::{Region,StrCursor};
+
+/// Describes a change to some source text, where the characters in a
+/// given region of the original text are replaced by some new text.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Edit<'a> {
+    /// The region (in chars) of the original text being replaced.
+    pub region: Region,
+    /// The text replacing that region.
+    pub text: &'a str
+}
+
+impl<'a> Edit<'a> {
+    pub fn new(region: Region, text: &'a str) -> Self {
+        Self{region,text}
+    }
+
+    /// Determine the change in length (in chars) caused by this edit.
+    pub fn delta(&self) -> isize {
+        self.text.chars().count() as isize - self.region.len() as isize
+    }
+}
+
+/// Apply an edit to some source text and update its tokens (which
+/// were previously produced by lexing `source` with `scanner`)
+/// accordingly.  Rather than lexing the entire text again, lexing
+/// restarts just before the edit and continues only until the new
+/// tokens resynchronise with the old ones.  Tokens after that point
+/// are simply shifted into place.  This returns the range of indices
+/// in `tokens` which were re-lexed.
+///
+/// Lexing restarts from the token before the first token affected by
+/// the edit.  This assumes no scanner looks further ahead than the
+/// following token when deciding where a token ends.  An edit whose
+/// region is reversed is normalised, and one extending past the end
+/// of the text is clamped to it.
+pub fn relex<S>(scanner: &S, source: &mut String, tokens: &mut Vec<Token<S::Token>>, edit: Edit) -> Range<usize>
+where S:Scanner<Item=char>, S::Token:Copy+PartialEq {
+    let n = source.chars().count();
+    let Region{start,end} = edit.region;
+    let edit = Edit::new(Region::new(start.min(end).min(n),start.max(end).min(n)),edit.text);
+    let delta = edit.delta();
+    let Region{start,end} = edit.region;
+    // Apply edit to source text
+    let bytes = byte_offset(source,start)..byte_offset(source,end);
+    source.replace_range(bytes,edit.text);
+    // Determine restart point
+    let first = tokens.partition_point(|t| t.end() < start).saturating_sub(1);
+    let restart = tokens.get(first).map_or(0,|t| t.start());
+    // Determine end of edit in new text
+    let edit_end = (end as isize + delta) as usize;
+    // Lex until resynchronised
+    let cursor = StrCursor::new(&source[byte_offset(source,restart)..]);
+    let mut relexed = Vec::new();
+    let mut next = first;
+    let mut resync = tokens.len();
+    for mut t in Lexer::with_cursor(cursor,scanner) {
+        t.shift(restart);
+        if t.start() >= edit_end {
+            // Skip over old tokens which cannot match
+            while next < tokens.len() && (tokens[next].start() as isize + delta) < t.start() as isize {
+                next += 1;
+            }
+            if next < tokens.len() && tokens[next].start() >= end {
+                let mut old = tokens[next];
+                old.shift_by(delta);
+                if old == t {
+                    resync = next;
+                    break;
+                }
+            }
+        }
+        relexed.push(t);
+    }
+    // Shift unaffected tokens
+    let n = relexed.len();
+    let mut tail : Vec<Token<S::Token>> = tokens.drain(resync..).collect();
+    tail.iter_mut().for_each(|t| t.shift_by(delta));
+    tokens.truncate(first);
+    tokens.extend(relexed);
+    tokens.extend(tail);
+    first .. first + n
+}
+
+/// Determine the byte offset of a given char offset in a string.
+fn byte_offset(text: &str, offset: usize) -> usize {
+    text.char_indices().nth(offset).map_or(text.len(),|(i,_)| i)
+}
@@ -0,0 +1,606 @@
+use std::borrow::Cow;
+use std::fmt;
+use crate::{Any,Lexer,Matcher,NoneOf,Scanner,SyntaxError,Token,TokenKind,Unit,Within};
+use crate::util::{Cursor,Region,StrCursor};
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+/// Identifies the kinds of token making up a JSON document.  Some
+/// kinds (e.g. comments) are only permitted in `Mode::Lenient`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    WhiteSpace,
+    /// A comment such as `// hello` (lenient only).
+    LineComment,
+    /// A comment such as `/* hello */` (lenient only).
+    BlockComment,
+    LeftBrace,
+    RightBrace,
+    LeftSquare,
+    RightSquare,
+    Colon,
+    Comma,
+    /// A string literal, such as `"hello"` (or `'hello'` when
+    /// lenient).
+    String,
+    /// A number, such as `-1.5e3` (or `0x1F`, `.5` and `Infinity` when
+    /// lenient).
+    Number,
+    True,
+    False,
+    Null,
+    /// An unquoted object key, such as `name` (lenient only).
+    Identifier,
+    /// A string literal which runs to the end of the line.
+    UnterminatedString,
+    /// A block comment which runs to the end of the input.
+    UnterminatedComment,
+    /// A character which cannot begin any token.
+    Unknown
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::UnterminatedString|Kind::UnterminatedComment|Kind::Unknown)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::LineComment|Kind::BlockComment)
+    }
+}
+
+/// A scanner for JSON documents, which accepts the union of strict
+/// JSON (as in RFC 8259) and lenient JSON5-style documents.  The
+/// `EventParser` is responsible for rejecting lenient forms in strict
+/// mode.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct JsonScanner;
+
+impl Scanner for JsonScanner {
+    type Item = char;
+    type Token = Kind;
+
+    fn scan<C:Cursor<Item=char>>(&self, input: &mut C) -> Option<Kind> {
+        rules().scan(input)
+    }
+}
+
+/// Split a JSON document into tokens, including whitespace and
+/// comments.
+pub fn lex(input: &str) -> Vec<Token<Kind>> {
+    Lexer::with_cursor(StrCursor::new(input),JsonScanner).collect()
+}
+
+fn rules() -> impl Scanner<Item=char,Token=Kind> {
+    let whitespace = Any([' ','\t','\n','\r','\u{0B}','\u{0C}','\u{A0}','\u{FEFF}','\u{2028}','\u{2029}']).one_or_more();
+    // "//" [^\n]*
+    let line_comment = '/'.then('/').then(NoneOf(['\n']).zero_or_more());
+    // "/*" ([^*] | "*"+ [^*/])* "*"+ "/"
+    let body = NoneOf(['*']).or('*'.one_or_more().then(NoneOf(['*','/']))).zero_or_more();
+    let block_comment = '/'.then('*').then(body).then('*'.one_or_more()).then('/');
+    let unterminated_comment = '/'.then('*').then(body).then('*'.zero_or_more());
+    Unit(whitespace,Kind::WhiteSpace)
+        .or(Unit(line_comment,Kind::LineComment))
+        .or(Unit(block_comment,Kind::BlockComment))
+        .or(Unit(unterminated_comment,Kind::UnterminatedComment))
+        .or(Unit('{',Kind::LeftBrace))
+        .or(Unit('}',Kind::RightBrace))
+        .or(Unit('[',Kind::LeftSquare))
+        .or(Unit(']',Kind::RightSquare))
+        .or(Unit(':',Kind::Colon))
+        .or(Unit(',',Kind::Comma))
+        .or(Unit(string('"').or(string('\'')),Kind::String))
+        .or(Unit(unterminated('"').or(unterminated('\'')),Kind::UnterminatedString))
+        .or(Unit(Word("true"),Kind::True))
+        .or(Unit(Word("false"),Kind::False))
+        .or(Unit(Word("null"),Kind::Null))
+        .or(Unit(lenient_number(),Kind::Number))
+        .or(Unit(identifier(),Kind::Identifier))
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+/// Match the body of a string literal delimited by a given quote,
+/// including any escapes (and line continuations).
+fn string_body(quote: char) -> impl Matcher<Item=char> {
+    let escape = '\\'.then('\r').then('\n').or('\\'.then(NoneOf([])));
+    NoneOf([quote,'\\','\n','\r']).or(escape).zero_or_more()
+}
+
+fn string(quote: char) -> impl Matcher<Item=char> {
+    quote.then(string_body(quote)).then(quote)
+}
+
+fn unterminated(quote: char) -> impl Matcher<Item=char> {
+    quote.then(string_body(quote)).then('\\'.optional())
+}
+
+fn digit() -> Within<char> {
+    Within('0'..='9')
+}
+
+fn exponent() -> impl Matcher<Item=char> {
+    Any(['e','E']).then(Any(['+','-']).optional()).then(digit().one_or_more())
+}
+
+/// Match a number as defined by RFC 8259:
+///
+/// ```text
+/// number = [ minus ] int [ frac ] [ exp ]
+/// int = zero / ( digit1-9 *DIGIT )
+/// frac = decimal-point 1*DIGIT
+/// exp = e [ minus / plus ] 1*DIGIT
+/// ```
+pub fn number() -> impl Matcher<Item=char> {
+    let int = '0'.or(Within('1'..='9').then(digit().zero_or_more()));
+    let frac = '.'.then(digit().one_or_more());
+    '-'.optional().then(int).then(frac.optional()).then(exponent().optional())
+}
+
+/// Match a number in the style of JSON5, which additionally permits
+/// leading plus signs, hexadecimal, leading or trailing decimal
+/// points, `Infinity` and `NaN`.
+pub fn lenient_number() -> impl Matcher<Item=char> {
+    let hex = '0'.then(Any(['x','X'])).then(Within('0'..='9').or(Within('a'..='f')).or(Within('A'..='F')).one_or_more());
+    let decimal = digit().one_or_more().then('.'.then(digit().zero_or_more()).optional())
+        .or('.'.then(digit().one_or_more()))
+        .then(exponent().optional());
+    let value = hex.or(Word("Infinity")).or(Word("NaN")).or(decimal);
+    Any(['+','-']).optional().then(value)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+fn identifier() -> impl Matcher<Item=char> {
+    let start = Within('a'..='z').or(Within('A'..='Z')).or(Any(['_','$']));
+    let rest = start.clone().or(digit());
+    start.then(rest.zero_or_more())
+}
+
+/// Matches a given word, provided it is not immediately followed by
+/// an identifier character (e.g. `null` but not `nullable`).
+#[derive(Clone,Copy,Debug)]
+struct Word(&'static str);
+
+impl Matcher for Word {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let mut n = 0;
+        for c in self.0.chars() {
+            match input.next() {
+                Some(d) if c == d => { n += 1; }
+                Some(_) => { input.backup(n+1); return false; }
+                None => { input.backup(n); return false; }
+            }
+        }
+        match input.next() {
+            Some(c) if is_identifier_char(c) => { input.backup(n+1); false }
+            Some(_) => { input.backup(1); true }
+            None => true
+        }
+    }
+}
+
+/// Check whether some text is exactly a number as defined by RFC 8259.
+fn is_strict_number(text: &str) -> bool {
+    let mut cursor = StrCursor::new(text);
+    number().matches(&mut cursor) && cursor.rest().is_empty()
+}
+
+/// Determine the value of a number token, including lenient forms such
+/// as `0x1F` or `-Infinity`.
+pub fn number_value(text: &str) -> Option<f64> {
+    let (negative,digits) = match text.strip_prefix('-') {
+        Some(t) => (true,t),
+        None => (false,text.strip_prefix('+').unwrap_or(text))
+    };
+    let value = match digits {
+        "Infinity" => f64::INFINITY,
+        "NaN" => f64::NAN,
+        _ if digits.starts_with("0x") || digits.starts_with("0X") => {
+            u64::from_str_radix(&digits[2..],16).ok()? as f64
+        }
+        _ => digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Identifies an error arising whilst parsing a JSON document.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Error {
+    /// An unexpected token (or end of file) was encountered.
+    Syntax(SyntaxError<Kind>),
+    /// A character which cannot begin any token was encountered.
+    UnexpectedCharacter(Region),
+    /// A string literal was not closed before the end of its line.
+    UnterminatedString(Region),
+    /// A block comment was not closed before the end of the input.
+    UnterminatedComment(Region),
+    /// An unknown or malformed escape sequence was encountered within
+    /// a string literal (e.g. `\q` or `\u12`).
+    InvalidEscape(Region),
+    /// A `\uXXXX` escape for half of a surrogate pair, which was not
+    /// part of a complete pair.
+    UnpairedSurrogate(Region),
+    /// An unescaped control character was encountered within a string
+    /// literal.
+    ControlCharacter(Region),
+    /// A number not permitted in strict mode (e.g. `01` or `.5`).
+    InvalidNumber(Region),
+    /// Syntax only permitted in lenient mode (e.g. a comment) was
+    /// encountered in strict mode.
+    NotPermitted(Region)
+}
+
+impl Error {
+    /// Get the region where this error arose.
+    pub fn region(&self) -> Region {
+        match self {
+            Error::Syntax(e) => e.region,
+            Error::UnexpectedCharacter(r) => *r,
+            Error::UnterminatedString(r) => *r,
+            Error::UnterminatedComment(r) => *r,
+            Error::InvalidEscape(r) => *r,
+            Error::UnpairedSurrogate(r) => *r,
+            Error::ControlCharacter(r) => *r,
+            Error::InvalidNumber(r) => *r,
+            Error::NotPermitted(r) => *r
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax(e) => write!(f,"{e}"),
+            Error::UnexpectedCharacter(_) => write!(f,"unexpected character"),
+            Error::UnterminatedString(_) => write!(f,"unterminated string"),
+            Error::UnterminatedComment(_) => write!(f,"unterminated comment"),
+            Error::InvalidEscape(_) => write!(f,"invalid escape sequence"),
+            Error::UnpairedSurrogate(_) => write!(f,"unpaired surrogate"),
+            Error::ControlCharacter(_) => write!(f,"control character in string"),
+            Error::InvalidNumber(_) => write!(f,"invalid number"),
+            Error::NotPermitted(_) => write!(f,"not permitted in strict mode")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<SyntaxError<Kind>> for Error {
+    fn from(e: SyntaxError<Kind>) -> Self {
+        Error::Syntax(e)
+    }
+}
+
+// =============================================================================
+// Event Parser
+// =============================================================================
+
+/// Determines which documents are accepted by an `EventParser`.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum Mode {
+    /// Accept only JSON as defined by RFC 8259.
+    #[default]
+    Strict,
+    /// Additionally accept comments, trailing commas, single-quoted
+    /// strings, unquoted keys, JSON5 numbers and JSON5 escapes.
+    Lenient
+}
+
+/// An event produced when parsing a JSON document.  Strings are only
+/// copied when they contain escapes, whilst numbers are left as they
+/// appear in the document (see `number_value()`).
+#[derive(Clone,Debug,PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// The key of the next member of an object.
+    Key(Cow<'a,str>),
+    String(Cow<'a,str>),
+    Number(&'a str),
+    Bool(bool),
+    Null
+}
+
+/// The kinds of token which can begin a value.
+const VALUES : [Kind;7] = [Kind::LeftBrace,Kind::LeftSquare,Kind::String,Kind::Number,Kind::True,Kind::False,Kind::Null];
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Container {
+    Object,
+    Array
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum State {
+    /// Expecting a value (e.g. after a colon).
+    Value,
+    /// Expecting the first value of an array, or its end.
+    FirstValue,
+    /// Expecting a value after a comma in an array.
+    NextValue,
+    /// Expecting the first key of an object, or its end.
+    FirstKey,
+    /// Expecting a key after a comma in an object.
+    NextKey,
+    /// Expecting whatever follows a complete value.
+    After,
+    Done
+}
+
+/// A streaming parser which turns a JSON document into a sequence of
+/// events, each with the region of the document it arose from.  Tokens
+/// are lexed on demand and no tree is constructed, hence memory usage
+/// is proportional only to the nesting depth of the document.  Parsing
+/// stops at the first error.  For example, `{"a": [1]}` produces
+/// `StartObject`, `Key("a")`, `StartArray`, `Number("1")`, `EndArray`
+/// and `EndObject`.
+pub struct EventParser<'a> {
+    input: &'a str,
+    lexer: Lexer<StrCursor<'a>,JsonScanner>,
+    mode: Mode,
+    stack: Vec<Container>,
+    state: State
+}
+
+impl<'a> EventParser<'a> {
+    pub fn new(input: &'a str, mode: Mode) -> Self {
+        let lexer = Lexer::with_cursor(StrCursor::new(input),JsonScanner);
+        Self{input, lexer, mode, stack: Vec::new(), state: State::Value}
+    }
+
+    /// Get the current nesting depth.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn lenient(&self) -> bool {
+        self.mode == Mode::Lenient
+    }
+
+    /// Read the next significant token, along with its text.
+    fn token(&mut self) -> Result<Option<(Token<Kind>,&'a str)>,Error> {
+        loop {
+            let start = self.lexer.get_ref().byte_offset();
+            let Some(t) = self.lexer.next() else { return Ok(None); };
+            let text = &self.input[start..self.lexer.get_ref().byte_offset()];
+            match t.kind {
+                Kind::WhiteSpace if self.lenient() => {}
+                Kind::WhiteSpace => {
+                    // Only four whitespace characters are strict JSON
+                    if let Some(i) = text.chars().position(|c| !matches!(c,' '|'\t'|'\n'|'\r')) {
+                        let start = t.start() + i;
+                        return Err(Error::NotPermitted(Region::new(start,start+1)));
+                    }
+                }
+                Kind::LineComment|Kind::BlockComment if self.lenient() => {}
+                Kind::LineComment|Kind::BlockComment => { return Err(Error::NotPermitted(t.region)); }
+                Kind::UnterminatedString => { return Err(Error::UnterminatedString(t.region)); }
+                Kind::UnterminatedComment => { return Err(Error::UnterminatedComment(t.region)); }
+                Kind::Unknown => { return Err(Error::UnexpectedCharacter(t.region)); }
+                _ => { return Ok(Some((t,text))); }
+            }
+        }
+    }
+
+    /// Construct a syntax error for an unexpected token (or end of
+    /// file).
+    fn unexpected(&self, found: Option<Token<Kind>>, expected: &[Kind]) -> Error {
+        let region = match found {
+            Some(t) => t.region,
+            None => {
+                let end = self.lexer.get_ref().offset();
+                Region::new(end,end)
+            }
+        };
+        Error::Syntax(SyntaxError{found, expected: expected.to_vec(), region})
+    }
+
+    fn step(&mut self) -> Result<Option<(Event<'a>,Region)>,Error> {
+        loop {
+            let token = self.token()?;
+            let kind = token.map(|(t,_)| t.kind);
+            match self.state {
+                State::Value|State::FirstValue|State::NextValue => {
+                    let close = self.state == State::FirstValue || (self.state == State::NextValue && self.lenient());
+                    if close && kind == Some(Kind::RightSquare) {
+                        return Ok(Some(self.close(token.unwrap().0)));
+                    }
+                    return self.value(token,close).map(Some);
+                }
+                State::FirstKey|State::NextKey => {
+                    let close = self.state == State::FirstKey || self.lenient();
+                    if close && kind == Some(Kind::RightBrace) {
+                        return Ok(Some(self.close(token.unwrap().0)));
+                    }
+                    return self.key(token,close).map(Some);
+                }
+                State::After => {
+                    match (self.stack.last(),kind) {
+                        (None,None) => { return Ok(None); }
+                        (Some(Container::Object),Some(Kind::Comma)) => { self.state = State::NextKey; }
+                        (Some(Container::Array),Some(Kind::Comma)) => { self.state = State::NextValue; }
+                        (Some(Container::Object),Some(Kind::RightBrace))
+                            | (Some(Container::Array),Some(Kind::RightSquare)) => {
+                            return Ok(Some(self.close(token.unwrap().0)));
+                        }
+                        (None,_) => { return Err(self.unexpected(token.map(|t| t.0),&[])); }
+                        (Some(Container::Object),_) => {
+                            return Err(self.unexpected(token.map(|t| t.0),&[Kind::Comma,Kind::RightBrace]));
+                        }
+                        (Some(Container::Array),_) => {
+                            return Err(self.unexpected(token.map(|t| t.0),&[Kind::Comma,Kind::RightSquare]));
+                        }
+                    }
+                }
+                State::Done => { return Ok(None); }
+            }
+        }
+    }
+
+    /// Close the innermost object or array.
+    fn close(&mut self, token: Token<Kind>) -> (Event<'a>,Region) {
+        self.state = State::After;
+        match self.stack.pop() {
+            Some(Container::Object) => (Event::EndObject,token.region),
+            _ => (Event::EndArray,token.region)
+        }
+    }
+
+    fn value(&mut self, token: Option<(Token<Kind>,&'a str)>, close: bool) -> Result<(Event<'a>,Region),Error> {
+        let Some((t,text)) = token.filter(|(t,_)| VALUES.contains(&t.kind)) else {
+            let mut expected = VALUES.to_vec();
+            if close { expected.push(Kind::RightSquare); }
+            return Err(self.unexpected(token.map(|t| t.0),&expected));
+        };
+        self.state = State::After;
+        let event = match t.kind {
+            Kind::LeftBrace => {
+                self.stack.push(Container::Object);
+                self.state = State::FirstKey;
+                Event::StartObject
+            }
+            Kind::LeftSquare => {
+                self.stack.push(Container::Array);
+                self.state = State::FirstValue;
+                Event::StartArray
+            }
+            Kind::String => Event::String(decode(text,t.region,self.mode)?),
+            Kind::Number if self.lenient() || is_strict_number(text) => Event::Number(text),
+            Kind::Number => { return Err(Error::InvalidNumber(t.region)); }
+            Kind::True => Event::Bool(true),
+            Kind::False => Event::Bool(false),
+            _ => Event::Null
+        };
+        Ok((event,t.region))
+    }
+
+    fn key(&mut self, token: Option<(Token<Kind>,&'a str)>, close: bool) -> Result<(Event<'a>,Region),Error> {
+        let key = match token {
+            Some((t,text)) if t.kind == Kind::String => (decode(text,t.region,self.mode)?,t.region),
+            Some((t,text)) if t.kind == Kind::Identifier && self.lenient() => (Cow::Borrowed(text),t.region),
+            _ => {
+                let mut expected = vec![Kind::String];
+                if self.lenient() { expected.push(Kind::Identifier); }
+                if close { expected.push(Kind::RightBrace); }
+                return Err(self.unexpected(token.map(|t| t.0),&expected));
+            }
+        };
+        match self.token()? {
+            Some((t,_)) if t.kind == Kind::Colon => {}
+            token => { return Err(self.unexpected(token.map(|t| t.0),&[Kind::Colon])); }
+        }
+        self.state = State::Value;
+        Ok((Event::Key(key.0),key.1))
+    }
+}
+
+impl<'a> Iterator for EventParser<'a> {
+    type Item = Result<(Event<'a>,Region),Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.step();
+        if !matches!(result,Ok(Some(_))) { self.state = State::Done; }
+        result.transpose()
+    }
+}
+
+/// Decode the escape sequences within a string literal (including its
+/// quotes), where `region` is the region of the literal.
+fn decode(text: &str, region: Region, mode: Mode) -> Result<Cow<'_,str>,Error> {
+    let lenient = mode == Mode::Lenient;
+    if text.starts_with('\'') && !lenient {
+        return Err(Error::NotPermitted(region));
+    }
+    let body = &text[1..text.len()-1];
+    // Offset of the first character of the body
+    let base = region.start + 1;
+    if !body.contains(|c:char| c == '\\' || c.is_control()) {
+        return Ok(Cow::Borrowed(body));
+    }
+    let chars : Vec<char> = body.chars().collect();
+    let mut s = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c < ' ' && !lenient {
+            return Err(Error::ControlCharacter(Region::new(base+i,base+i+1)));
+        } else if c != '\\' {
+            s.push(c);
+            i += 1;
+            continue;
+        }
+        // Lexer ensures a backslash is always followed by something
+        let e = chars[i+1];
+        let mut n = 2;
+        match e {
+            '"' | '\\' | '/' => s.push(e),
+            'b' => s.push('\u{08}'),
+            'f' => s.push('\u{0C}'),
+            'n' => s.push('\n'),
+            'r' => s.push('\r'),
+            't' => s.push('\t'),
+            'u' => {
+                let invalid = Error::InvalidEscape(Region::new(base+i,base+(i+6).min(chars.len())));
+                let unpaired = Error::UnpairedSurrogate(Region::new(base+i,base+i+6));
+                let hi = hex(&chars,i+2,4).ok_or(invalid)?;
+                n = 6;
+                let c = match hi {
+                    0xD800..=0xDBFF => {
+                        // High surrogate must be followed by low surrogate
+                        if chars.get(i+6) != Some(&'\\') || chars.get(i+7) != Some(&'u') {
+                            return Err(unpaired);
+                        }
+                        match hex(&chars,i+8,4) {
+                            Some(lo@0xDC00..=0xDFFF) => {
+                                n = 12;
+                                0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+                            }
+                            _ => { return Err(unpaired); }
+                        }
+                    }
+                    0xDC00..=0xDFFF => { return Err(unpaired); }
+                    _ => hi
+                };
+                s.push(char::from_u32(c).unwrap());
+            }
+            // JSON5 escapes
+            '\'' if lenient => s.push('\''),
+            'v' if lenient => s.push('\u{0B}'),
+            '0' if lenient && !chars.get(i+2).is_some_and(|c| c.is_ascii_digit()) => s.push('\0'),
+            'x' if lenient => {
+                let invalid = Error::InvalidEscape(Region::new(base+i,base+(i+4).min(chars.len())));
+                s.push(char::from_u32(hex(&chars,i+2,2).ok_or(invalid)?).unwrap());
+                n = 4;
+            }
+            // Line continuations
+            '\n' | '\u{2028}' | '\u{2029}' if lenient => {}
+            '\r' if lenient => {
+                if chars.get(i+2) == Some(&'\n') { n = 3; }
+            }
+            _ => {
+                return Err(Error::InvalidEscape(Region::new(base+i,base+i+2)));
+            }
+        }
+        i += n;
+    }
+    Ok(Cow::Owned(s))
+}
+
+/// Parse a given number of hexadecimal digits starting at a given
+/// position.
+fn hex(chars: &[char], start: usize, n: usize) -> Option<u32> {
+    let digits = chars.get(start..start+n)?;
+    digits.iter().try_fold(0,|acc,c| Some(acc * 16 + c.to_digit(16)?))
+}
@@ -0,0 +1,562 @@
+use crate::{Any,Matcher,NoneOf,Scanner,Text,Token,TokenKind,Unit,Within};
+use crate::util::{Cursor,StrCursor};
+
+// =============================================================================
+// Standards
+// =============================================================================
+
+/// Identifies a revision of the C or C++ language, which determines
+/// the set of reserved keywords (and whether raw strings are
+/// permitted).
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum Standard {
+    C89,
+    C99,
+    C11,
+    #[default]
+    C17,
+    C23,
+    Cpp98,
+    Cpp11,
+    Cpp14,
+    Cpp17,
+    Cpp20,
+    Cpp23
+}
+
+const C89_KEYWORDS : &[&str] = &[
+    "auto","break","case","char","const","continue","default","do","double","else","enum","extern",
+    "float","for","goto","if","int","long","register","return","short","signed","sizeof","static",
+    "struct","switch","typedef","union","unsigned","void","volatile","while"
+];
+
+const C99_KEYWORDS : &[&str] = &["inline","restrict","_Bool","_Complex","_Imaginary"];
+
+const C11_KEYWORDS : &[&str] = &[
+    "_Alignas","_Alignof","_Atomic","_Generic","_Noreturn","_Static_assert","_Thread_local"
+];
+
+const C23_KEYWORDS : &[&str] = &[
+    "alignas","alignof","bool","constexpr","false","nullptr","static_assert","thread_local","true",
+    "typeof","typeof_unqual","_BitInt","_Decimal32","_Decimal64","_Decimal128"
+];
+
+const CPP98_KEYWORDS : &[&str] = &[
+    "and","and_eq","asm","auto","bitand","bitor","bool","break","case","catch","char","class","compl",
+    "const","const_cast","continue","default","delete","do","double","dynamic_cast","else","enum",
+    "explicit","export","extern","false","float","for","friend","goto","if","inline","int","long",
+    "mutable","namespace","new","not","not_eq","operator","or","or_eq","private","protected","public",
+    "register","reinterpret_cast","return","short","signed","sizeof","static","static_cast","struct",
+    "switch","template","this","throw","true","try","typedef","typeid","typename","union","unsigned",
+    "using","virtual","void","volatile","wchar_t","while","xor","xor_eq"
+];
+
+const CPP11_KEYWORDS : &[&str] = &[
+    "alignas","alignof","char16_t","char32_t","constexpr","decltype","noexcept","nullptr",
+    "static_assert","thread_local"
+];
+
+const CPP20_KEYWORDS : &[&str] = &[
+    "char8_t","concept","consteval","constinit","co_await","co_return","co_yield","requires"
+];
+
+impl Standard {
+    /// Determine whether this is a revision of C++ (rather than C).
+    pub fn is_cpp(self) -> bool {
+        matches!(self,Standard::Cpp98|Standard::Cpp11|Standard::Cpp14|Standard::Cpp17|Standard::Cpp20|Standard::Cpp23)
+    }
+
+    /// Determine whether raw string literals (e.g. `R"(x)"`) are
+    /// permitted, which is the case from C++11 onwards.
+    pub fn has_raw_strings(self) -> bool {
+        self.is_cpp() && self != Standard::Cpp98
+    }
+
+    /// Get the keywords reserved by this standard.
+    pub fn keywords(self) -> impl Iterator<Item=&'static str> {
+        let sets : &[&[&str]] = match self {
+            Standard::C89 => &[C89_KEYWORDS],
+            Standard::C99 => &[C89_KEYWORDS,C99_KEYWORDS],
+            Standard::C11|Standard::C17 => &[C89_KEYWORDS,C99_KEYWORDS,C11_KEYWORDS],
+            Standard::C23 => &[C89_KEYWORDS,C99_KEYWORDS,C11_KEYWORDS,C23_KEYWORDS],
+            Standard::Cpp98 => &[CPP98_KEYWORDS],
+            Standard::Cpp11|Standard::Cpp14|Standard::Cpp17 => &[CPP98_KEYWORDS,CPP11_KEYWORDS],
+            Standard::Cpp20|Standard::Cpp23 => &[CPP98_KEYWORDS,CPP11_KEYWORDS,CPP20_KEYWORDS]
+        };
+        sets.iter().flat_map(|s| s.iter().copied())
+    }
+
+    /// Determine whether a given word is reserved by this standard.
+    pub fn is_keyword(self, word: &str) -> bool {
+        self.keywords().any(|k| k == word)
+    }
+}
+
+// =============================================================================
+// Tokens
+// =============================================================================
+
+/// Identifies the kinds of token making up a C (or C++) source file.
+/// Digraphs (e.g. `<:`) are identified with the punctuator they stand
+/// for (e.g. `LeftSquare`).
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum CToken {
+    /// Spaces, tabs, line breaks and line splices (i.e. a backslash
+    /// followed by a line break).
+    WhiteSpace,
+    /// A comment such as `// hello`.
+    LineComment,
+    /// A comment such as `/* hello */`.
+    BlockComment,
+    /// A block comment which runs to the end of the input.
+    UnterminatedComment,
+    /// A preprocessor directive, such as `#include <stdio.h>`, which
+    /// runs to the end of its (possibly continued) line.
+    Preprocessor,
+    /// An identifier, such as `main` or `_x1`.
+    Identifier,
+    /// An identifier reserved by the selected standard, such as `int`.
+    Keyword,
+    /// An integer literal, such as `42`, `0x1F`, `0b101`, `017`,
+    /// `1'000` or `10ull`.
+    Integer,
+    /// A floating-point literal, such as `1.5`, `.5e-3f`, `1e10` or
+    /// `0x1.8p3`.
+    Float,
+    /// A character literal, such as `'a'`, `'\n'` or `u8'x'`.
+    Char,
+    /// A string literal, such as `"hello"` or `L"wide"`.
+    String,
+    /// A raw string literal, such as `R"x(a)b)x"` (C++11 onwards).
+    RawString,
+    /// A character literal not closed before the end of its line.
+    UnterminatedChar,
+    /// A string literal not closed before the end of its line (or the
+    /// end of the input, for raw strings).
+    UnterminatedString,
+    LeftParen,
+    RightParen,
+    LeftSquare,
+    RightSquare,
+    LeftBrace,
+    RightBrace,
+    /// `.`
+    Dot,
+    /// `...`
+    Ellipsis,
+    /// `->`
+    Arrow,
+    /// `.*` (C++ only)
+    DotStar,
+    /// `->*` (C++ only)
+    ArrowStar,
+    /// `::`
+    ColonColon,
+    PlusPlus,
+    MinusMinus,
+    Ampersand,
+    Star,
+    Plus,
+    Minus,
+    Tilde,
+    Bang,
+    Slash,
+    Percent,
+    ShiftLeft,
+    ShiftRight,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    /// `<=>` (C++20)
+    Spaceship,
+    EqualEqual,
+    BangEqual,
+    Caret,
+    Bar,
+    AmpAmp,
+    BarBar,
+    Question,
+    Colon,
+    Semicolon,
+    Equal,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    PlusEqual,
+    MinusEqual,
+    ShiftLeftEqual,
+    ShiftRightEqual,
+    AmpEqual,
+    CaretEqual,
+    BarEqual,
+    Comma,
+    /// `#` (outside of a preprocessor directive)
+    Hash,
+    /// `##` (outside of a preprocessor directive)
+    HashHash,
+    /// A character which cannot begin any token.
+    Unknown
+}
+
+impl TokenKind for CToken {
+    fn is_error(self) -> bool {
+        matches!(self,CToken::UnterminatedComment|CToken::UnterminatedChar|CToken::UnterminatedString|CToken::Unknown)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,CToken::WhiteSpace|CToken::LineComment|CToken::BlockComment)
+    }
+}
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+/// Splits a C (or C++) source file into tokens.  Some context is
+/// required, since a `#` only begins a preprocessor directive at the
+/// start of a line, and whether an identifier is a keyword depends
+/// upon the selected standard.
+pub struct CLexer<'a> {
+    input: StrCursor<'a>,
+    standard: Standard,
+    /// Indicates whether only whitespace (or comments) have been seen
+    /// on the current line.
+    line_start: bool
+}
+
+impl<'a> CLexer<'a> {
+    pub fn new(input: &'a str, standard: Standard) -> Self {
+        Self{input: StrCursor::new(input), standard, line_start: true}
+    }
+}
+
+impl Iterator for CLexer<'_> {
+    type Item = Token<CToken>;
+
+    fn next(&mut self) -> Option<Token<CToken>> {
+        let start = self.input.offset();
+        let rest = self.input.rest();
+        let byte = self.input.byte_offset();
+        let mut kind = None;
+        if self.line_start {
+            kind = Unit(directive(),CToken::Preprocessor).scan(&mut self.input);
+        }
+        if kind.is_none() && self.standard.has_raw_strings() {
+            kind = raw_rules().scan(&mut self.input);
+        }
+        let mut kind = match kind {
+            Some(k) => k,
+            None => rules(self.standard).scan(&mut self.input)?
+        };
+        let text = &rest[..self.input.byte_offset() - byte];
+        let end = self.input.offset();
+        self.input.reset();
+        // Update context
+        match kind {
+            CToken::WhiteSpace if text.contains('\n') => { self.line_start = true; }
+            CToken::WhiteSpace|CToken::LineComment|CToken::BlockComment => {}
+            CToken::Identifier if self.standard.is_keyword(text) => {
+                kind = CToken::Keyword;
+                self.line_start = false;
+            }
+            _ => { self.line_start = false; }
+        }
+        Some(Token::new(kind,start..end))
+    }
+}
+
+/// Split a C (or C++) source file into tokens, including whitespace
+/// and comments.
+pub fn lex(input: &str, standard: Standard) -> Vec<Token<CToken>> {
+    CLexer::new(input,standard).collect()
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+fn rules(standard: Standard) -> impl Scanner<Item=char,Token=CToken> {
+    let whitespace = Any([' ','\t','\n','\r','\u{0B}','\u{0C}']).or(splice()).one_or_more();
+    Unit(whitespace,CToken::WhiteSpace)
+        .or(Unit(line_comment(),CToken::LineComment))
+        .or(Unit(block_comment(),CToken::BlockComment))
+        .or(Unit('/'.then('*').then(comment_body()).then('*'.zero_or_more()),CToken::UnterminatedComment))
+        .or(Unit(literal('\''),CToken::Char))
+        .or(Unit(literal('"'),CToken::String))
+        .or(Unit(unterminated('\''),CToken::UnterminatedChar))
+        .or(Unit(unterminated('"'),CToken::UnterminatedString))
+        .or(Unit(identifier(),CToken::Identifier))
+        .or(Unit(float(),CToken::Float))
+        .or(Unit(integer(),CToken::Integer))
+        .or(long_punctuators(standard))
+        .or(short_punctuators())
+        .or(Unit(NoneOf([]),CToken::Unknown))
+}
+
+/// Rules for raw strings, which take priority (when enabled) since
+/// they overlap with identifiers (e.g. `R` or `u8R`).
+fn raw_rules() -> impl Scanner<Item=char,Token=CToken> {
+    Unit(prefix().optional().then(RawString),CToken::RawString)
+        .or(Unit(prefix().optional().then('R').then('"').then(NoneOf([]).zero_or_more()),CToken::UnterminatedString))
+}
+
+/// Punctuators of two or more characters, longest first.
+fn long_punctuators(standard: Standard) -> impl Scanner<Item=char,Token=CToken> {
+    Unit(Text("%:%:"),CToken::HashHash)
+        .or(Unit(Text("..."),CToken::Ellipsis))
+        .or(Unit(Text("<<="),CToken::ShiftLeftEqual))
+        .or(Unit(Text(">>="),CToken::ShiftRightEqual))
+        .or(Unit(Text("<=>"),CToken::Spaceship))
+        .or(Unit(Text("->*"),CToken::ArrowStar))
+        .or(Unit(Text("->"),CToken::Arrow))
+        .or(Unit(Text("++"),CToken::PlusPlus))
+        .or(Unit(Text("--"),CToken::MinusMinus))
+        .or(Unit(Text("<<"),CToken::ShiftLeft))
+        .or(Unit(Text(">>"),CToken::ShiftRight))
+        .or(Unit(Text("<="),CToken::LessEqual))
+        .or(Unit(Text(">="),CToken::GreaterEqual))
+        .or(Unit(Text("=="),CToken::EqualEqual))
+        .or(Unit(Text("!="),CToken::BangEqual))
+        .or(Unit(Text("&&"),CToken::AmpAmp))
+        .or(Unit(Text("||"),CToken::BarBar))
+        .or(Unit(Text("::"),CToken::ColonColon))
+        .or(Unit(Text(".*"),CToken::DotStar))
+        .or(Unit(Text("*="),CToken::StarEqual))
+        .or(Unit(Text("/="),CToken::SlashEqual))
+        .or(Unit(Text("%="),CToken::PercentEqual))
+        .or(Unit(Text("+="),CToken::PlusEqual))
+        .or(Unit(Text("-="),CToken::MinusEqual))
+        .or(Unit(Text("&="),CToken::AmpEqual))
+        .or(Unit(Text("^="),CToken::CaretEqual))
+        .or(Unit(Text("|="),CToken::BarEqual))
+        .or(Unit(Text("##"),CToken::HashHash))
+        // Digraphs
+        .or(Unit(Text("<:").not_followed_by(SplitDigraph(standard)),CToken::LeftSquare))
+        .or(Unit(Text(":>"),CToken::RightSquare))
+        .or(Unit(Text("<%"),CToken::LeftBrace))
+        .or(Unit(Text("%>"),CToken::RightBrace))
+        .or(Unit(Text("%:"),CToken::Hash))
+}
+
+fn short_punctuators() -> impl Scanner<Item=char,Token=CToken> {
+    Unit('(',CToken::LeftParen)
+        .or(Unit(')',CToken::RightParen))
+        .or(Unit('[',CToken::LeftSquare))
+        .or(Unit(']',CToken::RightSquare))
+        .or(Unit('{',CToken::LeftBrace))
+        .or(Unit('}',CToken::RightBrace))
+        .or(Unit('.',CToken::Dot))
+        .or(Unit('&',CToken::Ampersand))
+        .or(Unit('*',CToken::Star))
+        .or(Unit('+',CToken::Plus))
+        .or(Unit('-',CToken::Minus))
+        .or(Unit('~',CToken::Tilde))
+        .or(Unit('!',CToken::Bang))
+        .or(Unit('/',CToken::Slash))
+        .or(Unit('%',CToken::Percent))
+        .or(Unit('<',CToken::Less))
+        .or(Unit('>',CToken::Greater))
+        .or(Unit('^',CToken::Caret))
+        .or(Unit('|',CToken::Bar))
+        .or(Unit('?',CToken::Question))
+        .or(Unit(':',CToken::Colon))
+        .or(Unit(';',CToken::Semicolon))
+        .or(Unit('=',CToken::Equal))
+        .or(Unit(',',CToken::Comma))
+        .or(Unit('#',CToken::Hash))
+}
+
+/// Matches the remainder of `<::` where, since C++11, it is lexed as
+/// `<` followed by `::` rather than beginning with the digraph `<:`
+/// (e.g. `std::vector<::std::string>`).  The exceptions are `<:::`
+/// and `<::>`, where the digraph is kept.
+#[derive(Clone,Copy,Debug)]
+struct SplitDigraph(Standard);
+
+impl Matcher for SplitDigraph {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let standard = self.0;
+        standard.is_cpp() && standard != Standard::Cpp98 && ':'.then(NoneOf([':','>'])).matches(input)
+    }
+}
+
+/// Match a backslash followed by a line break.
+fn splice() -> impl Matcher<Item=char> {
+    '\\'.then('\r'.optional()).then('\n')
+}
+
+fn comment_body() -> impl Matcher<Item=char> {
+    NoneOf(['*']).or('*'.one_or_more().then(NoneOf(['*','/']))).zero_or_more()
+}
+
+fn block_comment() -> impl Matcher<Item=char> {
+    '/'.then('*').then(comment_body()).then('*'.one_or_more()).then('/')
+}
+
+fn line_comment() -> impl Matcher<Item=char> {
+    '/'.then('/').then(NoneOf(['\n','\r','\\']).or(splice()).or('\\').zero_or_more())
+}
+
+/// Match a preprocessor directive, assuming only whitespace precedes
+/// it on the current line.  Block comments within the directive may
+/// span multiple lines, whilst a line comment ends it.  Literals are
+/// matched first, so that (e.g.) `"/*"` does not begin a comment.
+fn directive() -> impl Matcher<Item=char> {
+    let body = literal('"').or(literal('\''))
+        .or(line_comment())
+        .or(block_comment())
+        .or(splice())
+        .or(NoneOf(['\n','\r','\\']))
+        .or('\\')
+        .zero_or_more();
+    '#'.or(Text("%:")).then(body)
+}
+
+// Identifiers
+
+fn identifier_start() -> impl Matcher<Item=char> {
+    Within('a'..='z').or(Within('A'..='Z')).or(Any(['_','$'])).or(Within('\u{80}'..=char::MAX))
+}
+
+fn identifier_char() -> impl Matcher<Item=char> {
+    identifier_start().or(digit())
+}
+
+fn identifier() -> impl Matcher<Item=char> {
+    identifier_start().then(identifier_char().zero_or_more())
+}
+
+// Character and string literals
+
+/// Match an encoding prefix, such as `u8` or `L`.
+fn prefix() -> impl Matcher<Item=char> {
+    Text("u8").or(Any(['u','U','L']))
+}
+
+fn escape() -> impl Matcher<Item=char> {
+    '\\'.then('\r'.then('\n').or(NoneOf([])))
+}
+
+/// Match a (possibly prefixed) character or string literal delimited
+/// by a given quote.
+fn literal(quote: char) -> impl Matcher<Item=char> {
+    let body = NoneOf([quote,'\\','\n','\r']).or(escape()).zero_or_more();
+    prefix().optional().then(quote).then(body).then(quote)
+}
+
+/// Match a character or string literal missing its closing quote.
+fn unterminated(quote: char) -> impl Matcher<Item=char> {
+    let body = NoneOf([quote,'\\','\n','\r']).or(escape()).zero_or_more();
+    prefix().optional().then(quote).then(body).then('\\'.optional())
+}
+
+/// Matches a raw string literal, such as `R"delim(...)delim"`, where
+/// the (optional) delimiter at the end must match that at the start.
+#[derive(Clone,Copy,Debug)]
+struct RawString;
+
+impl Matcher for RawString {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let start = input.offset();
+        if !'R'.then('"').matches(input) { return false; }
+        // Read the delimiter
+        let mut delimiter = Vec::new();
+        loop {
+            match input.next() {
+                Some('(') => { break; }
+                Some(c) if delimiter.len() < 16 && !matches!(c,' '|')'|'\\'|'\t'|'\n'|'\r'|'\u{0B}'|'\u{0C}'|'"') => {
+                    delimiter.push(c);
+                }
+                _ => {
+                    input.backup(input.offset() - start);
+                    return false;
+                }
+            }
+        }
+        // Search for the closing delimiter
+        loop {
+            match input.next() {
+                Some(')') => {
+                    let mark = input.offset();
+                    let closed = delimiter.iter().all(|d| input.next() == Some(*d)) && input.next() == Some('"');
+                    if closed { return true; }
+                    input.backup(input.offset() - mark);
+                }
+                Some(_) => {}
+                None => {
+                    input.backup(input.offset() - start);
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+// Numbers
+
+fn digit() -> Within<char> {
+    Within('0'..='9')
+}
+
+fn hex_digit() -> impl Matcher<Item=char>+Clone {
+    digit().or(Within('a'..='f')).or(Within('A'..='F'))
+}
+
+/// Match one or more items, where adjacent items may be separated by
+/// a single quote (e.g. `1'000`).
+fn separated<M:Matcher<Item=char>+Clone>(m: M) -> impl Matcher<Item=char> {
+    m.clone().then('\''.optional().then(m).zero_or_more())
+}
+
+fn sign() -> impl Matcher<Item=char> {
+    Any(['+','-']).optional()
+}
+
+/// Match a user-defined literal suffix (C++11), such as `_km`.
+fn user_suffix() -> impl Matcher<Item=char> {
+    '_'.then(identifier_char().zero_or_more())
+}
+
+fn integer() -> impl Matcher<Item=char> {
+    let hex = '0'.then(Any(['x','X'])).then(separated(hex_digit()));
+    let bin = '0'.then(Any(['b','B'])).then(separated(Any(['0','1'])));
+    // Octal integers are decimal integers starting with '0'
+    let dec = separated(digit());
+    hex.or(bin).or(dec).then(integer_suffix().optional())
+}
+
+/// Match an integer suffix, such as `u`, `LL`, `ull` or `uz`.
+fn integer_suffix() -> impl Matcher<Item=char> {
+    let unsigned = Any(['u','U']);
+    unsigned.then(integer_size().optional()).or(integer_size().then(unsigned.optional())).or(user_suffix())
+}
+
+fn integer_size() -> impl Matcher<Item=char> {
+    Text("ll").or(Text("LL")).or(Text("wb")).or(Text("WB")).or(Any(['l','L','z','Z']))
+}
+
+fn float() -> impl Matcher<Item=char> {
+    let exponent = || Any(['e','E']).then(sign()).then(separated(digit()));
+    let dec = separated(digit()).then('.').then(separated(digit()).optional()).then(exponent().optional())
+        .or('.'.then(separated(digit())).then(exponent().optional()))
+        .or(separated(digit()).then(exponent()));
+    // Hexadecimal floats require a binary exponent
+    let mantissa = separated(hex_digit()).then('.'.then(separated(hex_digit()).optional()).optional())
+        .or('.'.then(separated(hex_digit())));
+    let hex = '0'.then(Any(['x','X'])).then(mantissa).then(Any(['p','P'])).then(sign()).then(separated(digit()));
+    hex.or(dec).then(float_suffix().optional())
+}
+
+/// Match a floating-point suffix, such as `f`, `L`, `f32` or `dd`.
+fn float_suffix() -> impl Matcher<Item=char> {
+    Text("f128").or(Text("F128")).or(Text("f16")).or(Text("F16")).or(Text("f32")).or(Text("F32"))
+        .or(Text("f64")).or(Text("F64")).or(Text("bf16")).or(Text("BF16"))
+        .or(Text("df")).or(Text("dd")).or(Text("dl")).or(Text("DF")).or(Text("DD")).or(Text("DL"))
+        .or(Any(['f','F','l','L']))
+        .or(user_suffix())
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::io::{BufReader,Read};
+use crate::{Lexer,Matcher,NoneOf,ReadLexer,Scanner,Token,TokenKind,Unit};
+use crate::util::{Cursor,ReadError,Region,StrCursor};
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+/// Identifies the kinds of token making up a CSV (or TSV) file.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    /// An unquoted field, such as `hello world`.
+    Field,
+    /// A quoted field, such as `"a, ""b"" and c"`, which may contain
+    /// delimiters, quotes and line breaks.
+    QuotedField,
+    /// The delimiter separating fields, such as `,`.
+    Delimiter,
+    /// A line break separating records (`\n`, `\r\n` or `\r`).
+    RecordSeparator,
+    /// A quoted field which runs to the end of the input.
+    UnterminatedQuote
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::UnterminatedQuote)
+    }
+
+    fn is_trivia(self) -> bool {
+        false
+    }
+}
+
+/// Determines the characters used to delimit and quote fields.  Within
+/// a quoted field, a quote can always be written by doubling it (as in
+/// RFC 4180) or, if an escape character is given, by escaping it (e.g.
+/// `\"`).
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Dialect {
+    pub delimiter: char,
+    pub quote: char,
+    pub escape: Option<char>
+}
+
+impl Dialect {
+    /// Comma separated values, as in RFC 4180.
+    pub fn csv() -> Self {
+        Self{delimiter: ',', quote: '"', escape: None}
+    }
+
+    /// Tab separated values.
+    pub fn tsv() -> Self {
+        Self{delimiter: '\t', ..Self::csv()}
+    }
+
+    pub fn delimiter(self, delimiter: char) -> Self {
+        Self{delimiter, ..self}
+    }
+
+    pub fn quote(self, quote: char) -> Self {
+        Self{quote, ..self}
+    }
+
+    pub fn escape(self, escape: char) -> Self {
+        Self{escape: Some(escape), ..self}
+    }
+}
+
+impl Default for Dialect {
+    fn default() -> Self { Self::csv() }
+}
+
+/// A scanner for CSV files in a given dialect.  Since no context is
+/// required, this can be used with any lexer (e.g. a `ReadLexer` for
+/// streaming).  Note that, unlike RFC 4180, quotes are permitted
+/// within unquoted fields (e.g. `a"b`).
+#[derive(Clone,Copy,Debug,Default)]
+pub struct CsvScanner(pub Dialect);
+
+impl Scanner for CsvScanner {
+    type Item = char;
+    type Token = Kind;
+
+    fn scan<C:Cursor<Item=char>>(&self, input: &mut C) -> Option<Kind> {
+        let Dialect{delimiter,quote,escape} = self.0;
+        let field = NoneOf([delimiter,quote,'\n','\r']).then(NoneOf([delimiter,'\n','\r']).zero_or_more());
+        Unit(field,Kind::Field)
+            .or(Unit(Quoted{quote,escape},Kind::QuotedField))
+            .or(Unit(quote.then(NoneOf([]).zero_or_more()),Kind::UnterminatedQuote))
+            .or(Unit(delimiter,Kind::Delimiter))
+            .or(Unit('\r'.optional().then('\n').or('\r'),Kind::RecordSeparator))
+            .scan(input)
+    }
+}
+
+/// Split a CSV file into tokens.
+pub fn lex(input: &str, dialect: Dialect) -> Vec<Token<Kind>> {
+    Lexer::with_cursor(StrCursor::new(input),CsvScanner(dialect)).collect()
+}
+
+/// Split a CSV file into tokens as it is read from a given stream.
+/// Only the current token is held in memory, meaning arbitrarily large
+/// files can be lexed.
+pub fn stream<R:Read>(reader: R, dialect: Dialect) -> ReadLexer<BufReader<R>,CsvScanner> {
+    ReadLexer::new(reader,CsvScanner(dialect))
+}
+
+/// Matches a quoted field, such as `"a ""b"" c"`.
+#[derive(Clone,Copy,Debug)]
+struct Quoted {
+    quote: char,
+    escape: Option<char>
+}
+
+impl Matcher for Quoted {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let start = input.offset();
+        if !self.quote.matches(input) { return false; }
+        loop {
+            match input.next() {
+                Some(c) if Some(c) == self.escape && c != self.quote => {
+                    // Skip the escaped character
+                    if input.next().is_none() { break; }
+                }
+                Some(c) if c == self.quote => {
+                    // Either a doubled quote, or the end
+                    if !self.quote.matches(input) { return true; }
+                }
+                Some(_) => {}
+                None => { break; }
+            }
+        }
+        input.backup(input.offset() - start);
+        false
+    }
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Identifies an error arising whilst reading CSV records.
+#[derive(Debug)]
+pub enum Error {
+    /// A quoted field was not closed before the end of the input.
+    UnterminatedQuote(Region),
+    /// Text following the closing quote of a field, such as `"a"b`.
+    TrailingText(Region),
+    /// The underlying stream could not be read.
+    Read(ReadError)
+}
+
+impl Error {
+    /// Get the region where this error arose (if known).
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            Error::UnterminatedQuote(r) => Some(*r),
+            Error::TrailingText(r) => Some(*r),
+            Error::Read(_) => None
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnterminatedQuote(_) => write!(f,"unterminated quoted field"),
+            Error::TrailingText(_) => write!(f,"unexpected text after quoted field"),
+            Error::Read(e) => write!(f,"{e}")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ReadError> for Error {
+    fn from(e: ReadError) -> Self {
+        Error::Read(e)
+    }
+}
+
+// =============================================================================
+// Records
+// =============================================================================
+
+/// Reads records from a stream, where each record is the list of its
+/// (unquoted) fields.  Blank lines are skipped.  Iteration stops after
+/// the first error.
+pub struct Reader<R:Read> {
+    lexer: ReadLexer<BufReader<R>,CsvScanner>,
+    quote: char,
+    escape: Option<char>,
+    done: bool
+}
+
+impl<R:Read> Reader<R> {
+    pub fn new(reader: R, dialect: Dialect) -> Self {
+        let Dialect{quote,escape,..} = dialect;
+        Self{lexer: stream(reader,dialect), quote, escape, done: false}
+    }
+
+    fn record(&mut self) -> Result<Option<Vec<String>>,Error> {
+        let mut fields = Vec::new();
+        // The current field, and whether it was quoted
+        let mut field : Option<(String,bool)> = None;
+        loop {
+            let (t,text) = match self.lexer.next() {
+                Some(r) => r?,
+                None if fields.is_empty() && field.is_none() => { return Ok(None); }
+                None => { break; }
+            };
+            match t.kind {
+                Kind::Field if matches!(field,Some((_,true))) => {
+                    return Err(Error::TrailingText(t.region));
+                }
+                Kind::Field => { field = Some((text,false)); }
+                Kind::QuotedField => { field = Some((self.unquote(&text),true)); }
+                Kind::Delimiter => {
+                    fields.push(field.take().map(|(s,_)| s).unwrap_or_default());
+                    // Ensure a trailing delimiter yields an empty field
+                    field = Some((String::new(),false));
+                }
+                Kind::RecordSeparator if fields.is_empty() && field.is_none() => {}
+                Kind::RecordSeparator => { break; }
+                Kind::UnterminatedQuote => {
+                    return Err(Error::UnterminatedQuote(t.region));
+                }
+            }
+        }
+        fields.extend(field.map(|(s,_)| s));
+        Ok(Some(fields))
+    }
+
+    /// Strip the quotes from a quoted field, and decode any doubled
+    /// or escaped characters.
+    fn unquote(&self, text: &str) -> String {
+        let mut s = String::new();
+        let mut chars = text.chars().skip(1);
+        while let Some(c) = chars.next() {
+            if c == self.quote || Some(c) == self.escape {
+                // The lexer ensures the last quote is never escaped
+                match chars.next() {
+                    Some(d) => s.push(d),
+                    None => break
+                }
+            } else {
+                s.push(c);
+            }
+        }
+        s
+    }
+}
+
+impl<R:Read> Iterator for Reader<R> {
+    type Item = Result<Vec<String>,Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done { return None; }
+        let r = self.record().transpose();
+        self.done = !matches!(r,Some(Ok(_)));
+        r
+    }
+}
+
+/// Read all records from a CSV file held in memory.
+pub fn records(input: &str, dialect: Dialect) -> Result<Vec<Vec<String>>,Error> {
+    Reader::new(input.as_bytes(),dialect).collect()
+}
@@ -0,0 +1,121 @@
+use crate::{Any,Matcher,NoneOf,Scanner,Token,TokenKind,Unit};
+use crate::util::{Cursor,StrCursor};
+
+/// Identifies the kinds of token making up an INI file.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    /// Spaces and tabs.
+    WhiteSpace,
+    /// A line break (`\n` or `\r\n`).
+    Newline,
+    /// A comment occupying the remainder of a line, such as `; hello`
+    /// or `# hello`.
+    Comment,
+    /// A section header, such as `[server]`.
+    Section,
+    /// A key, such as `name` or `file path`.  Keys may contain spaces,
+    /// but not leading or trailing ones.
+    Key,
+    /// Either `=` or `:` separating a key from its value.
+    Equals,
+    /// The value of a key, excluding leading and trailing whitespace.
+    /// A backslash at the end of a line continues the value onto the
+    /// next line.
+    Value,
+    /// A character which cannot begin any token.
+    Unknown
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::Unknown)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::Comment)
+    }
+}
+
+/// Splits an INI file into tokens.  As with TOML, some context is
+/// required since (for example) `;` begins a comment at the start of a
+/// line, but not within a value.  Therefore, this tracks whether a key
+/// or value is expected next.
+pub struct IniLexer<'a> {
+    input: StrCursor<'a>,
+    /// Indicates whether a key (rather than a value) is expected next.
+    key: bool
+}
+
+impl<'a> IniLexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self{input: StrCursor::new(input), key: true}
+    }
+}
+
+impl Iterator for IniLexer<'_> {
+    type Item = Token<Kind>;
+
+    fn next(&mut self) -> Option<Token<Kind>> {
+        let start = self.input.offset();
+        let kind = match self.key {
+            true => key_rules().scan(&mut self.input)?,
+            false => value_rules().scan(&mut self.input)?
+        };
+        let end = self.input.offset();
+        self.input.reset();
+        match kind {
+            Kind::Newline => { self.key = true; }
+            Kind::Equals => { self.key = false; }
+            _ => {}
+        }
+        Some(Token::new(kind,start..end))
+    }
+}
+
+/// Split an INI file into tokens.
+pub fn lex(input: &str) -> Vec<Token<Kind>> {
+    IniLexer::new(input).collect()
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+fn whitespace() -> impl Matcher<Item=char> {
+    Any([' ','\t']).one_or_more()
+}
+
+fn newline() -> impl Matcher<Item=char> {
+    '\r'.optional().then('\n')
+}
+
+fn key_rules() -> impl Scanner<Item=char,Token=Kind> {
+    let comment = Any([';','#']).then(NoneOf(['\n','\r']).zero_or_more());
+    let section = '['.then(NoneOf([']','\n','\r']).zero_or_more()).then(']');
+    // Words separated by spaces, excluding any trailing spaces
+    let word = NoneOf([' ','\t','\n','\r','=',':']).one_or_more();
+    let first = NoneOf([' ','\t','\n','\r','=',':','[',';','#']);
+    let key = first.then(word.optional()).then(whitespace().then(word).zero_or_more());
+    Unit(whitespace(),Kind::WhiteSpace)
+        .or(Unit(newline(),Kind::Newline))
+        .or(Unit(comment,Kind::Comment))
+        .or(Unit(section,Kind::Section))
+        .or(Unit(Any(['=',':']),Kind::Equals))
+        .or(Unit(key,Kind::Key))
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+fn value_rules() -> impl Scanner<Item=char,Token=Kind> {
+    let value = segment().then(whitespace().then(segment()).zero_or_more());
+    Unit(whitespace(),Kind::WhiteSpace)
+        .or(Unit(newline(),Kind::Newline))
+        .or(Unit(value,Kind::Value))
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+/// Match a run of value characters without whitespace, where a
+/// backslash followed by a line break continues the value.
+fn segment() -> impl Matcher<Item=char> {
+    let continuation = '\\'.then(newline());
+    NoneOf([' ','\t','\n','\r','\\']).or(continuation).or('\\').one_or_more()
+}
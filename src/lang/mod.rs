@@ -0,0 +1,5 @@
+pub mod c;
+pub mod csv;
+pub mod ini;
+pub mod rust;
+pub mod toml;
@@ -0,0 +1,448 @@
+use crate::{Any,Matcher,NoneOf,Scanner,Text,Token,TokenKind,Unit,Within};
+use crate::util::{Cursor,StrCursor};
+
+/// Identifies the kinds of token making up a Rust source file.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    WhiteSpace,
+    /// A comment such as `// hello`.
+    LineComment,
+    /// A (possibly nested) comment such as `/* a /* b */ */`.
+    BlockComment,
+    /// An outer doc comment, such as `/// hello` or `/** hello */`.
+    DocComment,
+    /// An inner doc comment, such as `//! hello` or `/*! hello */`.
+    InnerDocComment,
+    /// A block comment which runs to the end of the input.
+    UnterminatedComment,
+    /// A line such as `#!/usr/bin/env rustx` at the very start of a
+    /// file.
+    Shebang,
+    Identifier,
+    /// An identifier such as `r#match`.
+    RawIdentifier,
+    /// A reserved word, such as `fn` or `match`.
+    Keyword,
+    /// A lifetime or label, such as `'a` or `'static`.
+    Lifetime,
+    /// A character literal, such as `'a'` or `'\u{1F600}'`.
+    Char,
+    /// A byte literal, such as `b'a'`.
+    Byte,
+    /// A string literal, such as `"hello"`.
+    String,
+    /// A byte string literal, such as `b"hello"`.
+    ByteString,
+    /// A C string literal, such as `c"hello"`.
+    CString,
+    /// A raw string literal, such as `r#"say "hi""#`.
+    RawString,
+    /// A raw byte string literal, such as `br"\d+"`.
+    RawByteString,
+    /// A raw C string literal, such as `cr"hello"`.
+    RawCString,
+    /// A character or byte literal not closed on the same line.
+    UnterminatedChar,
+    /// A string literal which runs to the end of the input.
+    UnterminatedString,
+    /// An integer literal, such as `1_000`, `0xFF`, `0b1010` or `7u8`.
+    /// As with `rustc`, this includes integers with a float suffix
+    /// (e.g. `1f32`).
+    Integer,
+    /// A floating-point literal, such as `1.5`, `1e-3`, `2.` or
+    /// `1.0f32`.
+    Float,
+    LeftParen,
+    RightParen,
+    LeftSquare,
+    RightSquare,
+    LeftBrace,
+    RightBrace,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    Bang,
+    Ampersand,
+    Bar,
+    AmpAmp,
+    BarBar,
+    ShiftLeft,
+    ShiftRight,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    CaretEqual,
+    AmpEqual,
+    BarEqual,
+    ShiftLeftEqual,
+    ShiftRightEqual,
+    Equal,
+    EqualEqual,
+    BangEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    At,
+    /// `_`
+    Underscore,
+    Dot,
+    DotDot,
+    DotDotDot,
+    DotDotEqual,
+    Comma,
+    Semicolon,
+    Colon,
+    ColonColon,
+    /// `->`
+    Arrow,
+    /// `=>`
+    FatArrow,
+    /// `<-`
+    LeftArrow,
+    Hash,
+    Dollar,
+    Question,
+    Tilde,
+    /// A character which cannot begin any token.
+    Unknown
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::UnterminatedComment|Kind::UnterminatedChar|Kind::UnterminatedString|Kind::Unknown)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::LineComment|Kind::BlockComment|Kind::DocComment|Kind::InnerDocComment)
+    }
+}
+
+/// The strict and reserved keywords of Rust (2024 edition).  Weak
+/// keywords (e.g. `union`) are treated as identifiers.
+const KEYWORDS : &[&str] = &[
+    "as","async","await","break","const","continue","crate","dyn","else","enum","extern","false","fn",
+    "for","if","impl","in","let","loop","match","mod","move","mut","pub","ref","return","self","Self",
+    "static","struct","super","trait","true","type","unsafe","use","where","while",
+    // Reserved for future use
+    "abstract","become","box","do","final","gen","macro","override","priv","try","typeof","unsized",
+    "virtual","yield"
+];
+
+/// Determine whether a given word is a Rust keyword.
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(&word)
+}
+
+/// Splits a Rust source file into tokens.  Whilst most tokens can be
+/// identified by a scanner alone, distinguishing keywords, doc
+/// comments and shebang lines requires the text of the token (or its
+/// position).
+pub struct RustLexer<'a> {
+    input: StrCursor<'a>
+}
+
+impl<'a> RustLexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self{input: StrCursor::new(input)}
+    }
+}
+
+impl Iterator for RustLexer<'_> {
+    type Item = Token<Kind>;
+
+    fn next(&mut self) -> Option<Token<Kind>> {
+        let start = self.input.offset();
+        let rest = self.input.rest();
+        let byte = self.input.byte_offset();
+        let kind = match start == 0 && is_shebang(rest) {
+            true => Unit(NoneOf(['\n']).zero_or_more(),Kind::Shebang).scan(&mut self.input)?,
+            false => rules().scan(&mut self.input)?
+        };
+        let text = &rest[..self.input.byte_offset() - byte];
+        let end = self.input.offset();
+        self.input.reset();
+        let kind = match kind {
+            Kind::Identifier if text == "_" => Kind::Underscore,
+            Kind::Identifier if is_keyword(text) => Kind::Keyword,
+            Kind::LineComment|Kind::BlockComment => comment(text),
+            _ => kind
+        };
+        Some(Token::new(kind,start..end))
+    }
+}
+
+/// Split a Rust source file into tokens, including whitespace and
+/// comments.
+pub fn lex(input: &str) -> Vec<Token<Kind>> {
+    RustLexer::new(input).collect()
+}
+
+/// Determine whether some input begins with a shebang line.  This
+/// excludes inner attributes, such as `#![allow(unused)]`.
+fn is_shebang(input: &str) -> bool {
+    match input.strip_prefix("#!") {
+        Some(rest) => !rest.trim_start().starts_with('['),
+        None => false
+    }
+}
+
+/// Distinguish doc comments from ordinary comments.  Note that
+/// comments such as `////` and `/***/` are not doc comments.
+fn comment(text: &str) -> Kind {
+    if text.starts_with("//!") || text.starts_with("/*!") {
+        Kind::InnerDocComment
+    } else if (text.starts_with("///") && !text.starts_with("////"))
+        || (text.starts_with("/**") && !text.starts_with("/***") && text != "/**/") {
+        Kind::DocComment
+    } else if text.starts_with("//") {
+        Kind::LineComment
+    } else {
+        Kind::BlockComment
+    }
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+fn rules() -> impl Scanner<Item=char,Token=Kind> {
+    let whitespace = Any([' ','\t','\n','\r','\u{0B}','\u{0C}','\u{85}','\u{200E}','\u{200F}','\u{2028}','\u{2029}']).one_or_more();
+    Unit(whitespace,Kind::WhiteSpace)
+        .or(Unit('/'.then('/').then(NoneOf(['\n']).zero_or_more()),Kind::LineComment))
+        .or(Unit(NestedComment,Kind::BlockComment))
+        .or(Unit('/'.then('*').then(NoneOf([]).zero_or_more()),Kind::UnterminatedComment))
+        .or(literals())
+        .or(Unit('r'.then('#').then(identifier()),Kind::RawIdentifier))
+        .or(Unit(identifier(),Kind::Identifier))
+        .or(Unit(float(),Kind::Float))
+        .or(Unit(integer(),Kind::Integer))
+        .or(long_punctuators())
+        .or(short_punctuators())
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+/// Rules for character and string literals (and lifetimes), which
+/// must come before identifiers since their prefixes overlap (e.g.
+/// `b'x'` or `r"x"`).
+fn literals() -> impl Scanner<Item=char,Token=Kind> {
+    let lifetime = '\''.then(Text("r#").optional()).then(identifier()).not_followed_by('\'');
+    let unterminated_char = 'b'.optional().then('\'').then(escape().or(NoneOf(['\'','\n','\r'])).optional());
+    let unterminated_string = Any(['b','c']).optional().then('"').then(NoneOf([]).zero_or_more());
+    let unterminated_raw = Text("br").or(Text("cr")).or(Text("r")).then('#'.zero_or_more()).then('"').then(NoneOf([]).zero_or_more());
+    Unit(char(),Kind::Char)
+        .or(Unit('b'.then(char()),Kind::Byte))
+        .or(Unit(lifetime,Kind::Lifetime))
+        .or(Unit(string(),Kind::String))
+        .or(Unit('b'.then(string()),Kind::ByteString))
+        .or(Unit('c'.then(string()),Kind::CString))
+        .or(Unit('r'.then(RawString),Kind::RawString))
+        .or(Unit('b'.then('r').then(RawString),Kind::RawByteString))
+        .or(Unit('c'.then('r').then(RawString),Kind::RawCString))
+        .or(Unit(unterminated_char,Kind::UnterminatedChar))
+        .or(Unit(unterminated_string,Kind::UnterminatedString))
+        .or(Unit(unterminated_raw,Kind::UnterminatedString))
+}
+
+/// Punctuators of two or more characters, longest first.
+fn long_punctuators() -> impl Scanner<Item=char,Token=Kind> {
+    Unit(Text("<<="),Kind::ShiftLeftEqual)
+        .or(Unit(Text(">>="),Kind::ShiftRightEqual))
+        .or(Unit(Text("..."),Kind::DotDotDot))
+        .or(Unit(Text("..="),Kind::DotDotEqual))
+        .or(Unit(Text(".."),Kind::DotDot))
+        .or(Unit(Text("::"),Kind::ColonColon))
+        .or(Unit(Text("->"),Kind::Arrow))
+        .or(Unit(Text("=>"),Kind::FatArrow))
+        .or(Unit(Text("<-"),Kind::LeftArrow))
+        .or(Unit(Text("&&"),Kind::AmpAmp))
+        .or(Unit(Text("||"),Kind::BarBar))
+        .or(Unit(Text("<<"),Kind::ShiftLeft))
+        .or(Unit(Text(">>"),Kind::ShiftRight))
+        .or(Unit(Text("+="),Kind::PlusEqual))
+        .or(Unit(Text("-="),Kind::MinusEqual))
+        .or(Unit(Text("*="),Kind::StarEqual))
+        .or(Unit(Text("/="),Kind::SlashEqual))
+        .or(Unit(Text("%="),Kind::PercentEqual))
+        .or(Unit(Text("^="),Kind::CaretEqual))
+        .or(Unit(Text("&="),Kind::AmpEqual))
+        .or(Unit(Text("|="),Kind::BarEqual))
+        .or(Unit(Text("=="),Kind::EqualEqual))
+        .or(Unit(Text("!="),Kind::BangEqual))
+        .or(Unit(Text("<="),Kind::LessEqual))
+        .or(Unit(Text(">="),Kind::GreaterEqual))
+}
+
+fn short_punctuators() -> impl Scanner<Item=char,Token=Kind> {
+    Unit('(',Kind::LeftParen)
+        .or(Unit(')',Kind::RightParen))
+        .or(Unit('[',Kind::LeftSquare))
+        .or(Unit(']',Kind::RightSquare))
+        .or(Unit('{',Kind::LeftBrace))
+        .or(Unit('}',Kind::RightBrace))
+        .or(Unit('+',Kind::Plus))
+        .or(Unit('-',Kind::Minus))
+        .or(Unit('*',Kind::Star))
+        .or(Unit('/',Kind::Slash))
+        .or(Unit('%',Kind::Percent))
+        .or(Unit('^',Kind::Caret))
+        .or(Unit('!',Kind::Bang))
+        .or(Unit('&',Kind::Ampersand))
+        .or(Unit('|',Kind::Bar))
+        .or(Unit('=',Kind::Equal))
+        .or(Unit('<',Kind::Less))
+        .or(Unit('>',Kind::Greater))
+        .or(Unit('@',Kind::At))
+        .or(Unit('.',Kind::Dot))
+        .or(Unit(',',Kind::Comma))
+        .or(Unit(';',Kind::Semicolon))
+        .or(Unit(':',Kind::Colon))
+        .or(Unit('#',Kind::Hash))
+        .or(Unit('$',Kind::Dollar))
+        .or(Unit('?',Kind::Question))
+        .or(Unit('~',Kind::Tilde))
+}
+
+// Identifiers
+
+fn identifier_start() -> impl Matcher<Item=char> {
+    Within('a'..='z').or(Within('A'..='Z')).or('_').or(Within('\u{80}'..=char::MAX))
+}
+
+fn identifier() -> impl Matcher<Item=char> {
+    identifier_start().then(identifier_start().or(digit()).zero_or_more())
+}
+
+// Character and string literals
+
+fn hex_digit() -> impl Matcher<Item=char> {
+    digit().or(Within('a'..='f')).or(Within('A'..='F'))
+}
+
+/// Match an escape sequence, such as `\n`, `\x7F` or `\u{1F600}`.
+fn escape() -> impl Matcher<Item=char> {
+    let unicode = '\\'.then('u').then('{').then(NoneOf(['}','\'','"','\n']).zero_or_more()).then('}');
+    let byte = '\\'.then('x').then(hex_digit()).then(hex_digit());
+    unicode.or(byte).or('\\'.then(NoneOf([])))
+}
+
+fn char() -> impl Matcher<Item=char> {
+    '\''.then(NoneOf(['\'','\\','\n','\r','\t']).or(escape())).then('\'')
+}
+
+fn string() -> impl Matcher<Item=char> {
+    '"'.then(NoneOf(['"','\\']).or(escape()).zero_or_more()).then('"')
+}
+
+/// Matches the remainder of a raw string literal following its prefix
+/// (e.g. `r` or `br`), such as `##"a "# b"##`, where the number of
+/// hashes at the end must match that at the start.
+#[derive(Clone,Copy,Debug)]
+struct RawString;
+
+impl Matcher for RawString {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let start = input.offset();
+        let mut hashes = 0;
+        loop {
+            match input.next() {
+                Some('#') => { hashes += 1; }
+                Some('"') => { break; }
+                _ => {
+                    input.backup(input.offset() - start);
+                    return false;
+                }
+            }
+        }
+        // Search for the closing quote and hashes
+        loop {
+            match input.next() {
+                Some('"') => {
+                    let mark = input.offset();
+                    if (0..hashes).all(|_| input.next() == Some('#')) { return true; }
+                    input.backup(input.offset() - mark);
+                }
+                Some(_) => {}
+                None => {
+                    input.backup(input.offset() - start);
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+/// Matches a block comment, where comments may be nested (e.g. `/* a
+/// /* b */ c */`).
+#[derive(Clone,Copy,Debug)]
+struct NestedComment;
+
+impl Matcher for NestedComment {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let start = input.offset();
+        if !'/'.then('*').matches(input) { return false; }
+        let mut depth = 1;
+        let mut last = None;
+        loop {
+            match (last,input.next()) {
+                (_,None) => {
+                    input.backup(input.offset() - start);
+                    return false;
+                }
+                (Some('/'),Some('*')) => {
+                    depth += 1;
+                    last = None;
+                }
+                (Some('*'),Some('/')) => {
+                    depth -= 1;
+                    if depth == 0 { return true; }
+                    last = None;
+                }
+                (_,c) => { last = c; }
+            }
+        }
+    }
+}
+
+// Numbers
+
+fn digit() -> Within<char> {
+    Within('0'..='9')
+}
+
+/// Match a decimal literal, such as `1_000`.
+fn decimal() -> impl Matcher<Item=char> {
+    digit().then(digit().or('_').zero_or_more())
+}
+
+/// Match a suffix, such as `u8` or `f64`.  As with `rustc`, any
+/// identifier is accepted here and validated later.
+fn suffix() -> impl Matcher<Item=char> {
+    identifier()
+}
+
+fn integer() -> impl Matcher<Item=char> {
+    let hex = '0'.then('x').then('_'.zero_or_more()).then(hex_digit()).then(hex_digit().or('_').zero_or_more());
+    let oct = '0'.then('o').then('_'.zero_or_more()).then(Within('0'..='7')).then(Within('0'..='7').or('_').zero_or_more());
+    let bin = '0'.then('b').then('_'.zero_or_more()).then(Any(['0','1'])).then(Any(['0','1','_']).zero_or_more());
+    hex.or(oct).or(bin).or(decimal()).then(suffix().optional())
+}
+
+fn float() -> impl Matcher<Item=char> {
+    let exponent = || Any(['e','E']).then(Any(['+','-']).optional()).then('_'.zero_or_more()).then(decimal());
+    let fraction = decimal().then('.').then(decimal()).then(exponent().optional()).then(suffix().optional());
+    let exponential = decimal().then(exponent()).then(suffix().optional());
+    // A trailing dot cannot be followed by a range, field or method
+    // (e.g. `1..2` or `1.max(2)`)
+    let dot = decimal().then('.').not_followed_by('.'.or(identifier_start()));
+    fraction.or(exponential).or(dot)
+}
@@ -0,0 +1,296 @@
+use crate::{Any,Matcher,NoneOf,Scanner,Text,Token,TokenKind,Unit,Within};
+use crate::util::{Cursor,StrCursor};
+
+/// Identifies the kinds of token making up a TOML document.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    /// Spaces and tabs.
+    WhiteSpace,
+    /// A line break (`\n` or `\r\n`), which is significant in TOML.
+    Newline,
+    /// A comment, such as `# hello`.
+    Comment,
+    /// A `[` either opening a table header or an array.
+    LeftSquare,
+    /// A `]` either closing a table header or an array.
+    RightSquare,
+    /// A `[[` opening an array of tables header.
+    DoubleLeftSquare,
+    /// A `]]` closing an array of tables header.
+    DoubleRightSquare,
+    LeftBrace,
+    RightBrace,
+    Equals,
+    Dot,
+    Comma,
+    /// An unquoted key, such as `name` or `1234`.
+    BareKey,
+    /// A string such as `"hello\n"`.
+    BasicString,
+    /// A string such as `"""hello"""`.
+    MultilineBasicString,
+    /// A string such as `'C:\Users'`.
+    LiteralString,
+    /// A string such as `'''hello'''`.
+    MultilineLiteralString,
+    /// An integer such as `+99`, `1_000`, `0xDEAD_BEEF`, `0o755` or
+    /// `0b1101`.
+    Integer,
+    /// A float such as `3.14`, `6.626e-34`, `inf` or `-nan`.
+    Float,
+    /// Either `true` or `false`.
+    Boolean,
+    /// A date-time with an offset, such as `1979-05-27T07:32:00Z`.
+    OffsetDateTime,
+    /// A date-time without an offset, such as `1979-05-27T07:32:00`.
+    LocalDateTime,
+    /// A date, such as `1979-05-27`.
+    LocalDate,
+    /// A time, such as `07:32:00.999`.
+    LocalTime,
+    /// A string which is not closed before the end of its line (or
+    /// the end of the input, for multiline strings).
+    UnterminatedString,
+    /// A character which cannot begin any token.
+    Unknown
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::UnterminatedString|Kind::Unknown)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::Comment)
+    }
+}
+
+/// Splits a TOML document into tokens.  TOML cannot be lexed without
+/// some context, since keys overlap with values (e.g. `true`, `1234`
+/// and `1979-05-27` are valid bare keys).  Therefore, this tracks
+/// whether a key or value is expected next and chooses between two
+/// scanners accordingly.
+pub struct TomlLexer<'a> {
+    input: StrCursor<'a>,
+    /// Brackets currently open.
+    stack: Vec<Bracket>,
+    /// Indicates whether a key (rather than a value) is expected next.
+    key: bool
+}
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Bracket {
+    Header,
+    ArrayHeader,
+    Array,
+    InlineTable
+}
+
+impl<'a> TomlLexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self{input: StrCursor::new(input), stack: Vec::new(), key: true}
+    }
+
+    /// Update the context following a given token.
+    fn update(&mut self, kind: Kind) {
+        match kind {
+            Kind::Newline if self.stack.is_empty() => { self.key = true; }
+            Kind::Equals => { self.key = false; }
+            Kind::Dot => { self.key = true; }
+            Kind::LeftSquare if self.key => { self.stack.push(Bracket::Header); }
+            Kind::DoubleLeftSquare => { self.stack.push(Bracket::ArrayHeader); }
+            Kind::LeftSquare => { self.stack.push(Bracket::Array); }
+            Kind::LeftBrace => {
+                self.stack.push(Bracket::InlineTable);
+                self.key = true;
+            }
+            Kind::Comma => { self.key = self.stack.last() == Some(&Bracket::InlineTable); }
+            Kind::RightSquare|Kind::DoubleRightSquare|Kind::RightBrace => {
+                self.stack.pop();
+                self.key = self.stack.is_empty();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Iterator for TomlLexer<'_> {
+    type Item = Token<Kind>;
+
+    fn next(&mut self) -> Option<Token<Kind>> {
+        let start = self.input.offset();
+        let kind = match self.key {
+            true => key_rules().scan(&mut self.input)?,
+            false => value_rules().scan(&mut self.input)?
+        };
+        let end = self.input.offset();
+        self.input.reset();
+        self.update(kind);
+        Some(Token::new(kind,start..end))
+    }
+}
+
+/// Split a TOML document into tokens.
+pub fn lex(input: &str) -> Vec<Token<Kind>> {
+    TomlLexer::new(input).collect()
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Rules common to both keys and values.
+fn common_rules() -> impl Scanner<Item=char,Token=Kind> {
+    Unit(Any([' ','\t']).one_or_more(),Kind::WhiteSpace)
+        .or(Unit('\r'.optional().then('\n'),Kind::Newline))
+        .or(Unit('#'.then(NoneOf(['\n','\r']).zero_or_more()),Kind::Comment))
+        .or(Unit('}',Kind::RightBrace))
+        .or(Unit(',',Kind::Comma))
+}
+
+fn key_rules() -> impl Scanner<Item=char,Token=Kind> {
+    let bare = Within('a'..='z').or(Within('A'..='Z')).or(Within('0'..='9')).or(Any(['_','-'])).one_or_more();
+    common_rules()
+        .or(Unit('['.then('['),Kind::DoubleLeftSquare))
+        .or(Unit(']'.then(']'),Kind::DoubleRightSquare))
+        .or(Unit('[',Kind::LeftSquare))
+        .or(Unit(']',Kind::RightSquare))
+        .or(Unit('=',Kind::Equals))
+        .or(Unit('.',Kind::Dot))
+        .or(Unit(bare,Kind::BareKey))
+        .or(Unit(basic_string(),Kind::BasicString))
+        .or(Unit(literal_string(),Kind::LiteralString))
+        .or(Unit(unterminated_string(),Kind::UnterminatedString))
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+fn value_rules() -> impl Scanner<Item=char,Token=Kind> {
+    common_rules()
+        .or(Unit('[',Kind::LeftSquare))
+        .or(Unit(']',Kind::RightSquare))
+        .or(Unit('{',Kind::LeftBrace))
+        .or(Unit(multiline_basic_string(),Kind::MultilineBasicString))
+        .or(Unit(multiline_literal_string(),Kind::MultilineLiteralString))
+        .or(Unit(unterminated_multiline_string(),Kind::UnterminatedString))
+        .or(Unit(basic_string(),Kind::BasicString))
+        .or(Unit(literal_string(),Kind::LiteralString))
+        .or(Unit(unterminated_string(),Kind::UnterminatedString))
+        .or(Unit(date().then(Any(['T','t',' '])).then(time()).then(offset()),Kind::OffsetDateTime))
+        .or(Unit(date().then(Any(['T','t',' '])).then(time()),Kind::LocalDateTime))
+        .or(Unit(date(),Kind::LocalDate))
+        .or(Unit(time(),Kind::LocalTime))
+        .or(Unit(float(),Kind::Float))
+        .or(Unit(integer(),Kind::Integer))
+        .or(Unit(word("true").or(word("false")),Kind::Boolean))
+        .or(Unit(NoneOf([]),Kind::Unknown))
+}
+
+// Strings
+
+fn escape() -> impl Matcher<Item=char> {
+    '\\'.then(NoneOf([]))
+}
+
+fn basic_string() -> impl Matcher<Item=char> {
+    '"'.then(NoneOf(['"','\\','\n']).or(escape()).zero_or_more()).then('"')
+}
+
+fn literal_string() -> impl Matcher<Item=char> {
+    '\''.then(NoneOf(['\'','\n']).zero_or_more()).then('\'')
+}
+
+/// A multiline basic string may contain up to two consecutive quotes,
+/// and may end with up to two additional quotes (e.g. `"""a"""""`).
+fn multiline_basic_string() -> impl Matcher<Item=char> {
+    let plain = NoneOf(['"','\\']).or(escape());
+    let quotes = '"'.then('"'.optional()).then(NoneOf(['"','\\']).or(escape()));
+    let body = plain.or(quotes).zero_or_more();
+    '"'.then('"').then('"').then(body).then('"').then('"').then('"').then('"'.optional()).then('"'.optional())
+}
+
+fn multiline_literal_string() -> impl Matcher<Item=char> {
+    let quotes = '\''.then('\''.optional()).then(NoneOf(['\'']));
+    let body = NoneOf(['\'']).or(quotes).zero_or_more();
+    '\''.then('\'').then('\'').then(body).then('\'').then('\'').then('\'').then('\''.optional()).then('\''.optional())
+}
+
+/// Match a multiline string missing its closing quotes, which runs to
+/// the end of the input.
+fn unterminated_multiline_string() -> impl Matcher<Item=char> {
+    let basic = '"'.then('"').then('"').then(NoneOf([]).zero_or_more());
+    let literal = '\''.then('\'').then('\'').then(NoneOf([]).zero_or_more());
+    basic.or(literal)
+}
+
+/// Match a string missing its closing quote, which runs to the end of
+/// the line.
+fn unterminated_string() -> impl Matcher<Item=char> {
+    let basic = '"'.then(NoneOf(['"','\\','\n','\r']).or(escape()).zero_or_more()).then('\\'.optional());
+    let literal = '\''.then(NoneOf(['\'','\n','\r']).zero_or_more());
+    basic.or(literal)
+}
+
+// Numbers
+
+fn digit() -> Within<char> {
+    Within('0'..='9')
+}
+
+/// Match one or more items, where adjacent items may be separated by
+/// a single underscore (e.g. `1_000`).
+fn underscored<M:Matcher<Item=char>+Clone>(m: M) -> impl Matcher<Item=char> {
+    m.clone().then('_'.optional().then(m).zero_or_more())
+}
+
+fn sign() -> impl Matcher<Item=char> {
+    Any(['+','-']).optional()
+}
+
+fn integer() -> impl Matcher<Item=char> {
+    let hex = Within('0'..='9').or(Within('a'..='f')).or(Within('A'..='F'));
+    let hex = '0'.then('x').then(underscored(hex));
+    let oct = '0'.then('o').then(underscored(Within('0'..='7')));
+    let bin = '0'.then('b').then(underscored(Any(['0','1'])));
+    let dec = sign().then('0'.or(Within('1'..='9').then('_'.optional().then(digit()).zero_or_more())));
+    hex.or(oct).or(bin).or(dec)
+}
+
+fn float() -> impl Matcher<Item=char> {
+    let int = '0'.or(Within('1'..='9').then('_'.optional().then(digit()).zero_or_more()));
+    let frac = '.'.then(underscored(digit()));
+    let special = sign().then(word("inf").or(word("nan")));
+    special.or(sign().then(int).then(frac.then(exponent().optional()).or(exponent())))
+}
+
+fn exponent() -> impl Matcher<Item=char> {
+    Any(['e','E']).then(sign()).then(underscored(digit()))
+}
+
+// Dates and times
+
+fn two_digits() -> impl Matcher<Item=char> {
+    digit().then(digit())
+}
+
+/// Match a date such as `1979-05-27`.
+fn date() -> impl Matcher<Item=char> {
+    two_digits().then(two_digits()).then('-').then(two_digits()).then('-').then(two_digits())
+}
+
+/// Match a time such as `07:32:00.999`.
+fn time() -> impl Matcher<Item=char> {
+    two_digits().then(':').then(two_digits()).then(':').then(two_digits())
+        .then('.'.then(digit().one_or_more()).optional())
+}
+
+/// Match a time offset such as `Z` or `-07:00`.
+fn offset() -> impl Matcher<Item=char> {
+    Any(['Z','z']).or(Any(['+','-']).then(two_digits()).then(':').then(two_digits()))
+}
+
+/// Matches a given word, provided it is not immediately followed by a
+/// bare key character (e.g. `true` but not `trueish`).
+fn word(w: &'static str) -> impl Matcher<Item=char> {
+    let bare = Within('a'..='z').or(Within('A'..='Z')).or(Within('0'..='9')).or(Any(['_','-']));
+    Text(w).not_followed_by(bare)
+}
@@ -1,39 +1,57 @@
+use std::io::{BufRead,BufReader,Read};
 use crate::{Token};
-use crate::util::{ResetIterator};
+use crate::util::{Cursor,ReadError,ResetIterator,Utf8Reader};
 use crate::scanner::Scanner;
 
-/// =============================================================================
-/// Lexer
-/// =============================================================================
+// =============================================================================
+// Lexer
+// =============================================================================
 
-pub struct Lexer<I:Iterator,S:Scanner> {
-    iter: ResetIterator<I>,
-    rules: S,
-    offset: usize
+/// Turns a sequence of items into a sequence of tokens by repeatedly
+/// applying a given `Scanner` to some input `Cursor`.
+pub struct Lexer<C:Cursor,S:Scanner> {
+    input: C,
+    rules: S
 }
 
-impl<I:Iterator,S:Scanner> Lexer<I,S> {
+impl<I:Iterator,S:Scanner> Lexer<ResetIterator<I>,S>
+where I::Item: Copy {
+    /// Construct a lexer from an arbitrary iterator.  Items read from
+    /// the iterator are buffered until the current token is complete.
     pub fn new(iter: I, rules: S) -> Self {
-        let iter = ResetIterator::new(iter);
-        Self{iter,rules, offset:0}
+        let input = ResetIterator::new(iter);
+        Self{input,rules}
     }
 }
 
-impl<I:Iterator,S:Scanner<Item=I::Item>> Iterator for Lexer<I,S>
-where I::Item: Copy
+impl<C:Cursor,S:Scanner> Lexer<C,S> {
+    /// Construct a lexer from an arbitrary cursor (e.g. a
+    /// `SliceCursor` or `StrCursor` when the input is already in
+    /// memory).
+    pub fn with_cursor(input: C, rules: S) -> Self {
+        Self{input,rules}
+    }
+
+    /// Get a reference to the underlying input cursor.
+    pub fn get_ref(&self) -> &C {
+        &self.input
+    }
+}
+
+impl<C:Cursor,S:Scanner<Item=C::Item>> Iterator for Lexer<C,S>
 {
     type Item = Token<S::Token>;
-    
+
     fn next(&mut self) -> Option<Self::Item> {
         // Compute start offset
-        let start = self.iter.offset();
+        let start = self.input.offset();
         // See what we've got
-        match self.rules.scan(&mut self.iter) {
-            Some(t) => {               
+        match self.rules.scan(&mut self.input) {
+            Some(t) => {
                 // Compute end offset
-                let end = self.iter.offset();
-                // Reset iterator
-                self.iter.reset();                
+                let end = self.input.offset();
+                // Reset input
+                self.input.reset();
                 // Done
                 Some(Token::new(t,start..end))
             }
@@ -41,3 +59,60 @@ where I::Item: Copy
         }
     }
 }
+
+// =============================================================================
+// Reader Lexer
+// =============================================================================
+
+/// A lexer which reads UTF-8 encoded characters directly from a byte
+/// stream.  Only the characters of the current token are held in
+/// memory at any one time, meaning arbitrarily large inputs can be
+/// lexed.  Each token is returned along with its text.
+pub struct ReadLexer<B:BufRead,S:Scanner<Item=char>> {
+    input: ResetIterator<Utf8Reader<B>>,
+    rules: S
+}
+
+impl<R:Read,S:Scanner<Item=char>> ReadLexer<BufReader<R>,S> {
+    /// Construct a lexer from an arbitrary (unbuffered) reader.
+    pub fn new(reader: R, rules: S) -> Self {
+        Self::from_buf_read(BufReader::new(reader),rules)
+    }
+}
+
+impl<B:BufRead,S:Scanner<Item=char>> ReadLexer<B,S> {
+    /// Construct a lexer from a buffered reader.
+    pub fn from_buf_read(reader: B, rules: S) -> Self {
+        let input = ResetIterator::new(Utf8Reader::new(reader));
+        Self{input,rules}
+    }
+
+    /// Get the number of bytes consumed from the underlying stream.
+    /// Note, this may be ahead of the end of the last token returned.
+    pub fn byte_offset(&self) -> usize {
+        self.input.get_ref().byte_offset()
+    }
+}
+
+impl<B:BufRead,S:Scanner<Item=char>> Iterator for ReadLexer<B,S> {
+    type Item = Result<(Token<S::Token>,String),ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Compute start offset
+        let start = self.input.offset();
+        // See what we've got
+        match self.rules.scan(&mut self.input) {
+            Some(t) => {
+                // Compute end offset
+                let end = self.input.offset();
+                // Extract token text
+                let text = self.input.window().iter().collect();
+                // Reset input
+                self.input.reset();
+                // Done
+                Some(Ok((Token::new(t,start..end),text)))
+            }
+            None => self.input.get_mut().take_error().map(Err)
+        }
+    }
+}
@@ -1,13 +1,33 @@
 // Private modules
+mod derive;
+mod incremental;
 mod lexer;
+mod macros;
 mod matcher;
 mod scanner;
+mod stream;
 mod token;
 // Public modules
+pub mod bytes;
+pub mod diagnostic;
+pub mod escape;
+pub mod json;
+pub mod lang;
+pub mod number;
+pub mod parser;
+pub mod pratt;
+pub mod sexp;
+pub mod spec;
+pub mod tree;
 pub mod util;
 // Exports from private modules
+pub use derive::*;
+pub use incremental::*;
 pub use lexer::*;
+pub use macros::*;
 pub use matcher::*;
 pub use scanner::*;
+pub use stream::*;
 pub use token::*;
-
+// Derive macros
+pub use lexington_derive::Lexington;
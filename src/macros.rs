@@ -0,0 +1,203 @@
+use crate::{Matcher,Text};
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+/// Declare a token enum together with the rules for recognising each
+/// kind of token.  Each rule has the form `Kind = pattern;`, where a
+/// pattern is a sequence of:
+///
+/// * Literals, such as `'('` or `"->"`.
+/// * Character classes, such as `['a'..='z','_']`, and negated
+///   character classes, such as `[^'"','\n']`.
+/// * `any`, which matches any single character.
+/// * Groups, such as `("0x" | "0X")`.
+/// * Arbitrary matchers, such as `{ number::decimal(None) }`.
+///
+/// Each of these can be followed by `*`, `+` or `?`, and alternatives
+/// can be separated by `|`.  Rules prefixed with `skip` (e.g. for
+/// whitespace) are still matched, but their tokens are dropped by
+/// `lex()`.  Rules are tried in order, with the first match taken.
+/// For example:
+///
+/// ```
+/// lexington::lexer! {
+///     pub enum Kind {
+///         skip WhiteSpace = [' ','\t','\n']+;
+///         Number = ['0'..='9']+;
+///         Identifier = ['a'..='z','A'..='Z','_'] ['a'..='z','A'..='Z','0'..='9','_']*;
+///         Arrow = "->";
+///         Minus = '-';
+///     }
+/// }
+///
+/// let tokens = Kind::lex("x -> 10");
+/// assert_eq!(tokens,&[(Kind::Identifier,0..1),(Kind::Arrow,2..4),(Kind::Number,5..7)]);
+/// ```
+///
+/// This generates the enum, along with `scanner()`, `is_skipped()` and
+/// `lex()` functions for it.
+#[macro_export]
+macro_rules! lexer {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($body:tt)* }) => {
+        $crate::__lexer!(@rules [$(#[$meta])*] [$vis] $name [] $($body)*);
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __lexer {
+    // Generate everything once all rules are parsed
+    (@rules [$($meta:tt)*] [$vis:vis] $name:ident [$(([$($attr:tt)*] $skip:tt $kind:ident [$($pat:tt)*]))*]) => {
+        $($meta)*
+        #[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+        $vis enum $name {
+            $($($attr)* $kind,)*
+        }
+
+        impl $name {
+            /// Construct a scanner which tries each rule in turn.
+            #[allow(dead_code)]
+            $vis fn scanner() -> impl $crate::Scanner<Item=char,Token=$name> {
+                $crate::__lexer!(@or $(($crate::Unit($crate::__pattern!($($pat)*),$name::$kind)))*)
+            }
+
+            /// Determine whether tokens of this kind are skipped.
+            #[allow(dead_code)]
+            $vis fn is_skipped(self) -> bool {
+                match self {
+                    $($name::$kind => $skip,)*
+                }
+            }
+
+            /// Split a string into tokens, dropping those which are
+            /// skipped.  Lexing stops at the first character not
+            /// matched by any rule.
+            #[allow(dead_code)]
+            $vis fn lex(input: &str) -> Vec<$crate::Token<$name>> {
+                let cursor = $crate::util::StrCursor::new(input);
+                $crate::Lexer::with_cursor(cursor,Self::scanner()).filter(|t| !t.kind.is_skipped()).collect()
+            }
+        }
+    };
+    // Start a rule
+    (@rules $meta:tt $vis:tt $name:ident $rules:tt $(#[$attr:meta])* skip $kind:ident = $($rest:tt)*) => {
+        $crate::__lexer!(@rule $meta $vis $name $rules [$(#[$attr])*] true $kind [] $($rest)*);
+    };
+    (@rules $meta:tt $vis:tt $name:ident $rules:tt $(#[$attr:meta])* $kind:ident = $($rest:tt)*) => {
+        $crate::__lexer!(@rule $meta $vis $name $rules [$(#[$attr])*] false $kind [] $($rest)*);
+    };
+    // Finish a rule.  To limit recursion, up to four tokens are taken
+    // in one step.
+    (@rule $meta:tt $vis:tt $name:ident [$($rules:tt)*] $attrs:tt $skip:tt $kind:ident [$($pat:tt)*] ; $($rest:tt)*) => {
+        $crate::__lexer!(@rules $meta $vis $name [$($rules)* ($attrs $skip $kind [$($pat)*])] $($rest)*);
+    };
+    (@rule $meta:tt $vis:tt $name:ident [$($rules:tt)*] $attrs:tt $skip:tt $kind:ident [$($pat:tt)*] $a:tt ; $($rest:tt)*) => {
+        $crate::__lexer!(@rules $meta $vis $name [$($rules)* ($attrs $skip $kind [$($pat)* $a])] $($rest)*);
+    };
+    (@rule $meta:tt $vis:tt $name:ident [$($rules:tt)*] $attrs:tt $skip:tt $kind:ident [$($pat:tt)*] $a:tt $b:tt ; $($rest:tt)*) => {
+        $crate::__lexer!(@rules $meta $vis $name [$($rules)* ($attrs $skip $kind [$($pat)* $a $b])] $($rest)*);
+    };
+    (@rule $meta:tt $vis:tt $name:ident [$($rules:tt)*] $attrs:tt $skip:tt $kind:ident [$($pat:tt)*] $a:tt $b:tt $c:tt ; $($rest:tt)*) => {
+        $crate::__lexer!(@rules $meta $vis $name [$($rules)* ($attrs $skip $kind [$($pat)* $a $b $c])] $($rest)*);
+    };
+    // Otherwise, none of the next four tokens ends the rule
+    (@rule $meta:tt $vis:tt $name:ident $rules:tt $attrs:tt $skip:tt $kind:ident [$($pat:tt)*] $a:tt $b:tt $c:tt $d:tt $($rest:tt)*) => {
+        $crate::__lexer!(@rule $meta $vis $name $rules $attrs $skip $kind [$($pat)* $a $b $c $d] $($rest)*);
+    };
+    (@or ($first:expr)) => {
+        $first
+    };
+    (@or ($first:expr) $($rest:tt)+) => {
+        $crate::Scanner::or($first,$crate::__lexer!(@or $($rest)+))
+    };
+}
+
+/// Construct a matcher from a pattern, as used in `lexer!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pattern {
+    // Split alternatives
+    (@alt [$($alts:tt)*] [$($seq:tt)*]) => {
+        $crate::__pattern!(@or $($alts)* [$($seq)*])
+    };
+    (@alt [$($alts:tt)*] [$($seq:tt)*] | $($rest:tt)*) => {
+        $crate::__pattern!(@alt [$($alts)* [$($seq)*]] [] $($rest)*)
+    };
+    (@alt $alts:tt [$($seq:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::__pattern!(@alt $alts [$($seq)* $t] $($rest)*)
+    };
+    (@or [$($first:tt)*]) => {
+        $crate::__pattern!(@seq [] $($first)*)
+    };
+    (@or [$($first:tt)*] $($rest:tt)+) => {
+        $crate::Matcher::or($crate::__pattern!(@seq [] $($first)*),$crate::__pattern!(@or $($rest)+))
+    };
+    // Split sequences
+    (@seq [$($items:tt)*]) => {
+        $crate::__pattern!(@then $($items)*)
+    };
+    (@seq [$($items:tt)*] $a:tt * $($rest:tt)*) => {
+        $crate::__pattern!(@seq [$($items)* ($crate::Matcher::zero_or_more($crate::__pattern!(@atom $a)))] $($rest)*)
+    };
+    (@seq [$($items:tt)*] $a:tt + $($rest:tt)*) => {
+        $crate::__pattern!(@seq [$($items)* ($crate::Matcher::one_or_more($crate::__pattern!(@atom $a)))] $($rest)*)
+    };
+    (@seq [$($items:tt)*] $a:tt ? $($rest:tt)*) => {
+        $crate::__pattern!(@seq [$($items)* ($crate::Matcher::optional($crate::__pattern!(@atom $a)))] $($rest)*)
+    };
+    (@seq [$($items:tt)*] $a:tt $($rest:tt)*) => {
+        $crate::__pattern!(@seq [$($items)* ($crate::__pattern!(@atom $a))] $($rest)*)
+    };
+    (@then $first:tt) => {
+        $first
+    };
+    (@then $first:tt $($rest:tt)+) => {
+        $crate::Matcher::then($first,$crate::__pattern!(@then $($rest)+))
+    };
+    // Atoms
+    (@atom [^ $($lo:literal $(..= $hi:literal)?),+ $(,)?]) => {
+        $crate::NotClass([$(($lo,$crate::__pattern!(@hi $lo $($hi)?))),+])
+    };
+    (@atom [$($lo:literal $(..= $hi:literal)?),+ $(,)?]) => {
+        $crate::Class([$(($lo,$crate::__pattern!(@hi $lo $($hi)?))),+])
+    };
+    (@atom ($($p:tt)+)) => {
+        $crate::__pattern!(@alt [] [] $($p)+)
+    };
+    (@atom {$e:expr}) => {
+        $e
+    };
+    (@atom any) => {
+        $crate::NoneOf::<char,0>([])
+    };
+    (@atom $s:literal) => {
+        $crate::Literal::pattern($s)
+    };
+    (@hi $lo:literal) => { $lo };
+    (@hi $lo:literal $hi:literal) => { $hi };
+    ($($p:tt)+) => {
+        $crate::__pattern!(@alt [] [] $($p)+)
+    };
+}
+
+/// Converts a literal in a `lexer!` pattern into a matcher.
+#[doc(hidden)]
+pub trait Literal {
+    type Output: Matcher<Item=char>;
+
+    fn pattern(self) -> Self::Output;
+}
+
+impl Literal for char {
+    type Output = char;
+
+    fn pattern(self) -> char { self }
+}
+
+impl Literal for &'static str {
+    type Output = Text<'static>;
+
+    fn pattern(self) -> Text<'static> { Text(self) }
+}
@@ -0,0 +1,361 @@
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self,Read,Write};
+use std::process::ExitCode;
+use lexington::{json,sexp,Token,TokenKind};
+use lexington::lang::{c,csv,ini,rust,toml};
+use lexington::spec::Spec;
+use lexington::util::{Encoding,LineIndex,OffsetKind,Region};
+
+const USAGE : &str = "\
+Usage: lexington [OPTIONS] [FILE...]
+
+Splits each file (or stdin, given no files or `-`) into tokens and
+prints them, along with their positions.
+
+Options:
+  -s, --spec <FILE>      Lex using a lexer specification (.lex) file
+  -l, --lang <NAME>      Lex using a built-in lexer: c, cpp, csv, ini,
+                         json, rust, sexp, toml or tsv
+  -f, --format <FORMAT>  Print tokens as a `table` (default), as `json`
+                         lines, or print the input highlighted in `colour`
+      --skip             Omit skipped tokens (e.g. whitespace and comments)
+  -h, --help             Print this message
+
+Without --spec or --lang, the lexer is chosen by file extension.  The
+exit status is 0 if all input was lexed without errors, 1 if any error
+tokens were produced, and 2 if a problem arose (e.g. reading a file).";
+
+// =============================================================================
+// Options
+// =============================================================================
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Colour
+}
+
+/// Identifies the lexer to apply.
+enum Source {
+    Spec(Spec),
+    Lang(&'static str),
+    /// Choose by file extension
+    Infer
+}
+
+struct Options {
+    source: Source,
+    format: Format,
+    skip: bool,
+    files: Vec<String>
+}
+
+fn parse_args(args: &[String]) -> Result<Option<Options>,String> {
+    let mut options = Options{source: Source::Infer, format: Format::Table, skip: false, files: Vec::new()};
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().cloned().ok_or_else(|| format!("missing value for {name}"));
+        match arg.as_str() {
+            "-h"|"--help" => return Ok(None),
+            "-s"|"--spec" => {
+                let path = value(arg)?;
+                let text = fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+                let spec = Spec::parse(&text).map_err(|e| e.to_diagnostic().render(&path,&text))?;
+                options.source = Source::Spec(spec);
+            }
+            "-l"|"--lang" => {
+                let name = value(arg)?;
+                let lang = LANGS.iter().find(|l| **l == name).ok_or_else(|| format!("unknown language `{name}`"))?;
+                options.source = Source::Lang(lang);
+            }
+            "-f"|"--format" => {
+                options.format = match value(arg)?.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "colour"|"color" => Format::Colour,
+                    f => return Err(format!("unknown format `{f}`"))
+                };
+            }
+            "--skip" => { options.skip = true; }
+            a if a.starts_with('-') && a != "-" => return Err(format!("unknown option `{a}`")),
+            _ => options.files.push(arg.clone())
+        }
+    }
+    if options.files.is_empty() {
+        options.files.push("-".to_string());
+    }
+    Ok(Some(options))
+}
+
+// =============================================================================
+// Lexing
+// =============================================================================
+
+const LANGS : [&str;9] = ["c","cpp","csv","ini","json","rust","sexp","toml","tsv"];
+
+/// A token resolved into a form common to all lexers.
+struct Lexeme {
+    /// The kind of token, or `None` for text matched by no rule.
+    kind: Option<String>,
+    region: Region,
+    error: bool,
+    skipped: bool
+}
+
+/// Choose a built-in lexer from a file extension.
+fn infer(path: &str) -> Option<&'static str> {
+    let extension = path.rsplit_once('.')?.1;
+    let lang = match extension {
+        "c"|"h" => "c",
+        "cc"|"cpp"|"cxx"|"hh"|"hpp"|"hxx" => "cpp",
+        "rs" => "rust",
+        "scm"|"lisp"|"sexp" => "sexp",
+        "csv"|"ini"|"json"|"toml"|"tsv" => extension,
+        _ => return None
+    };
+    LANGS.iter().copied().find(|l| *l == lang)
+}
+
+fn lex_lang(lang: &str, input: &str) -> Vec<Lexeme> {
+    match lang {
+        "c" => lexemes(c::lex(input,c::Standard::C17)),
+        "cpp" => lexemes(c::lex(input,c::Standard::Cpp20)),
+        "csv" => lexemes(csv::lex(input,csv::Dialect::csv())),
+        "ini" => lexemes(ini::lex(input)),
+        "json" => lexemes(json::lex(input)),
+        "rust" => lexemes(rust::lex(input)),
+        "sexp" => lexemes(sexp::lex(input)),
+        "toml" => lexemes(toml::lex(input)),
+        "tsv" => lexemes(csv::lex(input,csv::Dialect::tsv())),
+        _ => unreachable!()
+    }
+}
+
+/// Convert the tokens of a built-in lexer, where trivia (e.g.
+/// whitespace and comments) is skipped.
+fn lexemes<K:Debug+TokenKind>(tokens: Vec<Token<K>>) -> Vec<Lexeme> {
+    tokens.into_iter().map(|t| {
+        let kind = format!("{:?}",t.kind);
+        Lexeme{kind: Some(kind), region: t.region, error: t.kind.is_error(), skipped: t.kind.is_trivia()}
+    }).collect()
+}
+
+fn lex_spec(spec: &Spec, input: &str) -> Vec<Lexeme> {
+    spec.lexer(input).map(|t| {
+        let skipped = t.kind.is_some_and(|k| spec.is_skipped(k));
+        Lexeme{kind: t.kind.map(str::to_string), region: t.region, error: t.kind.is_none(), skipped}
+    }).collect()
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+/// Provides access to the text and positions of tokens within a file.
+struct Input<'a> {
+    name: &'a str,
+    text: &'a str,
+    index: LineIndex<'a>,
+    /// Byte offset of each character (and the end).
+    offsets: Vec<usize>
+}
+
+impl<'a> Input<'a> {
+    fn new(name: &'a str, text: &'a str) -> Self {
+        let offsets = text.char_indices().map(|(i,_)| i).chain([text.len()]).collect();
+        Self{name, text, index: LineIndex::new(text,OffsetKind::Chars), offsets}
+    }
+
+    fn text(&self, region: Region) -> &'a str {
+        &self.text[self.offsets[region.start]..self.offsets[region.end]]
+    }
+
+    /// Get the (one-based) line and column of an offset.
+    fn position(&self, offset: usize) -> (u32,u32) {
+        let p = self.index.position(offset,Encoding::Utf32);
+        (p.line+1,p.character+1)
+    }
+
+    fn range(&self, region: Region) -> String {
+        let (l1,c1) = self.position(region.start);
+        let (l2,c2) = self.position(region.end);
+        format!("{l1}:{c1}-{l2}:{c2}")
+    }
+}
+
+fn table(out: &mut impl Write, input: &Input, lexemes: &[Lexeme]) -> io::Result<()> {
+    let ranges : Vec<String> = lexemes.iter().map(|l| input.range(l.region)).collect();
+    let kinds : Vec<&str> = lexemes.iter().map(|l| l.kind.as_deref().unwrap_or("<none>")).collect();
+    let w1 = ranges.iter().map(String::len).max().unwrap_or(0);
+    let w2 = kinds.iter().map(|k| k.len()).max().unwrap_or(0);
+    for ((l,range),kind) in lexemes.iter().zip(&ranges).zip(&kinds) {
+        let text = format!("{:?}",input.text(l.region));
+        match l.error {
+            true => writeln!(out,"{range:w1$}  {kind:w2$}  {text}  <- error")?,
+            false => writeln!(out,"{range:w1$}  {kind:w2$}  {text}")?
+        }
+    }
+    Ok(())
+}
+
+fn json_lines(out: &mut impl Write, input: &Input, lexemes: &[Lexeme]) -> io::Result<()> {
+    for l in lexemes {
+        let kind = l.kind.as_deref().map_or("null".to_string(),json_string);
+        let (l1,c1) = input.position(l.region.start);
+        let (l2,c2) = input.position(l.region.end);
+        writeln!(out,"{{\"file\":{},\"kind\":{kind},\"text\":{},\"start\":{{\"line\":{l1},\"column\":{c1}}},\"end\":{{\"line\":{l2},\"column\":{c2}}},\"error\":{}}}",
+                 json_string(input.name),json_string(input.text(l.region)),l.error)?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut r = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => r.push_str("\\\""),
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '\t' => r.push_str("\\t"),
+            c if (c as u32) < 0x20 => r.push_str(&format!("\\u{:04x}",c as u32)),
+            c => r.push(c)
+        }
+    }
+    r.push('"');
+    r
+}
+
+const RESET : &str = "\x1b[0m";
+const PALETTE : [&str;4] = ["\x1b[34m","\x1b[33m","\x1b[36m","\x1b[35m"];
+
+/// Choose the colour for a given kind of token, based on its name.
+fn colour(l: &Lexeme) -> Option<&'static str> {
+    if l.error { return Some("\x1b[1;37;41m"); }
+    let kind = l.kind.as_deref()?.to_ascii_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| kind.contains(w));
+    if l.skipped && !has(&["comment"]) {
+        None
+    } else if has(&["comment"]) {
+        Some("\x1b[90m")
+    } else if has(&["string","char","quote"]) {
+        Some("\x1b[32m")
+    } else if has(&["number","integer","float","byte","boolean","true","false","null"]) {
+        Some("\x1b[36m")
+    } else if has(&["keyword","preprocessor","section"]) {
+        Some("\x1b[1;35m")
+    } else if has(&["identifier","symbol","atom","key","field","text","value"]) {
+        None
+    } else {
+        // Distinguish other kinds (e.g. from a specification) by name
+        let hash = kind.bytes().fold(0usize,|h,b| h.wrapping_mul(31).wrapping_add(b as usize));
+        Some(PALETTE[hash % PALETTE.len()])
+    }
+}
+
+fn highlight(out: &mut impl Write, input: &Input, lexemes: &[Lexeme]) -> io::Result<()> {
+    for l in lexemes {
+        let text = input.text(l.region);
+        match colour(l) {
+            // Avoid colouring across line breaks, as some terminals
+            // extend backgrounds to the end of the line
+            Some(c) => {
+                let lines : Vec<String> = text.split('\n').map(|s| if s.is_empty() { String::new() } else { format!("{c}{s}{RESET}") }).collect();
+                write!(out,"{}",lines.join("\n"))?
+            }
+            None => write!(out,"{text}")?
+        }
+    }
+    if !input.text.ends_with('\n') && !input.text.is_empty() { writeln!(out)?; }
+    Ok(())
+}
+
+// =============================================================================
+// Main
+// =============================================================================
+
+fn read(path: &str) -> io::Result<String> {
+    match path {
+        "-" => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s)?;
+            Ok(s)
+        }
+        _ => fs::read_to_string(path)
+    }
+}
+
+/// Lex and print each file, returning whether any error tokens were
+/// printed.
+fn run(options: &Options) -> Result<bool,String> {
+    let mut errors = false;
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    for (i,path) in options.files.iter().enumerate() {
+        let lang = match &options.source {
+            Source::Spec(_) => None,
+            Source::Lang(lang) => Some(*lang),
+            Source::Infer => match infer(path) {
+                Some(lang) => Some(lang),
+                None => return Err(format!("{path}: cannot determine language (use --lang or --spec)"))
+            }
+        };
+        let text = read(path).map_err(|e| format!("{path}: {e}"))?;
+        let mut lexemes = match (&options.source,lang) {
+            (Source::Spec(spec),_) => lex_spec(spec,&text),
+            (_,Some(lang)) => lex_lang(lang,&text),
+            _ => unreachable!()
+        };
+        // Any text left unlexed is an error
+        let n = text.chars().count();
+        let end = lexemes.last().map_or(0,|l| l.region.end);
+        if end < n {
+            lexemes.push(Lexeme{kind: None, region: Region::new(end,n), error: true, skipped: false});
+        }
+        if options.skip {
+            lexemes.retain(|l| !l.skipped);
+        }
+        errors |= lexemes.iter().any(|l| l.error);
+        let input = Input::new(path,&text);
+        let result = match options.format {
+            Format::Table => {
+                if options.files.len() > 1 {
+                    if i > 0 { writeln!(out).map_err(|e| e.to_string())?; }
+                    writeln!(out,"==> {path} <==").map_err(|e| e.to_string())?;
+                }
+                table(&mut out,&input,&lexemes)
+            }
+            Format::Json => json_lines(&mut out,&input,&lexemes),
+            Format::Colour => highlight(&mut out,&input,&lexemes)
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(errors)
+}
+
+fn main() -> ExitCode {
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            // Ignore errors (e.g. a closed pipe)
+            let _ = writeln!(io::stdout(),"{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("lexington: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&options) {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("lexington: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
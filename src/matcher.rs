@@ -1,5 +1,5 @@
 use std::ops::RangeInclusive;
-use crate::util::{ResetIterator};
+use crate::util::{Cursor};
 
 /// Responsible for matching a certain pattern against a data stream
 /// (e.g. a character stream).  This can be used, for example, for
@@ -10,13 +10,16 @@ pub trait Matcher : Sized {
     
     /// Determine how many consecutive characters in the input are
     /// matched by this matcher.
-    fn matches<I:Iterator<Item=Self::Item>>(&self, input: &mut ResetIterator<I>) -> bool;
+    fn matches<C:Cursor<Item=Self::Item>>(&self, input: &mut C) -> bool;
 
     /// Construct a given matcher that matches zero or some items.
     fn zero_or_more(self) -> ZeroOrMore<Self> { ZeroOrMore(self) }
 
     /// Construct a given matcher that matches one or some items.
     fn one_or_more(self) -> OneOrMore<Self> { OneOrMore(self) }
+
+    /// Construct a given matcher that matches zero or one items.
+    fn optional(self) -> Optional<Self> { Optional(self) }
     
     /// Construct a matcher from two matchers.
     fn or<Rhs:Matcher<Item=Self::Item>>(self, other: Rhs) -> Or<Self,Rhs> {
@@ -26,16 +29,24 @@ pub trait Matcher : Sized {
     fn then<Rhs:Matcher<Item=Self::Item>>(self, other: Rhs) -> Then<Self,Rhs> {
         Then(self,other)
     }
+
+    /// Construct a matcher which matches this, provided it is not
+    /// immediately followed by something matching another matcher.
+    /// For example, a keyword which is not followed by an identifier
+    /// character.  The lookahead is never consumed.
+    fn not_followed_by<Rhs:Matcher<Item=Self::Item>>(self, other: Rhs) -> NotFollowedBy<Self,Rhs> {
+        NotFollowedBy(self,other)
+    }
 }
 
 /// A default implementation for any type T.
 impl<T:PartialEq+Copy> Matcher for T {
     type Item = T;
     
-    fn matches<I:Iterator<Item=T>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
         match input.next() {
             Some(t) if self == &t => true,
-            Some(t) => {
+            Some(_) => {
                 input.backup(1);
                 false
             }
@@ -51,10 +62,10 @@ pub struct Any<T:PartialEq,const N:usize>(pub [T;N]);
 impl<T:PartialEq+Copy,const N:usize> Matcher for Any<T,N> {
     type Item = T;
 
-    fn matches<I:Iterator<Item=T>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
         match input.next() {
             Some(t) if self.0.contains(&t) => true,
-            Some(t) => {
+            Some(_) => {
                 input.backup(1);
                 false
             }
@@ -63,6 +74,48 @@ impl<T:PartialEq+Copy,const N:usize> Matcher for Any<T,N> {
     }    
 }
 
+/// A matcher which matches any single item _except_ those from a
+/// fixed list of items.  For example, `NoneOf(['"']).zero_or_more()`
+/// matches the body of a simple string literal.
+#[derive(Clone,Copy,Debug)]
+pub struct NoneOf<T:PartialEq,const N:usize>(pub [T;N]);
+
+impl<T:PartialEq+Copy,const N:usize> Matcher for NoneOf<T,N> {
+    type Item = T;
+
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
+        match input.next() {
+            Some(t) if !self.0.contains(&t) => true,
+            Some(_) => {
+                input.backup(1);
+                false
+            }
+            _ => false
+        }
+    }
+}
+
+/// A matcher which matches a fixed sequence of characters (e.g. a
+/// keyword).
+#[derive(Clone,Copy,Debug)]
+pub struct Text<'a>(pub &'a str);
+
+impl Matcher for Text<'_> {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let mut n = 0;
+        for c in self.0.chars() {
+            match input.next() {
+                Some(d) if c == d => { n += 1; }
+                Some(_) => { input.backup(n+1); return false; }
+                None => { input.backup(n); return false; }
+            }
+        }
+        true
+    }
+}
+
 /// A matching which matches any item within a given range.
 #[derive(Clone,Debug)]
 pub struct Within<T:PartialOrd>(pub RangeInclusive<T>);
@@ -70,10 +123,10 @@ pub struct Within<T:PartialOrd>(pub RangeInclusive<T>);
 impl<T:PartialOrd+Copy> Matcher for Within<T> {
     type Item = T;
 
-    fn matches<I:Iterator<Item=T>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
         match input.next() {
             Some(t) if self.0.contains(&t) => true,
-            Some(t) => {
+            Some(_) => {
                 input.backup(1);
                 false
             }
@@ -82,6 +135,47 @@ impl<T:PartialOrd+Copy> Matcher for Within<T> {
     }
 }
             
+/// A matcher which matches any item within one of a fixed list of
+/// (inclusive) ranges.  For example, `Class([('a','z'),('_','_')])`
+/// matches a lowercase letter or an underscore.
+#[derive(Clone,Copy,Debug)]
+pub struct Class<T:PartialOrd,const N:usize>(pub [(T,T);N]);
+
+impl<T:PartialOrd+Copy,const N:usize> Matcher for Class<T,N> {
+    type Item = T;
+
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
+        match input.next() {
+            Some(t) if self.0.iter().any(|(lo,hi)| *lo <= t && t <= *hi) => true,
+            Some(_) => {
+                input.backup(1);
+                false
+            }
+            _ => false
+        }
+    }
+}
+
+/// A matcher which matches any single item _except_ those within a
+/// fixed list of (inclusive) ranges.
+#[derive(Clone,Copy,Debug)]
+pub struct NotClass<T:PartialOrd,const N:usize>(pub [(T,T);N]);
+
+impl<T:PartialOrd+Copy,const N:usize> Matcher for NotClass<T,N> {
+    type Item = T;
+
+    fn matches<C:Cursor<Item=T>>(&self, input: &mut C) -> bool {
+        match input.next() {
+            Some(t) if !self.0.iter().any(|(lo,hi)| *lo <= t && t <= *hi) => true,
+            Some(_) => {
+                input.backup(1);
+                false
+            }
+            _ => false
+        }
+    }
+}
+
 /// A matcher which matches one or more occurences of a given item.
 #[derive(Clone,Copy,Debug)]
 pub struct Many<M:Matcher>(M);
@@ -89,7 +183,7 @@ pub struct Many<M:Matcher>(M);
 impl<M:Matcher> Matcher for Many<M> {
     type Item = M::Item;
 
-    fn matches<I:Iterator<Item=M::Item>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=M::Item>>(&self, input: &mut C) -> bool {
         // Try the first match        
         let first = self.0.matches(input);
         // Continue whilst more
@@ -106,7 +200,7 @@ pub struct OneOrMore<M:Matcher>(M);
 impl<M:Matcher> Matcher for OneOrMore<M> {
     type Item = M::Item;
 
-    fn matches<I:Iterator<Item=M::Item>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=M::Item>>(&self, input: &mut C) -> bool {
         // Try the first match        
         let first = self.0.matches(input);
         // Continue whilst more
@@ -123,7 +217,7 @@ pub struct ZeroOrMore<M:Matcher>(M);
 impl<M:Matcher> Matcher for ZeroOrMore<M> {
     type Item = M::Item;
 
-    fn matches<I:Iterator<Item=M::Item>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=M::Item>>(&self, input: &mut C) -> bool {
         // Continue whilst more
         while self.0.matches(input) {}
         // Always succeeds
@@ -131,6 +225,20 @@ impl<M:Matcher> Matcher for ZeroOrMore<M> {
     }
 }
 
+/// A matcher which matches zero or one occurences of a given item.
+#[derive(Clone,Copy,Debug)]
+pub struct Optional<M:Matcher>(M);
+
+impl<M:Matcher> Matcher for Optional<M> {
+    type Item = M::Item;
+
+    fn matches<C:Cursor<Item=M::Item>>(&self, input: &mut C) -> bool {
+        self.0.matches(input);
+        // Always succeeds
+        true
+    }
+}
+
 /// A `Matcher` which combines two `Matchers` together, such that it
 /// matches if either matches.
 #[derive(Clone,Copy,Debug)]
@@ -139,18 +247,36 @@ pub struct Or<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>>(Lhs,Rhs);
 impl<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>> Matcher for Or<Lhs,Rhs> {
     type Item = Lhs::Item;
 
-    fn matches<I:Iterator<Item=Lhs::Item>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=Lhs::Item>>(&self, input: &mut C) -> bool {
         self.0.matches(input) || self.1.matches(input)
     }
 }
 
+#[derive(Clone,Copy,Debug)]
+pub struct NotFollowedBy<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>>(Lhs,Rhs);
+
+impl<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>> Matcher for NotFollowedBy<Lhs,Rhs> {
+    type Item = Lhs::Item;
+
+    fn matches<C:Cursor<Item=Lhs::Item>>(&self, input: &mut C) -> bool {
+        let offset = input.offset();
+        if !self.0.matches(input) { return false; }
+        if self.1.matches(input) {
+            // Undo both the lookahead and the match itself
+            input.backup(input.offset() - offset);
+            return false;
+        }
+        true
+    }
+}
+
 #[derive(Clone,Copy,Debug)]
 pub struct Then<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>>(Lhs,Rhs);
 
 impl<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>> Matcher for Then<Lhs,Rhs> {
     type Item = Lhs::Item;
 
-    fn matches<I:Iterator<Item=Lhs::Item>>(&self, input: &mut ResetIterator<I>) -> bool {
+    fn matches<C:Cursor<Item=Lhs::Item>>(&self, input: &mut C) -> bool {
         let offset = input.offset();
         //
         if self.0.matches(input) {
@@ -165,13 +291,14 @@ impl<Lhs:Matcher,Rhs:Matcher<Item=Lhs::Item>> Matcher for Then<Lhs,Rhs> {
     }
 }
 
-/// =============================================================================
-/// Tests
-/// =============================================================================
+// =============================================================================
+// Tests
+// =============================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::{Matcher,ResetIterator};
+    use crate::util::{ResetIterator,SliceCursor,StrCursor};
+    use super::{Class,Matcher,NoneOf,NotClass,Text,Within};
 
     #[test]
     fn test_01() {
@@ -219,5 +346,75 @@ mod tests {
         let matcher = '('.or(')').one_or_more();
         assert!(matcher.matches(&mut input));
         assert_eq!(input.next(),None);        
-    }       
+    }
+
+    #[test]
+    fn test_07() {
+        let mut input = SliceCursor::new(&[1,1,2]);
+        let matcher = 1.one_or_more().then(2);
+        assert!(matcher.matches(&mut input));
+        assert_eq!(input.next(),None);
+    }
+
+    #[test]
+    fn test_08() {
+        let mut input = StrCursor::new("λλx");
+        let matcher = 'λ'.one_or_more().then('y');
+        assert!(!matcher.matches(&mut input));
+        assert_eq!(input.byte_offset(),0);
+        assert_eq!(input.next(),Some('λ'));
+    }
+
+    #[test]
+    fn test_09() {
+        let mut input = StrCursor::new("ab\"c");
+        let matcher = NoneOf(['"','\\']).zero_or_more();
+        assert!(matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('"'));
+        assert!(!NoneOf(['c']).matches(&mut input));
+        assert_eq!(input.next(),Some('c'));
+        assert!(!NoneOf(['c']).matches(&mut input));
+    }
+
+    #[test]
+    fn test_10() {
+        let mut input = StrCursor::new("-1");
+        let matcher = '-'.optional().then('1');
+        assert!(matcher.matches(&mut input));
+        let mut input = StrCursor::new("1");
+        assert!(matcher.matches(&mut input));
+        let mut input = StrCursor::new("-2");
+        assert!(!matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('-'));
+    }
+
+    #[test]
+    fn test_11() {
+        let matcher = Text("null").not_followed_by(Within('a'..='z'));
+        let mut input = StrCursor::new("null,");
+        assert!(matcher.matches(&mut input));
+        assert_eq!(input.next(),Some(','));
+        let mut input = StrCursor::new("nullable");
+        assert!(!matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('n'));
+        let mut input = StrCursor::new("nul");
+        assert!(!matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('n'));
+        let mut input = StrCursor::new("null");
+        assert!(matcher.matches(&mut input));
+    }
+
+    #[test]
+    fn test_12() {
+        let matcher = Class([('a','z'),('_','_')]).one_or_more();
+        let mut input = StrCursor::new("ab_c1");
+        assert!(matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('1'));
+        let mut input = StrCursor::new("A");
+        assert!(!matcher.matches(&mut input));
+        assert_eq!(input.next(),Some('A'));
+        let mut input = StrCursor::new("ab\n");
+        assert!(NotClass([('\n','\n'),('0','9')]).zero_or_more().matches(&mut input));
+        assert_eq!(input.next(),Some('\n'));
+    }
 }
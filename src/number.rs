@@ -0,0 +1,396 @@
+use std::fmt;
+use crate::{Any,Matcher,Text};
+use crate::util::{Cursor,Region};
+
+// =============================================================================
+// Matchers
+// =============================================================================
+
+/// Matches one or more digits in a given radix, where adjacent digits
+/// may optionally be separated by a given character (e.g. `1_000` or
+/// `1'000`).  A separator cannot begin or end the sequence.
+#[derive(Clone,Copy,Debug)]
+pub struct Digits(pub u32, pub Option<char>);
+
+impl Matcher for Digits {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let mut n = 0;
+        loop {
+            match input.next() {
+                Some(c) if c.is_digit(self.0) => { n += 1; }
+                Some(c) if n > 0 && Some(c) == self.1 => {
+                    // A separator must be followed by a digit
+                    match input.next() {
+                        Some(d) if d.is_digit(self.0) => { n += 1; }
+                        Some(_) => { input.backup(2); return true; }
+                        None => { input.backup(1); return true; }
+                    }
+                }
+                Some(_) => { input.backup(1); return n > 0; }
+                None => { return n > 0; }
+            }
+        }
+    }
+}
+
+/// Matches the longest of a given set of suffixes (e.g. `u8` or
+/// `f32`).
+#[derive(Clone,Copy,Debug)]
+pub struct Suffix<'a>(pub &'a [&'a str]);
+
+impl Matcher for Suffix<'_> {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        let start = input.offset();
+        let mut longest = None;
+        for s in self.0 {
+            if Text(s).matches(input) {
+                let n = input.offset() - start;
+                if longest.is_none_or(|m| n > m) { longest = Some(n); }
+                input.backup(n);
+            }
+        }
+        match longest {
+            Some(n) => { input.advance(n); true }
+            None => false
+        }
+    }
+}
+
+/// Match a decimal integer, such as `123` or `1_000`.
+pub fn decimal(separator: Option<char>) -> Digits {
+    Digits(10,separator)
+}
+
+/// Match a hexadecimal integer, such as `0xFF`.
+pub fn hexadecimal(separator: Option<char>) -> impl Matcher<Item=char> {
+    '0'.then(Any(['x','X'])).then(Digits(16,separator))
+}
+
+/// Match an octal integer, such as `0o755`.
+pub fn octal(separator: Option<char>) -> impl Matcher<Item=char> {
+    '0'.then(Any(['o','O'])).then(Digits(8,separator))
+}
+
+/// Match a binary integer, such as `0b1010`.
+pub fn binary(separator: Option<char>) -> impl Matcher<Item=char> {
+    '0'.then(Any(['b','B'])).then(Digits(2,separator))
+}
+
+/// Match an integer in any of the above forms.
+pub fn integer(separator: Option<char>) -> impl Matcher<Item=char> {
+    hexadecimal(separator).or(octal(separator)).or(binary(separator)).or(decimal(separator))
+}
+
+/// Match a decimal exponent, such as `e10` or `E-3`.
+pub fn exponent(separator: Option<char>) -> impl Matcher<Item=char> {
+    Any(['e','E']).then(Any(['+','-']).optional()).then(decimal(separator))
+}
+
+/// Match a decimal floating-point number, which must have either a
+/// fractional part or an exponent (e.g. `1.5`, `1.`, `.5` or `1e10`).
+pub fn float(separator: Option<char>) -> impl Matcher<Item=char> {
+    let fraction = decimal(separator).then('.').then(decimal(separator).optional());
+    fraction.or('.'.then(decimal(separator))).then(exponent(separator).optional())
+        .or(decimal(separator).then(exponent(separator)))
+}
+
+/// Match a hexadecimal floating-point number, which must have a binary
+/// exponent (e.g. `0x1.8p3` or `0x.8P-1`).
+pub fn hex_float(separator: Option<char>) -> impl Matcher<Item=char> {
+    let mantissa = Digits(16,separator).then('.'.then(Digits(16,separator).optional()).optional())
+        .or('.'.then(Digits(16,separator)));
+    '0'.then(Any(['x','X'])).then(mantissa).then(Any(['p','P'])).then(Any(['+','-']).optional()).then(decimal(separator))
+}
+
+/// Match a number in any of the above forms, preferring floats over
+/// integers.
+pub fn number(separator: Option<char>) -> impl Matcher<Item=char> {
+    hex_float(separator).or(float(separator)).or(integer(separator))
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Identifies an error arising whilst converting a numeric literal
+/// into a value.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Error {
+    /// The literal is not a number (e.g. `0x` or `1.2.3`).
+    Malformed(Region),
+    /// The literal does not fit within the target type.
+    Overflow(Region)
+}
+
+impl Error {
+    /// Get the region where this error arose.
+    pub fn region(&self) -> Region {
+        match self {
+            Error::Malformed(r) => *r,
+            Error::Overflow(r) => *r
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Malformed(_) => write!(f,"malformed number"),
+            Error::Overflow(_) => write!(f,"number out of range")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// =============================================================================
+// Values
+// =============================================================================
+
+// The following accept the text of a literal (without any suffix),
+// along with the region of its token for reporting errors.  A leading
+// sign is permitted, as are the prefixes `0x`, `0o` and `0b`.  Digit
+// separators (`_` or `'`) are ignored.  Note that a leading zero does
+// not indicate octal (i.e. `017` is seventeen).
+
+/// Convert an integer literal into an `i64`.
+pub fn to_i64(text: &str, region: Region) -> Result<i64,Error> {
+    let (negative,radix,digits) = split(text);
+    let m = magnitude(digits,radix,region)?;
+    match negative {
+        false => i64::try_from(m).map_err(|_| Error::Overflow(region)),
+        true if m == 1u128 << 63 => Ok(i64::MIN),
+        true => i64::try_from(m).map(|i| -i).map_err(|_| Error::Overflow(region))
+    }
+}
+
+/// Convert an (unsigned) integer literal into a `u128`.
+pub fn to_u128(text: &str, region: Region) -> Result<u128,Error> {
+    match split(text) {
+        (false,radix,digits) => magnitude(digits,radix,region),
+        (true,radix,digits) => {
+            // Only negative zero is permitted
+            match magnitude(digits,radix,region)? {
+                0 => Ok(0),
+                _ => Err(Error::Overflow(region))
+            }
+        }
+    }
+}
+
+/// Convert an integer literal of any size into a `BigInt`.
+pub fn to_big(text: &str, region: Region) -> Result<BigInt,Error> {
+    let (negative,radix,digits) = split(text);
+    let mut n = big(digits,radix,region)?;
+    n.negative = negative && !n.is_zero();
+    Ok(n)
+}
+
+/// Convert a numeric literal (either integer or floating-point) into
+/// an `f64`, which is rounded to the nearest representable value.
+pub fn to_f64(text: &str, region: Region) -> Result<f64,Error> {
+    let (negative,radix,digits) = split(text);
+    let value = match radix {
+        16 if digits.contains(['p','P']) => hex_float_value(digits,region)?,
+        10 => {
+            let s : String = digits.chars().filter(|c| !is_separator(*c)).collect();
+            let valid = s.starts_with(|c:char| c.is_ascii_digit() || c == '.')
+                && s.chars().all(|c| c.is_ascii_digit() || matches!(c,'.'|'e'|'E'|'+'|'-'));
+            match s.parse::<f64>() {
+                Ok(x) if valid => x,
+                _ => { return Err(Error::Malformed(region)); }
+            }
+        }
+        _ => big(digits,radix,region)?.to_f64()
+    };
+    match value.is_finite() {
+        true if negative => Ok(-value),
+        true => Ok(value),
+        false => Err(Error::Overflow(region))
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c == '_' || c == '\''
+}
+
+/// Split a literal into its sign, radix and remaining digits.
+fn split(text: &str) -> (bool,u32,&str) {
+    let (negative,text) = match text.strip_prefix('-') {
+        Some(t) => (true,t),
+        None => (false,text.strip_prefix('+').unwrap_or(text))
+    };
+    let radix = match text.get(..2) {
+        Some("0x"|"0X") => 16,
+        Some("0o"|"0O") => 8,
+        Some("0b"|"0B") => 2,
+        _ => { return (negative,10,text); }
+    };
+    (negative,radix,&text[2..])
+}
+
+/// Get the value of each digit, ignoring separators.
+fn digit_values(digits: &str, radix: u32, region: Region) -> Result<Vec<u32>,Error> {
+    let ds : Option<Vec<u32>> = digits.chars().filter(|c| !is_separator(*c)).map(|c| c.to_digit(radix)).collect();
+    match ds {
+        Some(ds) if !ds.is_empty() => Ok(ds),
+        _ => Err(Error::Malformed(region))
+    }
+}
+
+fn magnitude(digits: &str, radix: u32, region: Region) -> Result<u128,Error> {
+    let mut m : u128 = 0;
+    for d in digit_values(digits,radix,region)? {
+        m = m.checked_mul(radix as u128).and_then(|m| m.checked_add(d as u128)).ok_or(Error::Overflow(region))?;
+    }
+    Ok(m)
+}
+
+fn big(digits: &str, radix: u32, region: Region) -> Result<BigInt,Error> {
+    let mut n = BigInt::default();
+    for d in digit_values(digits,radix,region)? {
+        n.mul_add(radix,d);
+    }
+    Ok(n)
+}
+
+/// Compute the value of a hexadecimal float (without its `0x` prefix)
+/// such as `1.8p3`.
+fn hex_float_value(text: &str, region: Region) -> Result<f64,Error> {
+    let (mantissa,exponent) = text.split_once(['p','P']).ok_or(Error::Malformed(region))?;
+    let (int,frac) = mantissa.split_once('.').unwrap_or((mantissa,""));
+    let exponent = exponent.strip_prefix('+').unwrap_or(exponent);
+    let mut exponent : i64 = exponent.chars().filter(|c| !is_separator(*c)).collect::<String>()
+        .parse().map_err(|_| Error::Malformed(region))?;
+    if int.is_empty() && frac.is_empty() { return Err(Error::Malformed(region)); }
+    // Accumulate the mantissa, dropping digits beyond 128 bits
+    let mut m : u128 = 0;
+    for c in int.chars().chain(frac.chars()).filter(|c| !is_separator(*c)) {
+        let d = c.to_digit(16).ok_or(Error::Malformed(region))?;
+        if m >> 124 == 0 {
+            m = (m << 4) | d as u128;
+        } else {
+            exponent += 4;
+        }
+    }
+    exponent -= 4 * frac.chars().filter(|c| !is_separator(*c)).count() as i64;
+    // Normalise to at most 64 significant bits, keeping a sticky bit for
+    // rounding.
+    let shift = (128 - m.leading_zeros()).saturating_sub(64);
+    if shift > 0 {
+        let sticky = (m & ((1 << shift) - 1) != 0) as u128;
+        m = (m >> shift) | sticky;
+        exponent += shift as i64;
+    }
+    let exponent = i32::try_from(exponent).map_err(|_| Error::Overflow(region))?;
+    // Scale in two steps, since 2^exponent alone may under or overflow
+    // when the result does not.
+    let half = exponent / 2;
+    Ok(m as f64 * 2f64.powi(half) * 2f64.powi(exponent - half))
+}
+
+// =============================================================================
+// Big Integers
+// =============================================================================
+
+/// An integer of arbitrary size, represented as a sign and a
+/// magnitude.  The magnitude is stored as base 2^32 digits (least
+/// significant first) without trailing zeros, such that zero has no
+/// digits.
+#[derive(Clone,Debug,Default,PartialEq,Eq,Hash)]
+pub struct BigInt {
+    pub negative: bool,
+    pub digits: Vec<u32>
+}
+
+impl BigInt {
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Convert into an `i64` (if it fits).
+    pub fn to_i64(&self) -> Option<i64> {
+        let m = self.to_u128_magnitude()?;
+        match self.negative {
+            false => i64::try_from(m).ok(),
+            true if m == 1u128 << 63 => Some(i64::MIN),
+            true => i64::try_from(m).ok().map(|i| -i)
+        }
+    }
+
+    /// Convert into a `u128` (if it fits).
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.negative {
+            true => None,
+            false => self.to_u128_magnitude()
+        }
+    }
+
+    /// Convert into the nearest `f64`, which may be infinite.
+    pub fn to_f64(&self) -> f64 {
+        let mut x = 0.0;
+        for d in self.digits.iter().rev() {
+            x = x * 4294967296.0 + *d as f64;
+        }
+        match self.negative {
+            true => -x,
+            false => x
+        }
+    }
+
+    fn to_u128_magnitude(&self) -> Option<u128> {
+        if self.digits.len() > 4 { return None; }
+        Some(self.digits.iter().rev().fold(0,|m,d| (m << 32) | *d as u128))
+    }
+
+    /// Compute `self * m + a` on the magnitude.
+    fn mul_add(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for d in self.digits.iter_mut() {
+            let x = (*d as u64) * (m as u64) + carry;
+            *d = x as u32;
+            carry = x >> 32;
+        }
+        if carry != 0 { self.digits.push(carry as u32); }
+    }
+
+    /// Compute `self / d` on the magnitude, returning the remainder.
+    fn div_rem(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        for x in self.digits.iter_mut().rev() {
+            let y = (rem << 32) | *x as u64;
+            *x = (y / d as u64) as u32;
+            rem = y % d as u64;
+        }
+        while self.digits.last() == Some(&0) { self.digits.pop(); }
+        rem as u32
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(i: i64) -> Self {
+        let m = i.unsigned_abs();
+        let digits = [m as u32,(m >> 32) as u32].into_iter().collect::<Vec<_>>();
+        let mut n = BigInt{negative: i < 0, digits};
+        while n.digits.last() == Some(&0) { n.digits.pop(); }
+        n
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() { return write!(f,"0"); }
+        // Extract base 10^9 chunks, least significant first
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() { chunks.push(n.div_rem(1_000_000_000)); }
+        if self.negative { write!(f,"-")?; }
+        write!(f,"{}",chunks.pop().unwrap())?;
+        for c in chunks.iter().rev() { write!(f,"{c:09}")?; }
+        Ok(())
+    }
+}
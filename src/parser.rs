@@ -0,0 +1,277 @@
+use crate::{Checkpoint,Matcher,SyntaxError,Token,TokenStream};
+use crate::util::Region;
+
+/// Responsible for parsing a value (e.g. a node of an abstract syntax
+/// tree) from a stream of tokens, where `T` is the kind of token.
+/// This plays the same role for tokens as `Matcher` does for
+/// characters, except that parsers produce values rather than just
+/// succeeding or failing.
+///
+/// Parsers which fail without consuming any tokens leave the stream
+/// unchanged, allowing alternatives to be tried.  Parsers which fail
+/// after consuming tokens are treated as errors: combinators such as
+/// `or()`, `many()` and `optional()` commit to a parser once it has
+/// consumed a token, rather than backtracking.  Where more lookahead
+/// is needed, a parser can be written as a function which inspects
+/// the stream (e.g. with `checkpoint()` and `rewind()`) directly.
+pub trait Parser<T:Copy+PartialEq> : Sized {
+    /// Type of value produced by this parser.
+    type Output;
+
+    /// Attempt to parse a value from a given stream of tokens.
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>>;
+
+    /// Transform the value produced by this parser.
+    fn map<U,F:Fn(Self::Output)->U>(self, f: F) -> Map<Self,F> {
+        Map(self,f)
+    }
+
+    /// Parse this followed by another, producing both values.
+    fn then<P:Parser<T>>(self, other: P) -> Then<Self,P> {
+        Then(self,other)
+    }
+
+    /// Parse either this or (if that fails without consuming any
+    /// tokens) another.
+    fn or<P:Parser<T,Output=Self::Output>>(self, other: P) -> Or<Self,P> {
+        Or(self,other)
+    }
+
+    /// Parse zero or more occurrences of this.
+    fn many(self) -> Many<Self> {
+        Many(self)
+    }
+
+    /// Parse zero or one occurrences of this.
+    fn optional(self) -> Optional<Self> {
+        Optional(self)
+    }
+
+    /// Parse zero or more occurrences of this, separated by another
+    /// (e.g. a comma).
+    fn separated_by<P:Parser<T>>(self, separator: P) -> SeparatedBy<Self,P> {
+        SeparatedBy(self,separator)
+    }
+
+    /// Parse this between an opening and closing delimiter (e.g.
+    /// braces), producing only the value of this.
+    fn delimited<L:Parser<T>,R:Parser<T>>(self, open: L, close: R) -> Delimited<Self,L,R> {
+        Delimited(self,open,close)
+    }
+
+    /// Parse this, additionally producing the region covered by the
+    /// tokens consumed.
+    fn spanned(self) -> Spanned<Self> {
+        Spanned(self)
+    }
+}
+
+/// Any suitable function is a parser.  This allows recursive grammars
+/// to be written as mutually recursive functions.
+impl<T:Copy+PartialEq,O,F:Fn(&mut TokenStream<T>)->Result<O,SyntaxError<T>>> Parser<T> for F {
+    type Output = O;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<O,SyntaxError<T>> {
+        self(input)
+    }
+}
+
+/// Determine whether an input stream has moved on from a checkpoint.
+fn consumed<T:Copy+PartialEq>(input: &TokenStream<T>, checkpoint: Checkpoint) -> bool {
+    input.checkpoint() != checkpoint
+}
+
+// =============================================================================
+// Primitives
+// =============================================================================
+
+/// A parser which accepts a single token of a given kind.
+#[derive(Clone,Copy,Debug)]
+pub struct Tok<T>(pub T);
+
+impl<T:Copy+PartialEq> Parser<T> for Tok<T> {
+    type Output = Token<T>;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Token<T>,SyntaxError<T>> {
+        input.expect(self.0)
+    }
+}
+
+/// A parser which accepts a sequence of tokens whose kinds are
+/// accepted by a given `Matcher`, producing the tokens matched.  For
+/// example, `Match(Ident.then(Colon))`.  Since a `Matcher` does not
+/// report which kinds it tests for, errors from this identify only the
+/// token `found` (along with any kinds tested for by other parsers at
+/// the same position).
+#[derive(Clone,Copy,Debug)]
+pub struct Match<M>(pub M);
+
+impl<T:Copy+PartialEq,M:Matcher<Item=T>> Parser<T> for Match<M> {
+    type Output = Vec<Token<T>>;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Vec<Token<T>>,SyntaxError<T>> {
+        let checkpoint = input.checkpoint();
+        match input.matches(&self.0) {
+            true => Ok(input.since(checkpoint).iter().filter(|t| !input.is_trivia(t.kind)).copied().collect()),
+            false => Err(input.error())
+        }
+    }
+}
+
+// =============================================================================
+// Combinators
+// =============================================================================
+
+#[derive(Clone,Copy,Debug)]
+pub struct Map<P,F>(P,F);
+
+impl<T:Copy+PartialEq,U,P:Parser<T>,F:Fn(P::Output)->U> Parser<T> for Map<P,F> {
+    type Output = U;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<U,SyntaxError<T>> {
+        self.0.parse(input).map(&self.1)
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Then<A,B>(A,B);
+
+impl<T:Copy+PartialEq,A:Parser<T>,B:Parser<T>> Parser<T> for Then<A,B> {
+    type Output = (A::Output,B::Output);
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let a = self.0.parse(input)?;
+        let b = self.1.parse(input)?;
+        Ok((a,b))
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Or<A,B>(A,B);
+
+impl<T:Copy+PartialEq,A:Parser<T>,B:Parser<T,Output=A::Output>> Parser<T> for Or<A,B> {
+    type Output = A::Output;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let checkpoint = input.checkpoint();
+        let e1 = match self.0.parse(input) {
+            Ok(v) => { return Ok(v); }
+            // Committed to the first alternative
+            Err(e) if consumed(input,checkpoint) => { return Err(e); }
+            Err(e) => e
+        };
+        match self.1.parse(input) {
+            Ok(v) => Ok(v),
+            Err(e2) => Err(furthest(e1,e2))
+        }
+    }
+}
+
+/// Choose between two errors, preferring whichever arose furthest
+/// into the input.  Errors at the same position are merged.
+fn furthest<T:PartialEq>(mut e1: SyntaxError<T>, e2: SyntaxError<T>) -> SyntaxError<T> {
+    if e1.region.start > e2.region.start {
+        e1
+    } else if e1.region.start < e2.region.start {
+        e2
+    } else {
+        for kind in e2.expected {
+            if !e1.expected.contains(&kind) { e1.expected.push(kind); }
+        }
+        e1
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Many<P>(P);
+
+impl<T:Copy+PartialEq,P:Parser<T>> Parser<T> for Many<P> {
+    type Output = Vec<P::Output>;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let mut items = Vec::new();
+        loop {
+            let checkpoint = input.checkpoint();
+            match self.0.parse(input) {
+                Ok(v) => {
+                    items.push(v);
+                    // Guard against parsers which consume nothing
+                    if !consumed(input,checkpoint) { return Ok(items); }
+                }
+                Err(e) if consumed(input,checkpoint) => { return Err(e); }
+                Err(_) => { return Ok(items); }
+            }
+        }
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Optional<P>(P);
+
+impl<T:Copy+PartialEq,P:Parser<T>> Parser<T> for Optional<P> {
+    type Output = Option<P::Output>;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let checkpoint = input.checkpoint();
+        match self.0.parse(input) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if consumed(input,checkpoint) => Err(e),
+            Err(_) => Ok(None)
+        }
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct SeparatedBy<P,S>(P,S);
+
+impl<T:Copy+PartialEq,P:Parser<T>,S:Parser<T>> Parser<T> for SeparatedBy<P,S> {
+    type Output = Vec<P::Output>;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let mut items = Vec::new();
+        let checkpoint = input.checkpoint();
+        match self.0.parse(input) {
+            Ok(v) => items.push(v),
+            Err(e) if consumed(input,checkpoint) => { return Err(e); }
+            Err(_) => { return Ok(items); }
+        }
+        loop {
+            let checkpoint = input.checkpoint();
+            match self.1.parse(input) {
+                Ok(_) => items.push(self.0.parse(input)?),
+                Err(e) if consumed(input,checkpoint) => { return Err(e); }
+                Err(_) => { return Ok(items); }
+            }
+        }
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Delimited<P,L,R>(P,L,R);
+
+impl<T:Copy+PartialEq,P:Parser<T>,L:Parser<T>,R:Parser<T>> Parser<T> for Delimited<P,L,R> {
+    type Output = P::Output;
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        self.1.parse(input)?;
+        let v = self.0.parse(input)?;
+        self.2.parse(input)?;
+        Ok(v)
+    }
+}
+
+#[derive(Clone,Copy,Debug)]
+pub struct Spanned<P>(P);
+
+impl<T:Copy+PartialEq,P:Parser<T>> Parser<T> for Spanned<P> {
+    type Output = (P::Output,Region);
+
+    fn parse(&self, input: &mut TokenStream<T>) -> Result<Self::Output,SyntaxError<T>> {
+        let checkpoint = input.checkpoint();
+        let v = self.0.parse(input)?;
+        // Determine region covered by consumed tokens, ignoring any
+        // leading trivia.
+        let region = input.region_since(checkpoint);
+        Ok((v,region))
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{SyntaxError,Token,TokenStream};
+use crate::parser::Parser;
+use crate::util::Region;
+
+/// Determines how a sequence of infix operators with the same binding
+/// power are grouped.  For example, `a - b - c` is `(a - b) - c` as
+/// subtraction is left associative, whilst `a ^ b ^ c` is `a ^ (b ^
+/// c)` as exponentiation is right associative.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Assoc {
+    Left,
+    Right
+}
+
+type PrefixFn<'a,T,N> = Box<dyn Fn(Token<T>,N,Region)->N+'a>;
+type InfixFn<'a,T,N> = Box<dyn Fn(N,Token<T>,N,Region)->N+'a>;
+type PostfixFn<'a,T,N> = Box<dyn Fn(N,Token<T>,Region)->N+'a>;
+
+/// A Pratt (or _precedence climbing_) parser for expressions, driven by
+/// a table of prefix, infix and postfix operators keyed on token kind.
+/// Each operator has a _binding power_, where operators with higher
+/// binding power bind more tightly (e.g. `*` should have a higher
+/// binding power than `+`).  When an operator is parsed, its callback
+/// is given the operator token, its operand(s) and the region covered
+/// by the entire expression, and produces the resulting node of type
+/// `N`.  Operands which are not themselves operator expressions (e.g.
+/// numbers, variables or bracketed expressions) are parsed by a
+/// separate _atom_ parser.  Callbacks may borrow data (e.g. the source
+/// text, for building nodes from the text of tokens) for lifetime
+/// `'a`.
+pub struct Pratt<'a,T,N> {
+    prefix: Vec<(T,u32,PrefixFn<'a,T,N>)>,
+    infix: Vec<(T,u32,Assoc,InfixFn<'a,T,N>)>,
+    postfix: Vec<(T,u32,PostfixFn<'a,T,N>)>
+}
+
+impl<T:Copy+PartialEq,N> Default for Pratt<'_,T,N> {
+    fn default() -> Self {
+        Self{prefix: Vec::new(), infix: Vec::new(), postfix: Vec::new()}
+    }
+}
+
+impl<'a,T:Copy+PartialEq,N> Pratt<'a,T,N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a prefix operator (e.g. unary minus) with a given binding
+    /// power.
+    pub fn prefix<F:Fn(Token<T>,N,Region)->N+'a>(mut self, kind: T, power: u32, f: F) -> Self {
+        self.prefix.push((kind,power,Box::new(f)));
+        self
+    }
+
+    /// Add an infix operator (e.g. addition) with a given binding power
+    /// and associativity.
+    pub fn infix<F:Fn(N,Token<T>,N,Region)->N+'a>(mut self, kind: T, power: u32, assoc: Assoc, f: F) -> Self {
+        self.infix.push((kind,power,assoc,Box::new(f)));
+        self
+    }
+
+    /// Add a postfix operator (e.g. factorial) with a given binding
+    /// power.
+    pub fn postfix<F:Fn(N,Token<T>,Region)->N+'a>(mut self, kind: T, power: u32, f: F) -> Self {
+        self.postfix.push((kind,power,Box::new(f)));
+        self
+    }
+
+    /// Parse an expression from a given stream of tokens, using a
+    /// given parser for atoms.  Parsing stops at the first token which
+    /// is not an operator that can continue the expression, leaving
+    /// that token in the stream.
+    pub fn parse<A:Parser<T,Output=N>>(&self, input: &mut TokenStream<T>, atom: &A) -> Result<N,SyntaxError<T>> {
+        self.parse_spanned(input,atom).map(|(n,_)| n)
+    }
+
+    /// Parse an expression as for `parse()`, additionally producing the
+    /// region it covers.
+    pub fn parse_spanned<A:Parser<T,Output=N>>(&self, input: &mut TokenStream<T>, atom: &A) -> Result<(N,Region),SyntaxError<T>> {
+        self.expr(input,atom,0)
+    }
+
+    /// Parse an expression containing only operators whose (left)
+    /// binding power is at least `min`.
+    fn expr<A:Parser<T,Output=N>>(&self, input: &mut TokenStream<T>, atom: &A, min: u32) -> Result<(N,Region),SyntaxError<T>> {
+        let (mut lhs, mut region) = match self.find_prefix(input) {
+            Some(i) => {
+                let (_,power,f) = &self.prefix[i];
+                let op = input.next().unwrap();
+                let (rhs,r) = self.expr(input,atom,2*power+1)?;
+                let region = op.region.cover(r);
+                (f(op,rhs,region),region)
+            }
+            None => {
+                let checkpoint = input.checkpoint();
+                let n = atom.parse(input)?;
+                (n,input.region_since(checkpoint))
+            }
+        };
+        loop {
+            if let Some(i) = self.find_postfix(input) {
+                let (_,power,f) = &self.postfix[i];
+                if 2*power < min { break; }
+                let op = input.next().unwrap();
+                region = region.cover(op.region);
+                lhs = f(lhs,op,region);
+            } else if let Some(i) = self.find_infix(input) {
+                let (_,power,assoc,f) = &self.infix[i];
+                // Left associative operators bind slightly more
+                // tightly on their right, and vice versa.
+                let (left,right) = match assoc {
+                    Assoc::Left => (2*power,2*power+1),
+                    Assoc::Right => (2*power+1,2*power)
+                };
+                if left < min { break; }
+                let op = input.next().unwrap();
+                let (rhs,r) = self.expr(input,atom,right)?;
+                region = region.cover(r);
+                lhs = f(lhs,op,rhs,region);
+            } else {
+                break;
+            }
+        }
+        Ok((lhs,region))
+    }
+
+    fn find_prefix(&self, input: &mut TokenStream<T>) -> Option<usize> {
+        find(input,self.prefix.iter().map(|e| e.0))
+    }
+
+    fn find_infix(&self, input: &mut TokenStream<T>) -> Option<usize> {
+        find(input,self.infix.iter().map(|e| e.0))
+    }
+
+    fn find_postfix(&self, input: &mut TokenStream<T>) -> Option<usize> {
+        find(input,self.postfix.iter().map(|e| e.0))
+    }
+}
+
+/// Find the index of the operator matching the next token (if any).
+/// Every operator kind is tested with `at()`, such that all are
+/// reported as expected should an error arise at this position.
+fn find<T:Copy+PartialEq,I:Iterator<Item=T>>(input: &mut TokenStream<T>, kinds: I) -> Option<usize> {
+    let mut index = None;
+    for (i,kind) in kinds.enumerate() {
+        if input.at(kind) && index.is_none() { index = Some(i); }
+    }
+    index
+}
@@ -1,11 +1,11 @@
 use super::Matcher;
-use crate::util::{ResetIterator};
+use crate::util::{Cursor};
 
 pub trait Scanner {
     type Item;
     type Token;
     
-    fn scan<I:Iterator<Item=Self::Item>>(&self,input: &mut ResetIterator<I>) -> Option<Self::Token>;
+    fn scan<C:Cursor<Item=Self::Item>>(&self,input: &mut C) -> Option<Self::Token>;
 
     /// Combine two scanners together.
     fn or<Rhs:Scanner>(self, other: Rhs) -> (Self,Rhs) where Self:Sized { (self,other) }
@@ -15,7 +15,7 @@ impl<A:Scanner,B:Scanner<Item=A::Item,Token=A::Token>> Scanner for (A,B) {
     type Item = A::Item;
     type Token = A::Token;
     
-    fn scan<I:Iterator<Item=Self::Item>>(&self,input: &mut ResetIterator<I>) -> Option<Self::Token> {
+    fn scan<C:Cursor<Item=Self::Item>>(&self,input: &mut C) -> Option<Self::Token> {
         match self.0.scan(input) {
             Some(t) => Some(t),
             None => self.1.scan(input)
@@ -23,6 +23,16 @@ impl<A:Scanner,B:Scanner<Item=A::Item,Token=A::Token>> Scanner for (A,B) {
     }
 }
 
+/// A reference to a scanner is itself a scanner.
+impl<S:Scanner> Scanner for &S {
+    type Item = S::Item;
+    type Token = S::Token;
+
+    fn scan<C:Cursor<Item=Self::Item>>(&self,input: &mut C) -> Option<Self::Token> {
+        (*self).scan(input)
+    }
+}
+
 /// A scanner which matches a single item with a given token.  This
 /// is one of the fundamental building blocks for most scanners.
 pub struct Unit<M:Matcher,T>(pub M, pub T);
@@ -31,7 +41,7 @@ impl<M:Matcher,T:Copy> Scanner for Unit<M,T> {
     type Item = M::Item;
     type Token = T;
 
-    fn scan<I:Iterator<Item=Self::Item>>(&self,input: &mut ResetIterator<I>) -> Option<Self::Token> {
+    fn scan<C:Cursor<Item=Self::Item>>(&self,input: &mut C) -> Option<Self::Token> {
         match self.0.matches(input) {
             false => None,
             true => Some(self.1)
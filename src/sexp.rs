@@ -0,0 +1,314 @@
+use std::fmt;
+use crate::{Any,Lexer,Matcher,NoneOf,Scanner,SyntaxError,Token,TokenKind,TokenStream,Unit};
+use crate::tree::Lexemes;
+use crate::util::Region;
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+/// Identifies the kinds of token making up an S-expression.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    WhiteSpace,
+    /// A line comment, such as `; hello`.
+    Comment,
+    LeftBrace,
+    RightBrace,
+    /// A quote, as in `'x`.
+    Quote,
+    /// A string literal, such as `"hello\n"`.
+    String,
+    /// A string literal which runs to the end of the input.
+    UnterminatedString,
+    /// A symbol or number, such as `x`, `-1` or `1.5e3`.
+    Atom
+}
+
+impl TokenKind for Kind {
+    fn is_error(self) -> bool {
+        matches!(self,Kind::UnterminatedString)
+    }
+
+    fn is_trivia(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::Comment)
+    }
+}
+
+/// Characters which cannot appear within an atom.
+const DELIMITERS : [char;9] = [' ','\t','\n','\r','(',')','\'','"',';'];
+
+/// Construct a scanner for S-expressions.
+pub fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    // [ \t\n\r]+
+    let whitespace = Any([' ','\t','\n','\r']).one_or_more();
+    // ;[^\n]*
+    let comment = ';'.then(NoneOf(['\n']).zero_or_more());
+    // "([^"\\]|\\.)*
+    let body = NoneOf(['"','\\']).or('\\'.then(NoneOf([]))).zero_or_more();
+    let string = '"'.then(body).then('"');
+    let atom = NoneOf(DELIMITERS).one_or_more();
+    Unit(whitespace,Kind::WhiteSpace)
+        .or(Unit(comment,Kind::Comment))
+        .or(Unit('(',Kind::LeftBrace))
+        .or(Unit(')',Kind::RightBrace))
+        .or(Unit('\'',Kind::Quote))
+        .or(Unit(string,Kind::String))
+        .or(Unit('"'.then(body).then('\\'.zero_or_more()),Kind::UnterminatedString))
+        .or(Unit(atom,Kind::Atom))
+}
+
+/// Split some input into S-expression tokens, including whitespace and
+/// comments.
+pub fn lex(input: &str) -> Vec<Token<Kind>> {
+    Lexer::new(input.chars(),scanner()).collect()
+}
+
+// =============================================================================
+// S-Expressions
+// =============================================================================
+
+/// The value of an S-expression.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Value {
+    Symbol(String),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    /// A quoted expression, such as `'(1 2)`.
+    Quote(Box<SExp>),
+    List(Vec<SExp>)
+}
+
+/// An S-expression along with the region of the source it was parsed
+/// from.
+#[derive(Clone,Debug,PartialEq)]
+pub struct SExp {
+    pub value: Value,
+    pub region: Region
+}
+
+impl SExp {
+    pub fn new(value: Value, region: Region) -> Self {
+        Self{value,region}
+    }
+
+    /// Get the name of this symbol (if it is one).
+    pub fn as_symbol(&self) -> Option<&str> {
+        match &self.value {
+            Value::Symbol(s) => Some(s),
+            _ => None
+        }
+    }
+
+    /// Get the elements of this list (if it is one).
+    pub fn as_list(&self) -> Option<&[SExp]> {
+        match &self.value {
+            Value::List(l) => Some(l),
+            _ => None
+        }
+    }
+}
+
+/// Prints an S-expression in a form which parses back to the same
+/// value, though whitespace and comments are not preserved.  Note that
+/// non-finite floats are printed as symbols (e.g. `NaN`).
+impl fmt::Display for SExp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Symbol(s) => write!(f,"{s}"),
+            Value::Integer(i) => write!(f,"{i}"),
+            // Debug always includes a decimal point or exponent
+            Value::Float(x) => write!(f,"{x:?}"),
+            Value::String(s) => {
+                write!(f,"\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f,"\\\"")?,
+                        '\\' => write!(f,"\\\\")?,
+                        '\n' => write!(f,"\\n")?,
+                        '\t' => write!(f,"\\t")?,
+                        '\r' => write!(f,"\\r")?,
+                        '\0' => write!(f,"\\0")?,
+                        _ => write!(f,"{c}")?
+                    }
+                }
+                write!(f,"\"")
+            }
+            Value::Quote(e) => write!(f,"'{e}"),
+            Value::List(es) => {
+                write!(f,"(")?;
+                for (i,e) in es.iter().enumerate() {
+                    if i != 0 { write!(f," ")?; }
+                    write!(f,"{e}")?;
+                }
+                write!(f,")")
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Identifies an error arising whilst parsing an S-expression.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Error {
+    /// An unexpected token (or end of file) was encountered.
+    Syntax(SyntaxError<Kind>),
+    /// A string literal was not closed before the end of the input.
+    UnterminatedString(Region),
+    /// An unknown escape sequence (e.g. `\q`) was encountered within
+    /// a string literal.
+    InvalidEscape(Region),
+    /// An integer literal which does not fit within an `i64`.
+    IntegerOutOfRange(Region)
+}
+
+impl Error {
+    /// Get the region where this error arose.
+    pub fn region(&self) -> Region {
+        match self {
+            Error::Syntax(e) => e.region,
+            Error::UnterminatedString(r) => *r,
+            Error::InvalidEscape(r) => *r,
+            Error::IntegerOutOfRange(r) => *r
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax(e) => write!(f,"{e}"),
+            Error::UnterminatedString(_) => write!(f,"unterminated string"),
+            Error::InvalidEscape(_) => write!(f,"invalid escape sequence"),
+            Error::IntegerOutOfRange(_) => write!(f,"integer out of range")
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<SyntaxError<Kind>> for Error {
+    fn from(e: SyntaxError<Kind>) -> Self {
+        Error::Syntax(e)
+    }
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+/// Parse exactly one S-expression from some input, which may be
+/// surrounded by whitespace and comments.
+pub fn parse(input: &str) -> Result<SExp,Error> {
+    let mut parser = SExpParser::new(input);
+    let e = parser.sexp()?;
+    match parser.stream.is_eof() {
+        true => Ok(e),
+        false => Err(parser.stream.error().into())
+    }
+}
+
+/// Parse a sequence of zero or more S-expressions from some input.
+pub fn parse_all(input: &str) -> Result<Vec<SExp>,Error> {
+    let mut parser = SExpParser::new(input);
+    let mut es = Vec::new();
+    while !parser.stream.is_eof() {
+        es.push(parser.sexp()?);
+    }
+    Ok(es)
+}
+
+struct SExpParser<'a> {
+    stream: TokenStream<Kind>,
+    /// The text of each token, in order.
+    lexemes: Vec<(Token<Kind>,&'a str)>
+}
+
+impl<'a> SExpParser<'a> {
+    fn new(input: &'a str) -> Self {
+        let lexemes : Vec<_> = Lexemes::new(input,lex(input)).collect();
+        let tokens = lexemes.iter().map(|(t,_)| *t).collect();
+        let stream = TokenStream::with_trivia(tokens,&[Kind::WhiteSpace,Kind::Comment]);
+        Self{stream,lexemes}
+    }
+
+    /// Get the text of a given token.
+    fn text(&self, token: Token<Kind>) -> &'a str {
+        let i = self.lexemes.partition_point(|(t,_)| t.start() < token.start());
+        self.lexemes[i].1
+    }
+
+    fn sexp(&mut self) -> Result<SExp,Error> {
+        if let Some(t) = self.stream.peek().filter(|t| t.kind == Kind::UnterminatedString) {
+            Err(Error::UnterminatedString(t.region))
+        } else if let Some(t) = self.stream.eat(Kind::Atom) {
+            Ok(SExp::new(atom(self.text(t),t.region)?,t.region))
+        } else if let Some(t) = self.stream.eat(Kind::String) {
+            Ok(SExp::new(Value::String(unescape(self.text(t),t.region)?),t.region))
+        } else if let Some(t) = self.stream.eat(Kind::Quote) {
+            let e = self.sexp()?;
+            let region = t.region.cover(e.region);
+            Ok(SExp::new(Value::Quote(Box::new(e)),region))
+        } else if let Some(t) = self.stream.eat(Kind::LeftBrace) {
+            let mut es = Vec::new();
+            loop {
+                if let Some(u) = self.stream.eat(Kind::RightBrace) {
+                    return Ok(SExp::new(Value::List(es),t.region.cover(u.region)));
+                }
+                es.push(self.sexp()?);
+            }
+        } else {
+            Err(self.stream.error().into())
+        }
+    }
+}
+
+/// Determine whether an atom is an integer, a float or a symbol.
+/// Atoms which look like numbers (i.e. start with a digit, possibly
+/// after a sign or decimal point) but aren't are symbols, such as
+/// `1+`.
+fn atom(text: &str, region: Region) -> Result<Value,Error> {
+    let digits = text.strip_prefix(['+','-']).unwrap_or(text);
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        return text.parse().map(Value::Integer).map_err(|_| Error::IntegerOutOfRange(region));
+    }
+    let numeric = digits.trim_start_matches('.').starts_with(|c:char| c.is_ascii_digit());
+    match text.parse() {
+        Ok(x) if numeric => Ok(Value::Float(x)),
+        _ => Ok(Value::Symbol(text.to_string()))
+    }
+}
+
+/// Decode the escape sequences within a string literal (including its
+/// quotes), where `region` is the region of the literal.
+fn unescape(text: &str, region: Region) -> Result<String,Error> {
+    let mut s = String::new();
+    let mut chars = text[1..text.len()-1].chars().enumerate();
+    while let Some((_,c)) = chars.next() {
+        if c != '\\' { s.push(c); continue; }
+        // Lexer ensures a backslash is always followed by something
+        let (i,e) = chars.next().unwrap();
+        s.push(match e {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '"' | '\\' => e,
+            _ => {
+                let start = region.start + i;
+                return Err(Error::InvalidEscape(Region::new(start,start+2)));
+            }
+        });
+    }
+    Ok(s)
+}
@@ -0,0 +1,710 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::{Matcher,Text,Token};
+use crate::diagnostic::Diagnostic;
+use crate::lang::rust::is_keyword;
+use crate::util::{Cursor,Region,StrCursor};
+
+// The pattern AST is shared with `#[derive(Lexington)]`.
+#[path = "../derive/src/pattern.rs"]
+mod pattern;
+pub use pattern::Pattern;
+
+// =============================================================================
+// Specification
+// =============================================================================
+
+/// A lexer specification, as read from a `.lex` file.  This is line
+/// based, where each line holds one of:
+///
+/// * A definition, such as `DIGIT = [0-9]`, naming a pattern for use
+///   in later definitions and rules.
+/// * A rule, such as `Number : DIGIT+ ("." DIGIT+)?`, giving the kind
+///   of token produced by a pattern.  Rules prefixed with `skip` (e.g.
+///   for whitespace) produce tokens which are dropped, and rules ending
+///   with `-> name` switch to mode `name` after matching.
+/// * A mode, such as `mode string`, to which subsequent rules belong.
+///   Rules before the first mode belong to mode `main`, and the lexer
+///   starts in the first mode.
+///
+/// Patterns are made from strings (e.g. `"->"` or `"\n"`), character
+/// classes (e.g. `[a-z_]` or `[^"\\]`), `.` (any character except
+/// newline), names of definitions, and groups, along with `|`, `*`,
+/// `+` and `?`.  Comments start with `#`.  Within each mode, the
+/// longest match wins, with ties going to the earlier rule.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Spec {
+    pub modes: Vec<Mode>
+}
+
+/// A named set of rules.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Mode {
+    pub name: String,
+    pub rules: Vec<Rule>
+}
+
+/// Determines a kind of token produced by a pattern.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Rule {
+    pub kind: String,
+    pub pattern: Pattern,
+    pub skip: bool,
+    /// The mode to switch to after matching (if any), as an index into
+    /// `Spec::modes`.
+    pub next: Option<usize>
+}
+
+impl Spec {
+    /// Parse a lexer specification.
+    pub fn parse(text: &str) -> Result<Spec,Error> {
+        let mut parser = Parser{modes: Vec::new(), definitions: HashMap::new(), targets: Vec::new(), skips: HashMap::new(), declarations: Vec::new(), variants: HashMap::new()};
+        let mut start = 0;
+        for (i,raw) in text.split('\n').enumerate() {
+            let chars = raw.strip_suffix('\r').unwrap_or(raw).chars().collect();
+            parser.line(&mut Line{chars, index: 0, number: i+1, start})?;
+            start += raw.chars().count() + 1;
+        }
+        parser.finish(text)
+    }
+
+    /// Get the distinct kinds of token produced by this specification,
+    /// in order of appearance.
+    pub fn kinds(&self) -> Vec<&str> {
+        let mut kinds : Vec<&str> = Vec::new();
+        for rule in self.modes.iter().flat_map(|m| &m.rules) {
+            if !kinds.contains(&rule.kind.as_str()) { kinds.push(&rule.kind); }
+        }
+        kinds
+    }
+
+    /// Determine whether tokens of a given kind are skipped.
+    pub fn is_skipped(&self, kind: &str) -> bool {
+        self.modes.iter().flat_map(|m| &m.rules).any(|r| r.skip && r.kind == kind)
+    }
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// Identifies a problem with a lexer specification.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Error {
+    pub message: String,
+    /// The region of the specification (in chars) where this arose.
+    pub region: Region,
+    /// The (one-based) line where this arose.
+    pub line: usize,
+    /// The (one-based) column where this arose, in chars.
+    pub column: usize
+}
+
+impl Error {
+    /// Convert this error into a diagnostic, such that it can be
+    /// rendered along with the relevant line of the specification.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.message.clone()).with_label(self.region,"")
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}:{}: {}",self.line,self.column,self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+struct Parser {
+    modes: Vec<Mode>,
+    definitions: HashMap<String,Pattern>,
+    /// Mode switches to resolve once all modes are known, identified
+    /// by mode, rule and name, along with where the name appeared.
+    targets: Vec<(usize,usize,String,Error)>,
+    /// Whether each kind is skipped.
+    skips: HashMap<String,bool>,
+    /// Where each mode was declared (or, for an implicit `main` mode,
+    /// where its first rule appeared).
+    declarations: Vec<Error>,
+    /// The name from which each variant in generated code (e.g.
+    /// `Mode::BlockComment`) arises.
+    variants: HashMap<String,String>
+}
+
+impl Parser {
+    fn line(&mut self, line: &mut Line) -> Result<(),Error> {
+        if line.at_end() { return Ok(()); }
+        let from = line.index;
+        let word = line.ident().ok_or_else(|| line.error("expected a rule, definition or mode",from))?;
+        line.skip_whitespace();
+        if word == "mode" && line.peek().is_some_and(is_ident_start) {
+            return self.mode(line);
+        }
+        let skip = word == "skip" && line.peek().is_some_and(is_ident_start);
+        let (name,from) = match skip {
+            true => {
+                let from = line.index;
+                let name = line.ident().unwrap();
+                line.skip_whitespace();
+                (name,from)
+            }
+            false => (word,from)
+        };
+        if matches!(name.as_str(),"mode"|"skip"|"_") || is_keyword(&name) {
+            return Err(line.error(&format!("`{name}` is a reserved word"),from));
+        }
+        match line.next() {
+            Some('=') if !skip => self.definition(line,name,from),
+            Some(':') => self.rule(line,name,skip,from),
+            _ if skip => Err(line.error("expected `:`",line.index.saturating_sub(1))),
+            _ => Err(line.error("expected `:` or `=`",line.index.saturating_sub(1)))
+        }
+    }
+
+    fn mode(&mut self, line: &mut Line) -> Result<(),Error> {
+        let from = line.index;
+        let name = line.ident().unwrap();
+        if self.modes.iter().any(|m| m.name == name) {
+            return Err(line.error(&format!("duplicate mode `{name}`"),from));
+        }
+        let variant = camel_case(&name);
+        if variant.is_empty() || is_keyword(&variant) {
+            return Err(line.error(&format!("invalid mode name `{name}`"),from));
+        }
+        self.variant(line,&name,format!("Mode::{variant}"),from)?;
+        let declaration = line.error("",from);
+        line.end()?;
+        self.modes.push(Mode{name,rules: Vec::new()});
+        self.declarations.push(declaration);
+        Ok(())
+    }
+
+    fn definition(&mut self, line: &mut Line, name: String, from: usize) -> Result<(),Error> {
+        if self.definitions.contains_key(&name) {
+            return Err(line.error(&format!("duplicate definition `{name}`"),from));
+        }
+        let pattern = line.pattern(&self.definitions)?;
+        line.end()?;
+        self.definitions.insert(name,pattern);
+        Ok(())
+    }
+
+    fn rule(&mut self, line: &mut Line, kind: String, skip: bool, from: usize) -> Result<(),Error> {
+        match self.skips.get(&kind) {
+            Some(s) if *s != skip => {
+                return Err(line.error(&format!("`{kind}` is skipped by some rules but not others"),from));
+            }
+            _ => { self.skips.insert(kind.clone(),skip); }
+        }
+        self.variant(line,&kind,format!("Kind::{kind}"),from)?;
+        line.skip_whitespace();
+        let start = line.index;
+        let pattern = line.pattern(&self.definitions)?;
+        if pattern.is_nullable() {
+            return Err(line.error("pattern can match the empty string",start));
+        }
+        // Mode switch
+        let mut target = None;
+        line.skip_whitespace();
+        if line.peek() == Some('-') {
+            line.next();
+            line.expect('>')?;
+            line.skip_whitespace();
+            let from = line.index;
+            let name = line.ident().ok_or_else(|| line.error("expected mode name",from))?;
+            target = Some((name,line.error("",from)));
+        }
+        line.end()?;
+        if self.modes.is_empty() {
+            self.variant(line,"main","Mode::Main".to_string(),from)?;
+            self.modes.push(Mode{name: "main".to_string(), rules: Vec::new()});
+            self.declarations.push(line.error("",from));
+        }
+        let m = self.modes.len() - 1;
+        let mode = &mut self.modes[m];
+        if let Some((name,error)) = target {
+            self.targets.push((m,mode.rules.len(),name,error));
+        }
+        mode.rules.push(Rule{kind,pattern,skip,next: None});
+        Ok(())
+    }
+
+    /// Record the variant generated for a given name, checking that no
+    /// other name generates the same variant (e.g. modes `foo_bar` and
+    /// `fooBar` both generate `Mode::FooBar`).
+    fn variant(&mut self, line: &Line, name: &str, variant: String, from: usize) -> Result<(),Error> {
+        match self.variants.get(&variant) {
+            Some(other) if other != name => {
+                Err(line.error(&format!("`{name}` and `{other}` both generate `{variant}`"),from))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.variants.insert(variant,name.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(mut self, text: &str) -> Result<Spec,Error> {
+        for (m,r,name,error) in self.targets {
+            match self.modes.iter().position(|mode| mode.name == name) {
+                Some(i) => { self.modes[m].rules[r].next = Some(i); }
+                None => return Err(Error{message: format!("unknown mode `{name}`"), ..error})
+            }
+        }
+        if let Some(i) = self.modes.iter().position(|m| m.rules.is_empty()) {
+            let message = format!("mode `{}` has no rules",self.modes[i].name);
+            return Err(Error{message, ..self.declarations.swap_remove(i)});
+        }
+        if self.modes.is_empty() {
+            return Err(Self::locate(text,"specification has no rules"));
+        }
+        Ok(Spec{modes: self.modes})
+    }
+
+    /// Construct an error located at the end of the specification.
+    fn locate(text: &str, message: &str) -> Error {
+        let n = text.chars().count();
+        let line = text.split('\n').count();
+        let column = text.rsplit('\n').next().unwrap().chars().count() + 1;
+        Error{message: message.to_string(), region: Region::new(n,n), line, column}
+    }
+}
+
+/// A single line of a specification being parsed.
+struct Line {
+    chars: Vec<char>,
+    index: usize,
+    /// The (one-based) line number.
+    number: usize,
+    /// The offset (in chars) of this line within the specification.
+    start: usize
+}
+
+impl Line {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() { self.index += 1; }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) { self.index += 1; }
+    }
+
+    /// Check whether only whitespace or a comment remains.
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        matches!(self.peek(),None|Some('#'))
+    }
+
+    fn end(&mut self) -> Result<(),Error> {
+        match self.at_end() {
+            true => Ok(()),
+            false => Err(self.error("unexpected character",self.index))
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(),Error> {
+        match self.peek() == Some(c) {
+            true => { self.index += 1; Ok(()) }
+            false => Err(self.error(&format!("expected `{c}`"),self.index))
+        }
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        if !self.peek().is_some_and(is_ident_start) { return None; }
+        let from = self.index;
+        while self.peek().is_some_and(|c| c == '_' || c.is_ascii_alphanumeric()) { self.index += 1; }
+        Some(self.chars[from..self.index].iter().collect())
+    }
+
+    /// Construct an error for the text from a given index up to the
+    /// current position (or the character at that index, if none).
+    fn error(&self, message: &str, from: usize) -> Error {
+        let end = if self.index > from { self.index } else { (from+1).min(self.chars.len()).max(from) };
+        Error{message: message.to_string(), region: Region::new(self.start+from,self.start+end), line: self.number, column: from+1}
+    }
+
+    fn pattern(&mut self, definitions: &HashMap<String,Pattern>) -> Result<Pattern,Error> {
+        let mut alternatives = vec![self.sequence(definitions)?];
+        while self.peek() == Some('|') {
+            self.next();
+            alternatives.push(self.sequence(definitions)?);
+        }
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => Pattern::Or(alternatives)
+        })
+    }
+
+    fn sequence(&mut self, definitions: &HashMap<String,Pattern>) -> Result<Pattern,Error> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None|Some('|'|')'|'#') => break,
+                Some('-') if self.chars.get(self.index+1) == Some(&'>') => break,
+                _ => {}
+            }
+            let from = self.index;
+            let atom = self.atom(definitions)?;
+            let item = match self.peek() {
+                Some('*') => Pattern::ZeroOrMore(Box::new(atom)),
+                Some('+') => Pattern::OneOrMore(Box::new(atom)),
+                Some('?') => Pattern::Optional(Box::new(atom)),
+                _ => { items.push(atom); continue; }
+            };
+            self.next();
+            if matches!(item,Pattern::ZeroOrMore(ref p)|Pattern::OneOrMore(ref p) if p.is_nullable()) {
+                return Err(self.error("repetition of a pattern which can match the empty string",from));
+            }
+            items.push(item);
+        }
+        match items.len() {
+            0 => Err(self.error("expected pattern",self.index)),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(Pattern::Then(items))
+        }
+    }
+
+    fn atom(&mut self, definitions: &HashMap<String,Pattern>) -> Result<Pattern,Error> {
+        let from = self.index;
+        match self.peek() {
+            Some('(') => {
+                self.next();
+                let p = self.pattern(definitions)?;
+                match self.next() {
+                    Some(')') => Ok(p),
+                    _ => Err(self.error("unclosed `(`",from))
+                }
+            }
+            Some('"') => {
+                self.next();
+                let mut s = String::new();
+                loop {
+                    match self.next() {
+                        Some('"') => break,
+                        Some('\\') => s.push(self.escape()?),
+                        Some(c) => s.push(c),
+                        None => return Err(self.error("unterminated string",from))
+                    }
+                }
+                match s.is_empty() {
+                    true => Err(self.error("empty string",from)),
+                    false => Ok(Pattern::Text(s))
+                }
+            }
+            Some('[') => {
+                self.next();
+                self.class(from)
+            }
+            Some('.') => {
+                self.next();
+                Ok(Pattern::Class(vec![('\n','\n')],true))
+            }
+            Some(c) if is_ident_start(c) => {
+                let name = self.ident().unwrap();
+                match definitions.get(&name) {
+                    Some(p) => Ok(p.clone()),
+                    None => Err(self.error(&format!("unknown definition `{name}`"),from))
+                }
+            }
+            Some(c@('*'|'+'|'?')) => Err(self.error(&format!("nothing to repeat before `{c}`"),from)),
+            _ => Err(self.error("unexpected character",from))
+        }
+    }
+
+    /// Decode an escape sequence (with the backslash consumed).
+    fn escape(&mut self) -> Result<char,Error> {
+        let from = self.index - 1;
+        match self.next() {
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('0') => Ok('\0'),
+            Some('u') if self.peek() == Some('{') => {
+                self.next();
+                let digits = self.index;
+                while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) { self.index += 1; }
+                let hex : String = self.chars[digits..self.index].iter().collect();
+                let c = u32::from_str_radix(&hex,16).ok().and_then(char::from_u32);
+                match (c,self.next()) {
+                    (Some(c),Some('}')) => Ok(c),
+                    _ => Err(self.error("invalid unicode escape",from))
+                }
+            }
+            Some(c) if c.is_ascii_punctuation() || c == ' ' => Ok(c),
+            _ => Err(self.error("unknown escape sequence",from))
+        }
+    }
+
+    /// Parse a character class (with the opening bracket consumed).
+    fn class(&mut self, from: usize) -> Result<Pattern,Error> {
+        let negated = self.peek() == Some('^');
+        if negated { self.next(); }
+        let mut ranges = Vec::new();
+        loop {
+            let at = self.index;
+            let lo = match self.next() {
+                Some(']') if !ranges.is_empty() => break,
+                Some(']') => return Err(self.error("empty character class",from)),
+                Some('\\') => self.escape()?,
+                Some(c) => c,
+                None => return Err(self.error("unclosed `[`",from))
+            };
+            if self.peek() == Some('-') && !matches!(self.chars.get(self.index+1),Some(']')|None) {
+                self.next();
+                let hi = match self.next() {
+                    Some('\\') => self.escape()?,
+                    Some(c) => c,
+                    None => return Err(self.error("unclosed `[`",from))
+                };
+                if hi < lo {
+                    return Err(self.error("invalid range (start exceeds end)",at));
+                }
+                ranges.push((lo,hi));
+            } else {
+                ranges.push((lo,lo));
+            }
+        }
+        Ok(Pattern::Class(ranges,negated))
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic()
+}
+
+// =============================================================================
+// Patterns
+// =============================================================================
+
+/// Patterns can also be interpreted directly, with the same semantics
+/// as the matchers generated from them.
+impl Matcher for Pattern {
+    type Item = char;
+
+    fn matches<C:Cursor<Item=char>>(&self, input: &mut C) -> bool {
+        match self {
+            Pattern::Text(s) => Text(s).matches(input),
+            Pattern::Class(ranges,negated) => match input.next() {
+                Some(c) if ranges.iter().any(|(lo,hi)| *lo <= c && c <= *hi) != *negated => true,
+                Some(_) => {
+                    input.backup(1);
+                    false
+                }
+                None => false
+            }
+            Pattern::Then(ps) => {
+                let start = input.offset();
+                for p in ps {
+                    if !p.matches(input) {
+                        input.backup(input.offset() - start);
+                        return false;
+                    }
+                }
+                true
+            }
+            Pattern::Or(ps) => ps.iter().any(|p| p.matches(input)),
+            Pattern::ZeroOrMore(p) => {
+                while Pattern::matches(p,input) {}
+                true
+            }
+            Pattern::OneOrMore(p) => {
+                if !Pattern::matches(p,input) { return false; }
+                while Pattern::matches(p,input) {}
+                true
+            }
+            Pattern::Optional(p) => {
+                Pattern::matches(p,input);
+                true
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Interpreter
+// =============================================================================
+
+impl Spec {
+    /// Construct a lexer which interprets this specification directly,
+    /// rather than via generated code.
+    pub fn lexer<'a>(&'a self, input: &'a str) -> SpecLexer<'a> {
+        SpecLexer{spec: self, input: StrCursor::new(input), mode: 0}
+    }
+}
+
+/// Splits text into tokens according to a specification, where each
+/// token is identified by the name of its kind.  Unlike a generated
+/// lexer, this does not stop at a character not matched by any rule,
+/// but instead produces a token without a kind for it.
+pub struct SpecLexer<'a> {
+    spec: &'a Spec,
+    input: StrCursor<'a>,
+    /// Index of the current mode.
+    mode: usize
+}
+
+impl<'a> SpecLexer<'a> {
+    /// Get the name of the current mode.
+    pub fn mode(&self) -> &'a str {
+        &self.spec.modes[self.mode].name
+    }
+}
+
+impl<'a> Iterator for SpecLexer<'a> {
+    type Item = Token<Option<&'a str>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.input.offset();
+        // Find the longest match, with ties going to the earlier rule
+        let mut best : Option<(usize,&Rule)> = None;
+        for rule in &self.spec.modes[self.mode].rules {
+            if rule.pattern.matches(&mut self.input) {
+                let n = self.input.offset() - start;
+                self.input.backup(n);
+                if n > best.map_or(0,|(m,_)| m) { best = Some((n,rule)); }
+            }
+        }
+        let kind = match best {
+            Some((n,rule)) => {
+                self.input.advance(n);
+                self.mode = rule.next.unwrap_or(self.mode);
+                Some(rule.kind.as_str())
+            }
+            None => {
+                self.input.next()?;
+                None
+            }
+        };
+        let end = self.input.offset();
+        self.input.reset();
+        Some(Token::new(kind,start..end))
+    }
+}
+
+// =============================================================================
+// Generator
+// =============================================================================
+
+impl Spec {
+    /// Generate Rust source for this specification.  This declares a
+    /// `Kind` enum of tokens, a `Mode` enum whose `scan()` method scans
+    /// the next token in that mode, a `Lexer` which tracks the current
+    /// mode, and a `lex()` function which drops skipped tokens.  Since
+    /// this is intended for use with `include!()`, the output contains
+    /// no inner attributes.
+    pub fn to_rust(&self) -> String {
+        let kinds = self.kinds();
+        let mut out = String::new();
+        out.push_str("// Generated by lexington from a lexer specification.  Do not edit.\n\n");
+        out.push_str("/// Identifies the kinds of token.\n");
+        out.push_str("#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]\npub enum Kind {\n");
+        out.push_str(&kinds.iter().map(|k| format!("    {k}")).collect::<Vec<_>>().join(",\n"));
+        out.push_str("\n}\n\nimpl Kind {\n");
+        out.push_str("    /// Determine whether tokens of this kind are skipped.\n");
+        out.push_str("    pub fn is_skipped(self) -> bool {\n");
+        let skipped : Vec<String> = kinds.iter().filter(|k| self.is_skipped(k)).map(|k| format!("Kind::{k}")).collect();
+        match skipped.is_empty() {
+            true => out.push_str("        false\n"),
+            false => out.push_str(&format!("        matches!(self,{})\n",skipped.join("|")))
+        }
+        out.push_str("    }\n}\n\n");
+        // Modes
+        let names : Vec<String> = self.modes.iter().map(|m| camel_case(&m.name)).collect();
+        out.push_str("/// Identifies the modes of the lexer.\n");
+        out.push_str("#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]\npub enum Mode {\n");
+        out.push_str(&names.iter().map(|m| format!("    {m}")).collect::<Vec<_>>().join(",\n"));
+        out.push_str("\n}\n\nimpl Mode {\n");
+        out.push_str("    /// Scan the next token in this mode, along with the mode to\n");
+        out.push_str("    /// continue in.\n");
+        out.push_str("    pub fn scan<C: ::lexington::util::Cursor<Item=char>>(self, input: &mut C) -> Option<(Kind,Mode)> {\n");
+        out.push_str("        let mut longest = ::lexington::Longest::new();\n");
+        out.push_str("        match self {\n");
+        for (mode,name) in self.modes.iter().zip(&names) {
+            out.push_str(&format!("            Mode::{name} => {{\n"));
+            for rule in &mode.rules {
+                let next = rule.next.map_or(name,|i| &names[i]);
+                out.push_str(&format!("                longest.rule(input,{},0,(Kind::{},Mode::{next}));\n",rule.pattern.to_rust(),rule.kind));
+            }
+            out.push_str("            }\n");
+        }
+        out.push_str("        }\n        longest.finish(input)\n    }\n}\n\n");
+        // Lexer
+        out.push_str(&LEXER.replace("Mode::INITIAL",&format!("Mode::{}",names[0])));
+        out
+    }
+}
+
+const LEXER : &str = "\
+/// Splits text into tokens, switching modes as directed.  Lexing
+/// stops at the first character not matched by any rule.
+pub struct Lexer<'a> {
+    input: ::lexington::util::StrCursor<'a>,
+    mode: Mode
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self{input: ::lexington::util::StrCursor::new(input), mode: Mode::INITIAL}
+    }
+
+    /// Get the current mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = ::lexington::Token<Kind>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use ::lexington::util::Cursor;
+        let start = self.input.offset();
+        let (kind,mode) = self.mode.scan(&mut self.input)?;
+        let end = self.input.offset();
+        self.input.reset();
+        self.mode = mode;
+        Some(::lexington::Token::new(kind,start..end))
+    }
+}
+
+/// Split text into tokens, dropping those which are skipped.
+pub fn lex(input: &str) -> Vec<::lexington::Token<Kind>> {
+    Lexer::new(input).filter(|t| !t.kind.is_skipped()).collect()
+}
+";
+
+/// Convert a mode name (e.g. `block_comment`) into a variant name
+/// (e.g. `BlockComment`).
+fn camel_case(name: &str) -> String {
+    name.split('_').filter(|s| !s.is_empty()).map(|s| {
+        let mut cs = s.chars();
+        cs.next().unwrap().to_ascii_uppercase().to_string() + cs.as_str()
+    }).collect()
+}
+
+/// Generate Rust source from a lexer specification file, as for
+/// `Spec::to_rust()`.  This is intended for use from a build script,
+/// where errors are reported as `path:line:column: message`.
+pub fn compile<P:AsRef<Path>,Q:AsRef<Path>>(spec: P, output: Q) -> io::Result<()> {
+    let text = fs::read_to_string(&spec)?;
+    match Spec::parse(&text) {
+        Ok(s) => fs::write(output,s.to_rust()),
+        Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData,format!("{}:{e}",spec.as_ref().display())))
+    }
+}
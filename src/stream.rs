@@ -0,0 +1,251 @@
+use std::fmt;
+use crate::{Matcher,Token};
+use crate::util::{Cursor,Region};
+
+/// Identifies a syntax error encountered whilst parsing a stream of
+/// tokens.
+#[derive(Clone,Debug,PartialEq)]
+pub struct SyntaxError<T> {
+    /// The token encountered, or `None` if the end of the stream was
+    /// reached.
+    pub found: Option<Token<T>>,
+    /// The kinds of token which would have been accepted instead.
+    pub expected: Vec<T>,
+    /// The region where the error arose.  At the end of the stream,
+    /// this is an empty region after the last token.
+    pub region: Region
+}
+
+impl<T:fmt::Debug> fmt::Display for SyntaxError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.expected.as_slice() {
+            [] => write!(f,"unexpected ")?,
+            [kind] => write!(f,"expected {kind:?}, found ")?,
+            kinds => {
+                write!(f,"expected one of ")?;
+                for (i,kind) in kinds.iter().enumerate() {
+                    if i != 0 { write!(f,", ")?; }
+                    write!(f,"{kind:?}")?;
+                }
+                write!(f,", found ")?;
+            }
+        }
+        match &self.found {
+            Some(t) => write!(f,"{:?}",t.kind),
+            None => write!(f,"end of file")
+        }
+    }
+}
+
+impl<T:fmt::Debug> std::error::Error for SyntaxError<T> {}
+
+/// Identifies a position in a `TokenStream` which can be returned to
+/// later.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord)]
+pub struct Checkpoint(usize);
+
+/// A cursor over a sequence of tokens (e.g. as produced by a `Lexer`),
+/// providing the usual operations needed by a hand-written recursive
+/// descent parser.  Tokens of certain kinds (e.g. whitespace or
+/// comments) can be designated as _trivia_, and are then skipped
+/// automatically.
+#[derive(Clone,Debug)]
+pub struct TokenStream<T> {
+    tokens: Vec<Token<T>>,
+    /// Kinds of token which are skipped automatically.
+    trivia: Vec<T>,
+    /// Position of the next token in the stream.
+    offset: usize,
+    /// Kinds of token tested for at the current position, which are
+    /// reported as expected if an error arises here.
+    attempted: Vec<T>
+}
+
+impl<T:Copy+PartialEq> TokenStream<T> {
+    pub fn new(tokens: Vec<Token<T>>) -> Self {
+        Self::with_trivia(tokens,&[])
+    }
+
+    /// Construct a stream which skips over tokens of the given kinds.
+    pub fn with_trivia(tokens: Vec<Token<T>>, trivia: &[T]) -> Self {
+        Self{tokens, trivia: trivia.to_vec(), offset: 0, attempted: Vec::new()}
+    }
+
+    /// Check whether the end of the stream has been reached.
+    pub fn is_eof(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Get the next token without consuming it.
+    pub fn peek(&self) -> Option<Token<T>> {
+        self.peek_nth(0)
+    }
+
+    /// Get the `n`th token from the current position (where `n==0` is
+    /// the next token) without consuming anything.
+    pub fn peek_nth(&self, n: usize) -> Option<Token<T>> {
+        self.tokens[self.offset..].iter().filter(|t| !self.is_trivia(t.kind)).nth(n).copied()
+    }
+
+    /// Check whether the next token has a given kind.
+    pub fn at(&mut self, kind: T) -> bool {
+        if !self.attempted.contains(&kind) { self.attempted.push(kind); }
+        self.peek().is_some_and(|t| t.kind == kind)
+    }
+
+    /// Consume the next token, provided it has a given kind.
+    pub fn eat(&mut self, kind: T) -> Option<Token<T>> {
+        match self.at(kind) {
+            true => self.next(),
+            false => None
+        }
+    }
+
+    /// Consume the next token, provided it has a given kind.
+    /// Otherwise, report an error.
+    pub fn expect(&mut self, kind: T) -> Result<Token<T>,SyntaxError<T>> {
+        self.expect_one_of(&[kind])
+    }
+
+    /// Consume the next token, provided it has one of the given kinds.
+    /// Otherwise, report an error.
+    pub fn expect_one_of(&mut self, kinds: &[T]) -> Result<Token<T>,SyntaxError<T>> {
+        for kind in kinds {
+            if let Some(t) = self.eat(*kind) { return Ok(t); }
+        }
+        Err(self.error())
+    }
+
+    /// Construct an error at the current position, which reports all
+    /// the token kinds tested for here (e.g. by `at()` or `eat()`).
+    pub fn error(&self) -> SyntaxError<T> {
+        let found = self.peek();
+        let region = match found {
+            Some(t) => t.region,
+            None => {
+                let end = self.tokens.last().map_or(0,|t| t.end());
+                Region::new(end,end)
+            }
+        };
+        SyntaxError{found, expected: self.attempted.clone(), region}
+    }
+
+    /// Record the current position, so it can be returned to later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.offset)
+    }
+
+    /// Return to a previously recorded position.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.offset = checkpoint.0;
+        self.attempted.clear();
+    }
+
+    /// Get the tokens (including trivia) consumed between a given
+    /// checkpoint and the current position.
+    pub fn since(&self, checkpoint: Checkpoint) -> &[Token<T>] {
+        &self.tokens[checkpoint.0..self.offset]
+    }
+
+    /// Determine the region covered by the (non-trivia) tokens
+    /// consumed between a given checkpoint and the current position.
+    /// If no such tokens were consumed, this is an empty region at the
+    /// start of the next token.
+    pub fn region_since(&self, checkpoint: Checkpoint) -> Region {
+        let mut tokens = self.since(checkpoint).iter().filter(|t| !self.is_trivia(t.kind));
+        match tokens.next() {
+            Some(first) => first.region.cover(tokens.last().map_or(first.region,|t| t.region)),
+            None => {
+                let start = self.peek().map_or(0,|t| t.start());
+                Region::new(start,start)
+            }
+        }
+    }
+
+    /// Get a cursor over the kinds of the remaining (non-trivia)
+    /// tokens.  This allows any `Matcher` over token kinds to be
+    /// applied to the stream.
+    pub fn kinds(&mut self) -> Kinds<'_,T> {
+        Kinds{stream: self, start: 0, count: 0}
+    }
+
+    /// Apply a `Matcher` to the kinds of the remaining tokens, as for
+    /// `kinds()`.  On failure, the stream is left unchanged, including
+    /// the token kinds tested for at the current position.
+    pub(crate) fn matches<M:Matcher<Item=T>>(&mut self, matcher: &M) -> bool {
+        let attempted = self.attempted.clone();
+        let matched = matcher.matches(&mut self.kinds());
+        if !matched { self.attempted = attempted; }
+        matched
+    }
+
+    /// Check whether tokens of a given kind are trivia.
+    pub fn is_trivia(&self, kind: T) -> bool {
+        self.trivia.contains(&kind)
+    }
+}
+
+impl<T:Copy+PartialEq> Iterator for TokenStream<T> {
+    type Item = Token<T>;
+
+    /// Consume the next (non-trivia) token.
+    fn next(&mut self) -> Option<Token<T>> {
+        while self.offset < self.tokens.len() {
+            let token = self.tokens[self.offset];
+            self.offset += 1;
+            if !self.is_trivia(token.kind) {
+                self.attempted.clear();
+                return Some(token);
+            }
+        }
+        None
+    }
+}
+
+impl<T:Copy+PartialEq> FromIterator<Token<T>> for TokenStream<T> {
+    fn from_iter<I:IntoIterator<Item=Token<T>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// A cursor over the kinds of the (non-trivia) tokens in a
+/// `TokenStream`.  Offsets are measured from the point the cursor was
+/// created.
+pub struct Kinds<'a,T> {
+    stream: &'a mut TokenStream<T>,
+    /// Offset of the last reset point.
+    start: usize,
+    /// Number of tokens consumed through this cursor.
+    count: usize
+}
+
+impl<T:Copy+PartialEq> Iterator for Kinds<'_,T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let token = self.stream.next()?;
+        self.count += 1;
+        Some(token.kind)
+    }
+}
+
+impl<T:Copy+PartialEq> Cursor for Kinds<'_,T> {
+    fn offset(&self) -> usize {
+        self.count
+    }
+
+    fn backup(&mut self, n: usize) {
+        assert!(n <= self.count - self.start);
+        for _ in 0..n {
+            // Step back over trivia to the previous token
+            let stream = &mut self.stream;
+            while stream.is_trivia(stream.tokens[stream.offset-1].kind) { stream.offset -= 1; }
+            stream.offset -= 1;
+        }
+        self.count -= n;
+    }
+
+    fn reset(&mut self) {
+        self.start = self.count;
+    }
+}
@@ -32,6 +32,11 @@ impl<T> Token<T>  {
         self.region.end - self.region.start
     }
 
+    /// Check whether this token covers no characters at all.
+    pub fn is_empty(&self) -> bool {
+        self.region.is_empty()
+    }
+
     /// Extract the underlying region covered by this span as a
     /// `Range`.  This is really just for convenience.
     pub fn range(&self) -> Range<usize> { self.start() .. self.end() }
@@ -42,6 +47,12 @@ impl<T> Token<T>  {
     pub fn shift(&mut self, delta: usize) {
         self.region.shift(delta);
     }
+
+    /// Shift the span by a given amount which, unlike `shift()`, may
+    /// be negative (i.e. move down the sequence).
+    pub fn shift_by(&mut self, delta: isize) {
+        self.region.shift_by(delta);
+    }
 }
 
 impl<T:PartialEq> PartialEq<(T,Range<usize>)> for Token<T> {
@@ -50,3 +61,16 @@ impl<T:PartialEq> PartialEq<(T,Range<usize>)> for Token<T> {
         self.kind == other.0 && self.range() == other.1
     }
 }
+
+/// Classifies the kinds of token produced by a lexer, for tools which
+/// handle tokens from any lexer (e.g. to highlight errors, or omit
+/// whitespace and comments).
+pub trait TokenKind : Copy {
+    /// Determine whether tokens of this kind indicate malformed input,
+    /// such as an unterminated string or an unknown character.
+    fn is_error(self) -> bool;
+
+    /// Determine whether tokens of this kind carry no meaning, such as
+    /// whitespace or comments.
+    fn is_trivia(self) -> bool;
+}
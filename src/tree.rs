@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Range;
+use std::rc::Rc;
+use crate::Token;
+use crate::util::Region;
+
+// =============================================================================
+// Green Tree
+// =============================================================================
+
+/// A leaf of a green tree, holding the text of a single token
+/// (including trivia such as whitespace or comments).
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+pub struct GreenToken<K> {
+    kind: K,
+    text: String,
+    /// Length of the text (in chars).
+    len: usize
+}
+
+impl<K:Copy> GreenToken<K> {
+    pub fn new(kind: K, text: &str) -> Self {
+        Self{kind, text: text.to_string(), len: text.chars().count()}
+    }
+
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the length (in chars) of this token.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this token has no text at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// An interior node of a green tree.  Green nodes are immutable and
+/// know only their kind, their children and their total length, but
+/// not their absolute position in the source.  This means identical
+/// subtrees can be shared (e.g. every `1` token is the same green
+/// token), and a tree can be cheaply rebuilt after an edit.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct GreenNode<K> {
+    kind: K,
+    children: Vec<GreenElement<K>>,
+    /// Total length (in chars) of all children.
+    len: usize
+}
+
+impl<K:Copy> GreenNode<K> {
+    pub fn new(kind: K, children: Vec<GreenElement<K>>) -> Self {
+        let len = children.iter().map(|c| c.len()).sum();
+        Self{kind,children,len}
+    }
+
+    pub fn kind(&self) -> K {
+        self.kind
+    }
+
+    pub fn children(&self) -> &[GreenElement<K>] {
+        &self.children
+    }
+
+    /// Get the length (in chars) of the text covered by this node.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this node covers no text at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Writes out the exact source text covered by this node.
+impl<K> fmt::Display for GreenNode<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(n) => write!(f,"{n}")?,
+                GreenElement::Token(t) => write!(f,"{}",t.text)?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Either a node or a token in a green tree.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum GreenElement<K> {
+    Node(Rc<GreenNode<K>>),
+    Token(Rc<GreenToken<K>>)
+}
+
+impl<K:Copy> GreenElement<K> {
+    pub fn kind(&self) -> K {
+        match self {
+            GreenElement::Node(n) => n.kind,
+            GreenElement::Token(t) => t.kind
+        }
+    }
+
+    /// Get the length (in chars) of the text covered by this element.
+    pub fn len(&self) -> usize {
+        match self {
+            GreenElement::Node(n) => n.len,
+            GreenElement::Token(t) => t.len
+        }
+    }
+
+    /// Check whether this element covers no text at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the address of the shared element, which identifies it
+    /// uniquely when interning.
+    fn address(&self) -> usize {
+        match self {
+            GreenElement::Node(n) => Rc::as_ptr(n) as usize,
+            GreenElement::Token(t) => Rc::as_ptr(t) as usize
+        }
+    }
+}
+
+// =============================================================================
+// Tree Builder
+// =============================================================================
+
+/// Nodes with more children than this are not interned, since they
+/// are unlikely to be repeated.
+const MAX_INTERNED_CHILDREN: usize = 3;
+
+/// Identifies a position amongst the children of the node currently
+/// being built, such that a node can be started retrospectively (see
+/// `TreeBuilder::start_node_at()`).
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Checkpoint(usize);
+
+/// Constructs a green tree from a sequence of nodes and tokens, as
+/// produced by a parser.  Tokens with the same kind and text are
+/// shared, as are small nodes with identical children.  For example:
+///
+/// ```
+/// use lexington::tree::TreeBuilder;
+/// let mut builder = TreeBuilder::new();
+/// builder.start_node("add");
+/// builder.token("num","1");
+/// builder.token("op","+");
+/// builder.token("num","1");
+/// builder.finish_node();
+/// let root = builder.finish();
+/// assert_eq!(root.to_string(),"1+1");
+/// ```
+pub struct TreeBuilder<K> {
+    /// Kind of each unfinished node, along with the index of its
+    /// first child.
+    parents: Vec<(K,usize)>,
+    /// Children of all unfinished nodes.
+    children: Vec<GreenElement<K>>,
+    tokens: HashMap<(K,String),Rc<GreenToken<K>>>,
+    nodes: HashMap<(K,Vec<usize>),Rc<GreenNode<K>>>
+}
+
+impl<K:Copy+Eq+Hash> Default for TreeBuilder<K> {
+    fn default() -> Self {
+        Self{parents: Vec::new(), children: Vec::new(), tokens: HashMap::new(), nodes: HashMap::new()}
+    }
+}
+
+impl<K:Copy+Eq+Hash> TreeBuilder<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new node of a given kind, which becomes the parent of
+    /// subsequent nodes and tokens until it is finished.
+    pub fn start_node(&mut self, kind: K) {
+        self.parents.push((kind,self.children.len()));
+    }
+
+    /// Start a new node of a given kind which begins at an earlier
+    /// checkpoint, and therefore adopts everything added since then.
+    /// This is useful when the kind of a node is not known until after
+    /// its first child has been parsed (e.g. a binary expression).
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: K) {
+        let start = self.parents.last().map_or(0,|p| p.1);
+        assert!(start <= checkpoint.0 && checkpoint.0 <= self.children.len(), "invalid checkpoint");
+        self.parents.push((kind,checkpoint.0));
+    }
+
+    /// Record the current position, such that a node can be started
+    /// there later.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+
+    /// Add a token to the current node.
+    pub fn token(&mut self, kind: K, text: &str) {
+        let key = (kind,text.to_string());
+        let token = self.tokens.entry(key).or_insert_with(|| Rc::new(GreenToken::new(kind,text))).clone();
+        self.children.push(GreenElement::Token(token));
+    }
+
+    /// Add a token produced by a `Lexer` to the current node, where
+    /// `text` is the text of that token.
+    pub fn lexeme(&mut self, token: Token<K>, text: &str) {
+        debug_assert_eq!(token.len(),text.chars().count());
+        self.token(token.kind,text)
+    }
+
+    /// Finish the current node, making it a child of its parent.
+    pub fn finish_node(&mut self) {
+        let (kind,start) = self.parents.pop().expect("no node to finish");
+        let children : Vec<_> = self.children.drain(start..).collect();
+        let node = self.intern(kind,children);
+        self.children.push(GreenElement::Node(node));
+    }
+
+    /// Finish building, returning the root node.  This requires every
+    /// node started to have been finished, and exactly one root node.
+    pub fn finish(mut self) -> Rc<GreenNode<K>> {
+        assert!(self.parents.is_empty(), "unfinished node");
+        match (self.children.pop(), self.children.is_empty()) {
+            (Some(GreenElement::Node(n)),true) => n,
+            _ => panic!("expected exactly one root node")
+        }
+    }
+
+    fn intern(&mut self, kind: K, children: Vec<GreenElement<K>>) -> Rc<GreenNode<K>> {
+        if children.len() > MAX_INTERNED_CHILDREN {
+            return Rc::new(GreenNode::new(kind,children));
+        }
+        // Children are already interned, hence are identified by their
+        // addresses (which remain valid as the cache holds them).
+        let key = (kind,children.iter().map(|c| c.address()).collect());
+        self.nodes.entry(key).or_insert_with(|| Rc::new(GreenNode::new(kind,children))).clone()
+    }
+}
+
+/// Pairs each token produced by a `Lexer` with its text in the
+/// original source, assuming tokens are contiguous and in order (as
+/// a lexer produces them).  This avoids repeatedly converting char
+/// offsets into byte offsets.
+pub struct Lexemes<'a,I> {
+    source: &'a str,
+    tokens: I,
+    /// Current position in the source (in chars).
+    offset: usize,
+    /// Current position in the source (in bytes).
+    byte: usize
+}
+
+impl<'a,K,I:Iterator<Item=Token<K>>> Lexemes<'a,I> {
+    pub fn new<T:IntoIterator<IntoIter=I>>(source: &'a str, tokens: T) -> Self {
+        Self{source, tokens: tokens.into_iter(), offset: 0, byte: 0}
+    }
+
+    /// Advance to a given char offset, returning the byte offset.
+    fn seek(&mut self, offset: usize) -> usize {
+        assert!(offset >= self.offset, "tokens out of order");
+        let n = offset - self.offset;
+        let rest = &self.source[self.byte..];
+        self.byte += rest.char_indices().nth(n).map_or(rest.len(),|(i,_)| i);
+        self.offset = offset;
+        self.byte
+    }
+}
+
+impl<'a,K,I:Iterator<Item=Token<K>>> Iterator for Lexemes<'a,I> {
+    type Item = (Token<K>,&'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.tokens.next()?;
+        let start = self.seek(token.start());
+        let end = self.seek(token.end());
+        Some((token,&self.source[start..end]))
+    }
+}
+
+// =============================================================================
+// Red Tree
+// =============================================================================
+
+struct NodeData<K> {
+    green: Rc<GreenNode<K>>,
+    parent: Option<SyntaxNode<K>>,
+    /// Index of this node within its parent.
+    index: usize,
+    /// Absolute offset (in chars) of this node.
+    offset: usize
+}
+
+/// A node of a syntax tree, which is a lightweight cursor into a green
+/// tree.  Unlike green nodes, syntax nodes know their parent and their
+/// absolute position in the source.  Syntax nodes are created on demand
+/// when traversing a tree, and are cheap to clone.
+pub struct SyntaxNode<K>(Rc<NodeData<K>>);
+
+impl<K> Clone for SyntaxNode<K> {
+    fn clone(&self) -> Self {
+        SyntaxNode(self.0.clone())
+    }
+}
+
+/// Syntax nodes are equal when they refer to the same node in the same
+/// tree.  Since green nodes are shared, this requires the same position
+/// within the same parent (e.g. adjacent empty nodes of the same kind
+/// share a green node and an offset, but are still distinct).
+impl<K> PartialEq for SyntaxNode<K> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0,&other.0) || (Rc::ptr_eq(&self.0.green,&other.0.green)
+            && self.0.index == other.0.index
+            && self.0.parent == other.0.parent)
+    }
+}
+
+impl<K> Eq for SyntaxNode<K> {}
+
+impl<K:Copy> SyntaxNode<K> {
+    /// Construct the root of a syntax tree.
+    pub fn new_root(green: Rc<GreenNode<K>>) -> Self {
+        SyntaxNode(Rc::new(NodeData{green, parent: None, index: 0, offset: 0}))
+    }
+
+    pub fn kind(&self) -> K {
+        self.0.green.kind
+    }
+
+    /// Get the underlying green node.
+    pub fn green(&self) -> &Rc<GreenNode<K>> {
+        &self.0.green
+    }
+
+    /// Get the region of the source covered by this node.
+    pub fn region(&self) -> Region {
+        Region::new(self.0.offset,self.0.offset + self.0.green.len)
+    }
+
+    /// Get the source text covered by this node.
+    pub fn text(&self) -> String {
+        self.0.green.to_string()
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode<K>> {
+        self.0.parent.clone()
+    }
+
+    /// Iterate this node, its parent, its parent's parent, etc.
+    pub fn ancestors(&self) -> impl Iterator<Item=SyntaxNode<K>> {
+        std::iter::successors(Some(self.clone()),|n| n.parent())
+    }
+
+    /// Iterate all children of this node, including tokens.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item=SyntaxElement<K>> + '_ {
+        let mut offset = self.0.offset;
+        self.0.green.children.iter().enumerate().map(move |(i,c)| {
+            let e = self.element(i,c,offset);
+            offset += c.len();
+            e
+        })
+    }
+
+    /// Iterate the child nodes of this node (i.e. ignoring tokens).
+    pub fn children(&self) -> impl Iterator<Item=SyntaxNode<K>> + '_ {
+        self.children_with_tokens().filter_map(|e| e.into_node())
+    }
+
+    pub fn first_child(&self) -> Option<SyntaxNode<K>> {
+        self.children().next()
+    }
+
+    /// Get the nearest sibling node following this node (if any).
+    pub fn next_sibling(&self) -> Option<SyntaxNode<K>> {
+        self.siblings(self.0.index + 1).find_map(|e| e.into_node())
+    }
+
+    /// Get the nearest sibling node preceding this node (if any).
+    pub fn prev_sibling(&self) -> Option<SyntaxNode<K>> {
+        let parent = self.parent()?;
+        let v : Vec<_> = parent.children_with_tokens().take(self.0.index).collect();
+        v.into_iter().rev().find_map(|e| e.into_node())
+    }
+
+    /// Iterate all nodes within this node (including itself) in
+    /// preorder.
+    pub fn descendants(&self) -> impl Iterator<Item=SyntaxNode<K>> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let len = stack.len();
+            stack.extend(node.children());
+            stack[len..].reverse();
+            Some(node)
+        })
+    }
+
+    /// Iterate all nodes and tokens within this node (including
+    /// itself) in preorder.
+    pub fn descendants_with_tokens(&self) -> impl Iterator<Item=SyntaxElement<K>> {
+        let mut stack = vec![SyntaxElement::Node(self.clone())];
+        std::iter::from_fn(move || {
+            let element = stack.pop()?;
+            if let SyntaxElement::Node(n) = &element {
+                let len = stack.len();
+                stack.extend(n.children_with_tokens());
+                stack[len..].reverse();
+            }
+            Some(element)
+        })
+    }
+
+    /// Iterate all tokens within this node in order.  Concatenating
+    /// their text gives back the text of this node exactly.
+    pub fn tokens(&self) -> impl Iterator<Item=SyntaxToken<K>> {
+        self.descendants_with_tokens().filter_map(|e| e.into_token())
+    }
+
+    /// Find the token covering a given offset.  At the boundary
+    /// between two tokens, this is the token starting there.
+    pub fn token_at(&self, offset: usize) -> Option<SyntaxToken<K>> {
+        if !self.region().contains_offset(offset) { return None; }
+        let mut node = self.clone();
+        loop {
+            let child = node.children_with_tokens().find(|c| c.region().contains_offset(offset))?;
+            match child {
+                SyntaxElement::Node(n) => { node = n; }
+                SyntaxElement::Token(t) => { return Some(t); }
+            }
+        }
+    }
+
+    /// Find the smallest node which entirely covers a given region.
+    pub fn covering_node(&self, region: Region) -> Option<SyntaxNode<K>> {
+        if !self.region().contains(region) { return None; }
+        let mut node = self.clone();
+        loop {
+            let child = node.children().find(|c| c.region().contains(region));
+            match child {
+                Some(c) => { node = c; }
+                None => { return Some(node); }
+            }
+        }
+    }
+
+    fn element(&self, index: usize, green: &GreenElement<K>, offset: usize) -> SyntaxElement<K> {
+        let parent = Some(self.clone());
+        match green {
+            GreenElement::Node(n) => {
+                SyntaxElement::Node(SyntaxNode(Rc::new(NodeData{green: n.clone(), parent, index, offset})))
+            }
+            GreenElement::Token(t) => {
+                SyntaxElement::Token(SyntaxToken{green: t.clone(), parent: self.clone(), index, offset})
+            }
+        }
+    }
+
+    /// Iterate the siblings of this node from a given index onwards.
+    fn siblings(&self, index: usize) -> impl Iterator<Item=SyntaxElement<K>> {
+        let parent = self.parent();
+        let elements : Vec<_> = match &parent {
+            Some(p) => p.children_with_tokens().skip(index).collect(),
+            None => Vec::new()
+        };
+        elements.into_iter()
+    }
+}
+
+impl<K> fmt::Display for SyntaxNode<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.0.green)
+    }
+}
+
+impl<K:Copy+fmt::Debug> fmt::Debug for SyntaxNode<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{:?}@{:?}",self.kind(),Range::from(self.region()))
+    }
+}
+
+/// A token within a syntax tree, which knows its parent and absolute
+/// position in the source.
+#[derive(Clone)]
+pub struct SyntaxToken<K> {
+    green: Rc<GreenToken<K>>,
+    parent: SyntaxNode<K>,
+    /// Index of this token within its parent.
+    index: usize,
+    /// Absolute offset (in chars) of this token.
+    offset: usize
+}
+
+impl<K> PartialEq for SyntaxToken<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.parent == other.parent && self.index == other.index
+    }
+}
+
+impl<K> Eq for SyntaxToken<K> {}
+
+impl<K:Copy> SyntaxToken<K> {
+    pub fn kind(&self) -> K {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    /// Get the region of the source covered by this token.
+    pub fn region(&self) -> Region {
+        Region::new(self.offset,self.offset + self.green.len)
+    }
+
+    pub fn parent(&self) -> SyntaxNode<K> {
+        self.parent.clone()
+    }
+
+    /// Get the underlying green token.
+    pub fn green(&self) -> &Rc<GreenToken<K>> {
+        &self.green
+    }
+
+    /// Convert this into a plain `Token` (e.g. as produced by a
+    /// `Lexer`).
+    pub fn to_token(&self) -> Token<K> {
+        Token{kind: self.kind(), region: self.region()}
+    }
+}
+
+impl<K:Copy+fmt::Debug> fmt::Debug for SyntaxToken<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{:?}@{:?} {:?}",self.kind(),Range::from(self.region()),self.text())
+    }
+}
+
+/// Either a node or a token within a syntax tree.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum SyntaxElement<K:Copy> {
+    Node(SyntaxNode<K>),
+    Token(SyntaxToken<K>)
+}
+
+impl<K:Copy> SyntaxElement<K> {
+    pub fn kind(&self) -> K {
+        match self {
+            SyntaxElement::Node(n) => n.kind(),
+            SyntaxElement::Token(t) => t.kind()
+        }
+    }
+
+    /// Get the region of the source covered by this element.
+    pub fn region(&self) -> Region {
+        match self {
+            SyntaxElement::Node(n) => n.region(),
+            SyntaxElement::Token(t) => t.region()
+        }
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode<K>> {
+        match self {
+            SyntaxElement::Node(n) => n.parent(),
+            SyntaxElement::Token(t) => Some(t.parent())
+        }
+    }
+
+    pub fn into_node(self) -> Option<SyntaxNode<K>> {
+        match self {
+            SyntaxElement::Node(n) => Some(n),
+            SyntaxElement::Token(_) => None
+        }
+    }
+
+    pub fn into_token(self) -> Option<SyntaxToken<K>> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(t) => Some(t)
+        }
+    }
+}
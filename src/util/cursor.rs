@@ -0,0 +1,154 @@
+/// A cursor provides sequential access to the items of some
+/// underlying sequence, along with the ability to "backup" over items
+/// which have already been read.  This is the input abstraction used
+/// by both `Matcher` and `Scanner`.
+pub trait Cursor : Iterator {
+    /// Get the current position within the underlying sequence.
+    fn offset(&self) -> usize;
+
+    /// Move the current position back by `n` items.  This cannot move
+    /// back past the point of the last `reset()`.
+    fn backup(&mut self, n: usize);
+
+    /// Indicate that items before the current position will never be
+    /// revisited (i.e. via `backup()`).
+    fn reset(&mut self);
+
+    /// Get the remaining items from the current position onwards,
+    /// provided they are already held in memory.  This allows
+    /// matchers to implement fast paths (e.g. for skipping ahead).
+    fn remaining(&self) -> Option<&[Self::Item]> { None }
+
+    /// Move the current position forward by (at most) `n` items.
+    fn advance(&mut self, n: usize) {
+        for _ in 0..n { if self.next().is_none() { break; } }
+    }
+}
+
+/// A cursor over a slice of items.  Since the entire sequence is
+/// already in memory, this requires no buffering at all and moving
+/// around is just index arithmetic.
+#[derive(Clone,Copy,Debug)]
+pub struct SliceCursor<'a,T> {
+    /// The underlying sequence of items.
+    items: &'a [T],
+    /// Offset of the last reset point.
+    start: usize,
+    /// Current position within `items`.
+    offset: usize
+}
+
+impl<'a,T> SliceCursor<'a,T> {
+    /// Construct a cursor positioned at the start of a given slice.
+    pub fn new(items: &'a [T]) -> Self { Self{items, start:0, offset:0} }
+
+    /// Get the remaining items from the current position onwards.
+    pub fn rest(&self) -> &'a [T] {
+        &self.items[self.offset..]
+    }
+}
+
+impl<'a,T:Copy> Iterator for SliceCursor<'a,T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.items.get(self.offset)?;
+        self.offset += 1;
+        Some(*item)
+    }
+}
+
+impl<'a,T:Copy> Cursor for SliceCursor<'a,T> {
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn backup(&mut self, n: usize) {
+        assert!(n <= self.offset - self.start);
+        self.offset -= n;
+    }
+
+    fn reset(&mut self) {
+        self.start = self.offset;
+    }
+
+    fn remaining(&self) -> Option<&[T]> {
+        Some(self.rest())
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.offset = self.items.len().min(self.offset + n);
+    }
+}
+
+/// A cursor over the characters of a string slice.  Offsets are
+/// measured in characters (as for `str::chars()`), though the
+/// equivalent byte offset is also available for slicing the original
+/// string.
+#[derive(Clone,Copy,Debug)]
+pub struct StrCursor<'a> {
+    /// The underlying string.
+    input: &'a str,
+    /// Byte position within `input` corresponding to `offset`.
+    byte: usize,
+    /// Offset (in chars) of the last reset point.
+    start: usize,
+    /// Current position (in chars) within `input`.
+    offset: usize
+}
+
+impl<'a> StrCursor<'a> {
+    /// Construct a cursor positioned at the start of a given string.
+    pub fn new(input: &'a str) -> Self { Self{input, byte:0, start:0, offset:0} }
+
+    /// Get the current position as a byte offset into the original
+    /// string.
+    pub fn byte_offset(&self) -> usize {
+        self.byte
+    }
+
+    /// Get the remainder of the string from the current position
+    /// onwards.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.byte..]
+    }
+}
+
+impl<'a> Iterator for StrCursor<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let b = *self.input.as_bytes().get(self.byte)?;
+        self.offset += 1;
+        if b.is_ascii() {
+            // Fast path for ASCII characters
+            self.byte += 1;
+            Some(b as char)
+        } else {
+            let c = self.rest().chars().next()?;
+            self.byte += c.len_utf8();
+            Some(c)
+        }
+    }
+}
+
+impl<'a> Cursor for StrCursor<'a> {
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn backup(&mut self, n: usize) {
+        assert!(n <= self.offset - self.start);
+        let bytes = self.input.as_bytes();
+        for _ in 0..n {
+            // Step back over one (possibly multibyte) character
+            self.byte -= 1;
+            while (bytes[self.byte] & 0xC0) == 0x80 { self.byte -= 1; }
+        }
+        self.offset -= n;
+    }
+
+    fn reset(&mut self) {
+        self.start = self.offset;
+    }
+}
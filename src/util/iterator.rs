@@ -1,3 +1,5 @@
+use super::Cursor;
+
 /// An iterator which can be "reset" after an arbitrary number of
 /// calls to `next()`.  This is achieved using a
 /// buffer which stores items as they are read.
@@ -21,10 +23,10 @@ impl<I:Iterator> ResetIterator<I> {
     pub fn offset(&self) -> usize {
         self.offset
     }
-    
+
     pub fn backup(&mut self, n:usize) {
-        assert!(n <= self.items.len());
-        self.offset = self.offset - n;
+        assert!(n <= self.offset - self.start);
+        self.offset -= n;
     }
 
     /// Empty the internal lookahead buffer.
@@ -36,6 +38,22 @@ impl<I:Iterator> ResetIterator<I> {
         // Clean all items
         self.items.drain(0..n);
     }
+
+    /// Get the items read since the last reset, up to the current
+    /// position.
+    pub fn window(&self) -> &[I::Item] {
+        &self.items[..self.offset - self.start]
+    }
+
+    /// Get a reference to the underlying iterator.
+    pub fn get_ref(&self) -> &I {
+        &self.iter
+    }
+
+    /// Get a mutable reference to the underlying iterator.
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
 }
 
 impl<I:Iterator> Iterator for ResetIterator<I>
@@ -59,3 +77,18 @@ where I::Item : Copy {
         Some(self.items[i])        
     }
 }
+
+impl<I:Iterator> Cursor for ResetIterator<I>
+where I::Item : Copy {
+    fn offset(&self) -> usize {
+        ResetIterator::offset(self)
+    }
+
+    fn backup(&mut self, n:usize) {
+        ResetIterator::backup(self,n)
+    }
+
+    fn reset(&mut self) {
+        ResetIterator::reset(self)
+    }
+}
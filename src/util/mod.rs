@@ -1,5 +1,15 @@
+mod cursor;
 mod region;
 mod iterator;
+mod normalise;
+mod position;
+mod reader;
+mod source;
 
+pub use cursor::{Cursor,SliceCursor,StrCursor};
 pub use region::Region;
 pub use iterator::{ResetIterator};
+pub use reader::{ReadError,Utf8Reader};
+pub use normalise::{Normalise,OffsetMap,TextUnit};
+pub use position::{Encoding,LineIndex,OffsetKind,Position};
+pub use source::{FileId,Location,SourceFile,SourceMap,Span};
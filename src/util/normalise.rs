@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use super::Region;
+
+/// Identifies the items of a text stream (e.g. `char` or `u8`) which
+/// matter for normalisation.
+pub trait TextUnit : Copy + PartialEq + 'static {
+    /// The encoding of a byte order mark.
+    const BOM: &'static [Self];
+    /// The carriage return character.
+    const CR: Self;
+    /// The line feed character.
+    const LF: Self;
+}
+
+impl TextUnit for char {
+    const BOM: &'static [char] = &['\u{FEFF}'];
+    const CR: char = '\r';
+    const LF: char = '\n';
+}
+
+impl TextUnit for u8 {
+    const BOM: &'static [u8] = &[0xEF,0xBB,0xBF];
+    const CR: u8 = b'\r';
+    const LF: u8 = b'\n';
+}
+
+/// Records how offsets in a normalised stream correspond to offsets
+/// in the original stream.  Offsets on both sides are measured in the
+/// items of the stream, so a `u8` stream maps back to byte offsets in
+/// the original input, whilst a `char` stream maps back to char
+/// offsets (not bytes).  To recover byte offsets into the original
+/// bytes, normalise the `u8` stream instead.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct OffsetMap {
+    /// Each entry `(n,d,k)` records a run of `k` consecutive collapsed
+    /// items (e.g. `\r\n\r\n` gives `k=2`), such that from normalised
+    /// offset `n` onwards the corresponding original offset is `offset
+    /// + d + min(offset-n,k-1)`.  Entries are sorted by `n`.
+    entries: Vec<(usize,usize,usize)>
+}
+
+impl OffsetMap {
+    /// Translate an offset in the normalised stream into the
+    /// corresponding offset in the original stream (in the same units
+    /// as the stream).
+    pub fn original(&self, offset: usize) -> usize {
+        let i = self.entries.partition_point(|(n,_,_)| *n <= offset);
+        match i {
+            0 => offset,
+            _ => {
+                let (n,d,k) = self.entries[i-1];
+                offset + d + (offset-n).min(k-1)
+            }
+        }
+    }
+
+    /// Translate a region of the normalised stream into the
+    /// corresponding region of the original stream.  For example, a
+    /// normalised newline covers the whole of the original `\r\n`.
+    pub fn region(&self, region: Region) -> Region {
+        Region::new(self.original(region.start),self.original(region.end))
+    }
+
+    /// Record that, from normalised offset `offset` onwards, the
+    /// original offset is `offset+delta`.  This extends the previous
+    /// entry where it immediately precedes `offset`.
+    fn record(&mut self, offset: usize, delta: usize) {
+        match self.entries.last_mut() {
+            Some((n,_,k)) if *n + *k == offset => { *k += 1; }
+            _ => self.entries.push((offset,delta,1))
+        }
+    }
+}
+
+/// An iterator adapter which strips a leading byte order mark and,
+/// optionally, normalises line endings (i.e. `\r\n` and `\r`) into
+/// `\n`.  An `OffsetMap` is maintained as items are read, such that
+/// regions of the normalised stream (e.g. from `Token::region`) can
+/// be translated back into the original stream.
+pub struct Normalise<I:Iterator>
+where I::Item: TextUnit {
+    /// The underlying stream.
+    iter: I,
+    /// Items read from the underlying stream, but not yet returned.
+    buffer: VecDeque<I::Item>,
+    /// Determines whether or not to normalise line endings.
+    newlines: bool,
+    /// Determines whether the start of the stream has been checked
+    /// for a byte order mark.
+    started: bool,
+    /// Number of items consumed from the underlying stream.
+    consumed: usize,
+    /// Number of items returned from this stream.
+    produced: usize,
+    /// Mapping from normalised offsets to original offsets.
+    map: OffsetMap
+}
+
+impl<I:Iterator> Normalise<I>
+where I::Item: TextUnit {
+    pub fn new(iter: I, newlines: bool) -> Self {
+        Self{iter, buffer: VecDeque::new(), newlines, started: false, consumed: 0, produced: 0, map: OffsetMap::default()}
+    }
+
+    /// Get the offset mapping for those items returned so far.
+    pub fn map(&self) -> &OffsetMap {
+        &self.map
+    }
+
+    /// Consume this adapter, returning its offset mapping.
+    pub fn into_map(self) -> OffsetMap {
+        self.map
+    }
+
+    /// Ensure (where possible) at least `n` items are buffered.
+    fn fill(&mut self, n: usize) {
+        while self.buffer.len() < n {
+            match self.iter.next() {
+                Some(item) => self.buffer.push_back(item),
+                None => { break; }
+            }
+        }
+    }
+
+    /// Take the next buffered item.
+    fn take(&mut self) -> Option<I::Item> {
+        self.fill(1);
+        let item = self.buffer.pop_front()?;
+        self.consumed += 1;
+        Some(item)
+    }
+}
+
+impl<I:Iterator> Iterator for Normalise<I>
+where I::Item: TextUnit {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if !self.started {
+            let bom = I::Item::BOM;
+            self.started = true;
+            self.fill(bom.len());
+            if self.buffer.iter().take(bom.len()).eq(bom.iter()) {
+                // Strip byte order mark
+                self.buffer.drain(..bom.len());
+                self.consumed += bom.len();
+                self.map.record(0,bom.len());
+            }
+        }
+        let item = self.take()?;
+        self.produced += 1;
+        if self.newlines && item == I::Item::CR {
+            self.fill(1);
+            if self.buffer.front() == Some(&I::Item::LF) {
+                // Collapse "\r\n" into "\n"
+                self.take();
+                self.map.record(self.produced,self.consumed - self.produced);
+            }
+            Some(I::Item::LF)
+        } else {
+            Some(item)
+        }
+    }
+}
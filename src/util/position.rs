@@ -0,0 +1,167 @@
+use super::Region;
+
+/// Identifies the units in which a `Region` is measured.  For example,
+/// a lexer over `str::chars()` produces regions measured in `Chars`,
+/// whilst a lexer over `str::as_bytes()` produces regions measured in
+/// `Bytes`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum OffsetKind {
+    Bytes,
+    Chars
+}
+
+/// Identifies the units in which the `character` of a `Position` is
+/// measured.  This corresponds to the `PositionEncodingKind` of the
+/// Language Server Protocol, where `Utf16` is the default.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Encoding {
+    /// Measured in bytes.
+    Utf8,
+    /// Measured in UTF-16 code units.
+    Utf16,
+    /// Measured in unicode code points (i.e. `char`s).
+    Utf32
+}
+
+impl Encoding {
+    /// Determine the width of a given character in this encoding.
+    pub fn width(&self, c: char) -> usize {
+        match self {
+            Encoding::Utf8 => c.len_utf8(),
+            Encoding::Utf16 => c.len_utf16(),
+            Encoding::Utf32 => 1
+        }
+    }
+}
+
+/// A (zero-based) line and character position within a source file,
+/// as used by the Language Server Protocol.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32
+}
+
+impl Position {
+    pub fn new(line: u32, character: u32) -> Self {
+        Self{line,character}
+    }
+}
+
+/// An index over the lines of a source file, allowing offsets to be
+/// converted to and from line/character positions.  Lines are
+/// terminated by `\n`, `\r\n` or `\r`.  Out of range positions are
+/// clamped, as required by the Language Server Protocol (e.g. a
+/// `character` beyond the end of its line refers to the end of that
+/// line).
+#[derive(Clone,Debug)]
+pub struct LineIndex<'a> {
+    /// The source text being indexed.
+    text: &'a str,
+    /// Units of offsets given to (and returned from) this index.
+    kind: OffsetKind,
+    /// Byte offset of the start of each line.
+    bytes: Vec<usize>,
+    /// Char offset of the start of each line.
+    chars: Vec<usize>
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str, kind: OffsetKind) -> Self {
+        let (bytes,chars) = line_starts(text);
+        Self{text,kind,bytes,chars}
+    }
+
+    /// Get the number of lines in the source text.
+    pub fn line_count(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Get the text of a given line (excluding its terminator).
+    pub fn line(&self, line: usize) -> &'a str {
+        let start = self.bytes[line];
+        let end = self.bytes.get(line+1).copied().unwrap_or(self.text.len());
+        self.text[start..end].trim_end_matches('\n').trim_end_matches('\r')
+    }
+
+    /// Convert an offset into a position in a given encoding.
+    pub fn position(&self, offset: usize, encoding: Encoding) -> Position {
+        let (line,byte) = match self.kind {
+            OffsetKind::Bytes => {
+                // Clamp to a character boundary
+                let mut byte = offset.min(self.text.len());
+                while !self.text.is_char_boundary(byte) { byte -= 1; }
+                (self.bytes.partition_point(|b| *b <= byte) - 1, byte)
+            }
+            OffsetKind::Chars => char_to_byte(self.text,&self.bytes,&self.chars,offset)
+        };
+        let character : usize = self.text[self.bytes[line]..byte].chars().map(|c| encoding.width(c)).sum();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// Convert a position in a given encoding into an offset.
+    pub fn offset(&self, position: Position, encoding: Encoding) -> usize {
+        let line = position.line as usize;
+        if line >= self.line_count() {
+            return match self.kind {
+                OffsetKind::Bytes => self.text.len(),
+                OffsetKind::Chars => self.text.chars().count()
+            };
+        }
+        let mut width = 0;
+        let mut bytes = self.bytes[line];
+        let mut chars = self.chars[line];
+        for c in self.line(line).chars() {
+            width += encoding.width(c);
+            if width > position.character as usize { break; }
+            bytes += c.len_utf8();
+            chars += 1;
+        }
+        match self.kind {
+            OffsetKind::Bytes => bytes,
+            OffsetKind::Chars => chars
+        }
+    }
+
+    /// Convert a region into a pair of start and end positions.
+    pub fn range(&self, region: Region, encoding: Encoding) -> (Position,Position) {
+        (self.position(region.start,encoding), self.position(region.end,encoding))
+    }
+
+    /// Convert a pair of start and end positions into a region.
+    pub fn region(&self, start: Position, end: Position, encoding: Encoding) -> Region {
+        Region::new(self.offset(start,encoding),self.offset(end,encoding))
+    }
+}
+
+/// Convert a char offset into a line and byte offset, given the byte
+/// and char offsets of the start of each line (as determined by
+/// `line_starts()`).
+pub(crate) fn char_to_byte(text: &str, bytes: &[usize], chars: &[usize], offset: usize) -> (usize,usize) {
+    let line = chars.partition_point(|c| *c <= offset) - 1;
+    let start = bytes[line];
+    let n = offset - chars[line];
+    // Walk forward n characters
+    let byte = text[start..].char_indices().nth(n).map(|(i,_)| start + i).unwrap_or(text.len());
+    (line,byte)
+}
+
+/// Determine the byte and char offsets of the start of each line in
+/// some text.
+pub(crate) fn line_starts(text: &str) -> (Vec<usize>,Vec<usize>) {
+    let mut bytes = vec![0];
+    let mut chars = vec![0];
+    let mut iter = text.char_indices().enumerate().peekable();
+    while let Some((n,(i,c))) = iter.next() {
+        let eol = match c {
+            '\n' => true,
+            '\r' => !matches!(iter.peek(), Some((_,(_,'\n')))),
+            _ => false
+        };
+        if eol {
+            bytes.push(i + 1);
+            chars.push(n + 1);
+        }
+    }
+    (bytes,chars)
+}
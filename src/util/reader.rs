@@ -0,0 +1,120 @@
+use std::fmt;
+use std::io::{BufRead,ErrorKind};
+
+/// Identifies something which went wrong when reading characters from
+/// an underlying byte stream.
+#[derive(Debug)]
+pub enum ReadError {
+    /// An error was reported by the underlying stream.
+    Io(std::io::Error),
+    /// An invalid (or truncated) UTF-8 sequence was encountered
+    /// starting at the given byte offset.
+    InvalidUtf8(usize)
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f,"{e}"),
+            ReadError::InvalidUtf8(offset) => write!(f,"invalid UTF-8 sequence at byte {offset}")
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self { ReadError::Io(e) }
+}
+
+/// An iterator which incrementally decodes characters from a stream
+/// of UTF-8 encoded bytes.  Iteration stops at the end of the stream,
+/// or at the first error encountered.  In the latter case, the error
+/// can be recovered using `take_error()`.
+pub struct Utf8Reader<R:BufRead> {
+    /// The underlying stream of bytes.
+    reader: R,
+    /// Number of bytes consumed so far.
+    offset: usize,
+    /// The first error encountered (if any).
+    error: Option<ReadError>,
+    /// Indicates whether an error has been encountered.
+    failed: bool
+}
+
+impl<R:BufRead> Utf8Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Self{reader, offset: 0, error: None, failed: false}
+    }
+
+    /// Get the number of bytes consumed from the underlying stream.
+    pub fn byte_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Take the error which caused iteration to stop (if any).
+    pub fn take_error(&mut self) -> Option<ReadError> {
+        self.error.take()
+    }
+
+    /// Read the next byte from the underlying stream, returning `None`
+    /// at the end of the stream or if an error arises.
+    fn read_byte(&mut self) -> Option<u8> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok([]) => { return None; }
+                Ok(buf) => {
+                    let b = buf[0];
+                    self.reader.consume(1);
+                    self.offset += 1;
+                    return Some(b);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.fail(ReadError::Io(e));
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn fail(&mut self, error: ReadError) {
+        self.error = Some(error);
+        self.failed = true;
+    }
+}
+
+impl<R:BufRead> Iterator for Utf8Reader<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.failed { return None; }
+        let start = self.offset;
+        let first = self.read_byte()?;
+        // Determine length of encoded sequence
+        let width = match first {
+            0x00..=0x7F => { return Some(first as char); }
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => 0
+        };
+        let mut bytes = [first,0,0,0];
+        for b in bytes.iter_mut().take(width).skip(1) {
+            match self.read_byte() {
+                Some(v) => { *b = v; }
+                None if self.failed => { return None; }
+                None => { break; }
+            }
+        }
+        // Decode sequence (which also rules out overlong encodings,
+        // surrogates, etc).
+        match std::str::from_utf8(&bytes[..width]).ok().and_then(|s| s.chars().next()) {
+            Some(c) if width > 0 => Some(c),
+            _ => {
+                self.fail(ReadError::InvalidUtf8(start));
+                None
+            }
+        }
+    }
+}
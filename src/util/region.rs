@@ -1,10 +1,12 @@
+use std::cmp::Ordering;
 use std::ops::Range;
 
 /// Basically the same as `std::ops::Range`, but implements `Copy`.
 /// Note, like `Range`, this is _half open_.  That means `start`
 /// identifies the first index in the region, whilst `end` is one past
-/// the last index.
-#[derive(Clone,Copy,Debug,PartialEq)]
+/// the last index.  Regions are ordered by their start, and then by
+/// their end.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
 pub struct Region {
     pub start: usize,
     pub end: usize
@@ -19,10 +21,91 @@ impl Region {
         self.end - self.start
     }
 
+    /// Check whether this region covers no items at all.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Move this region up the sequence by a given amount.
     pub fn shift(&mut self, delta: usize) {
         self.start += delta;
         self.end += delta;
     }
+
+    /// Move this region by a given amount, which may be negative
+    /// (i.e. move down the sequence).
+    pub fn shift_by(&mut self, delta: isize) {
+        self.start = self.start.checked_add_signed(delta).expect("region shifted out of range");
+        self.end = self.end.checked_add_signed(delta).expect("region shifted out of range");
+    }
+
+    /// Check whether a given offset falls within this region.
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+
+    /// Check whether a given region lies entirely within this region.
+    pub fn contains(&self, other: Region) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Check whether this region shares at least one item with
+    /// another.
+    pub fn overlaps(&self, other: Region) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Check whether this region either overlaps, or is immediately
+    /// adjacent to, another.
+    pub fn touches(&self, other: Region) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Determine the region common to both this and another region.
+    /// This is empty when the two regions are adjacent, and `None`
+    /// when they don't touch at all.
+    pub fn intersect(&self, other: Region) -> Option<Region> {
+        match self.touches(other) {
+            true => Some(Region::new(self.start.max(other.start),self.end.min(other.end))),
+            false => None
+        }
+    }
+
+    /// Determine the region covered by both this and another region,
+    /// provided they touch (otherwise there is a gap between them).
+    pub fn union(&self, other: Region) -> Option<Region> {
+        match self.touches(other) {
+            true => Some(self.cover(other)),
+            false => None
+        }
+    }
+
+    /// Determine the smallest region covering both this and another
+    /// region (including any gap between them).  For example, this
+    /// gives the region of a syntax node from its first and last
+    /// tokens.
+    pub fn cover(&self, other: Region) -> Region {
+        Region::new(self.start.min(other.start),self.end.max(other.end))
+    }
+
+    /// Split this region into two at a given offset, which must lie
+    /// within the region (or at its end).
+    pub fn split_at(&self, offset: usize) -> (Region,Region) {
+        assert!(self.start <= offset && offset <= self.end);
+        (Region::new(self.start,offset),Region::new(offset,self.end))
+    }
+}
+
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Region) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Region {
+    fn cmp(&self, other: &Region) -> Ordering {
+        self.start.cmp(&other.start).then(self.end.cmp(&other.end))
+    }
 }
 
 /// Simple mechanism for constructing a `Region` from a `Range`.
@@ -32,6 +115,63 @@ impl From<Range<usize>> for Region {
     }
 }
 
-impl Into<Range<usize>> for Region {
-    fn into(self) -> Range<usize> { self.start .. self.end }
+impl From<Region> for Range<usize> {
+    fn from(r: Region) -> Range<usize> { r.start .. r.end }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Region;
+
+    #[test]
+    fn test_01() {
+        let r = Region::new(2,5);
+        assert!(r.contains_offset(2));
+        assert!(r.contains_offset(4));
+        assert!(!r.contains_offset(5));
+        assert!(r.contains(Region::new(3,5)));
+        assert!(r.contains(Region::new(5,5)));
+        assert!(!r.contains(Region::new(1,3)));
+    }
+
+    #[test]
+    fn test_02() {
+        let r = Region::new(2,5);
+        assert!(r.overlaps(Region::new(4,8)));
+        assert!(!r.overlaps(Region::new(5,8)));
+        assert!(r.touches(Region::new(5,8)));
+        assert!(!r.touches(Region::new(6,8)));
+    }
+
+    #[test]
+    fn test_03() {
+        let r = Region::new(2,5);
+        assert_eq!(r.intersect(Region::new(4,8)),Some(Region::new(4,5)));
+        assert_eq!(r.intersect(Region::new(5,8)),Some(Region::new(5,5)));
+        assert_eq!(r.intersect(Region::new(6,8)),None);
+        assert_eq!(r.union(Region::new(5,8)),Some(Region::new(2,8)));
+        assert_eq!(r.union(Region::new(6,8)),None);
+        assert_eq!(r.cover(Region::new(6,8)),Region::new(2,8));
+    }
+
+    #[test]
+    fn test_04() {
+        let mut r = Region::new(2,5);
+        r.shift_by(-2);
+        assert_eq!(r,Region::new(0,3));
+        r.shift_by(3);
+        assert_eq!(r,Region::new(3,6));
+        assert_eq!(r.split_at(4),(Region::new(3,4),Region::new(4,6)));
+    }
+
+    #[test]
+    fn test_05() {
+        let mut regions = vec![Region::new(3,4),Region::new(1,5),Region::new(1,2)];
+        regions.sort();
+        assert_eq!(regions,[Region::new(1,2),Region::new(1,5),Region::new(3,4)]);
+    }
 }
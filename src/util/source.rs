@@ -0,0 +1,220 @@
+use std::fmt;
+use super::{LineIndex,OffsetKind,Region};
+use super::position::{char_to_byte,line_starts};
+
+/// Uniquely identifies a file within a `SourceMap`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// Get the index of this file within its `SourceMap`.
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A `Region` within a specific file.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Span {
+    pub file: FileId,
+    pub region: Region
+}
+
+impl Span {
+    pub fn new(file: FileId, region: Region) -> Self {
+        Self{file,region}
+    }
+
+    /// Determine the smallest span covering both this and another
+    /// span (which must be in the same file).
+    pub fn cover(&self, other: Span) -> Span {
+        assert_eq!(self.file,other.file);
+        Span::new(self.file,self.region.cover(other.region))
+    }
+}
+
+/// A human-readable location within a file, where both `line` and
+/// `column` are one-based.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Location<'a> {
+    pub name: &'a str,
+    pub line: usize,
+    pub column: usize
+}
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}:{}:{}",self.name,self.line,self.column)
+    }
+}
+
+/// A single file held within a `SourceMap`.  Offsets into a file are
+/// measured in chars.
+#[derive(Clone,Debug)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    /// Offset of this file in the global offset space.
+    base: usize,
+    /// Length of this file (in chars).
+    len: usize,
+    /// Byte offset of the start of each line.
+    bytes: Vec<usize>,
+    /// Char offset of the start of each line.
+    chars: Vec<usize>
+}
+
+impl SourceFile {
+    fn new(name: String, text: String, base: usize) -> Self {
+        let (bytes,chars) = line_starts(&text);
+        let len = text.chars().count();
+        Self{name,text,base,len,bytes,chars}
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the length of this file (in chars).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of lines in this file.
+    pub fn line_count(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Get the offset of this file in the global offset space.
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Determine the location of a given offset in this file.
+    pub fn location(&self, offset: usize) -> Location<'_> {
+        let offset = offset.min(self.len);
+        let line = self.chars.partition_point(|c| *c <= offset) - 1;
+        Location{name: &self.name, line: line + 1, column: offset - self.chars[line] + 1}
+    }
+
+    /// Extract the text covered by a given region of this file (e.g.
+    /// the text of a token).  A reversed region is normalised, as for
+    /// `Diagnostic::with_label()`.
+    pub fn slice(&self, region: Region) -> &str {
+        let start = self.byte_offset(region.start.min(region.end));
+        let end = self.byte_offset(region.start.max(region.end));
+        &self.text[start..end]
+    }
+
+    /// Construct an index for converting offsets in this file into
+    /// editor positions.
+    pub fn index(&self) -> LineIndex<'_> {
+        LineIndex::new(&self.text,OffsetKind::Chars)
+    }
+
+    /// Convert a char offset into a byte offset.
+    fn byte_offset(&self, offset: usize) -> usize {
+        char_to_byte(&self.text,&self.bytes,&self.chars,offset.min(self.len)).1
+    }
+}
+
+/// Owns the text of a number of source files, each identified by a
+/// `FileId`.  Files are also laid out one after the other in a
+/// _global_ offset space, meaning a single `usize` can identify a
+/// position in any file.
+#[derive(Clone,Debug,Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new file to this map.
+    pub fn add<N:Into<String>,T:Into<String>>(&mut self, name: N, text: T) -> FileId {
+        // Leave a gap between files, such that the end of one file is
+        // distinct from the start of the next.
+        let base = self.files.last().map_or(0,|f| f.base + f.len + 1);
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile::new(name.into(),text.into(),base));
+        id
+    }
+
+    /// Get the file with a given identifier.
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.index()]
+    }
+
+    /// Get the name of a given file.
+    pub fn name(&self, id: FileId) -> &str {
+        self.get(id).name()
+    }
+
+    /// Get the text of a given file.
+    pub fn text(&self, id: FileId) -> &str {
+        self.get(id).text()
+    }
+
+    /// Iterate the identifiers of all files in this map.
+    pub fn ids(&self) -> impl Iterator<Item=FileId> {
+        (0..self.files.len() as u32).map(FileId)
+    }
+
+    /// Extract the text covered by a given span.
+    pub fn slice(&self, span: Span) -> &str {
+        self.get(span.file).slice(span.region)
+    }
+
+    /// Determine the location of the start of a given span.
+    pub fn location(&self, span: Span) -> Location<'_> {
+        self.get(span.file).location(span.region.start)
+    }
+
+    /// Convert a span into a region of the global offset space.
+    pub fn global(&self, span: Span) -> Region {
+        let mut region = span.region;
+        region.shift(self.get(span.file).base);
+        region
+    }
+
+    /// Determine which file a global offset falls within, returning
+    /// the file and the corresponding local offset.
+    pub fn lookup(&self, offset: usize) -> Option<(FileId,usize)> {
+        let i = self.files.partition_point(|f| f.base <= offset).checked_sub(1)?;
+        let file = &self.files[i];
+        match offset - file.base <= file.len {
+            true => Some((FileId(i as u32),offset - file.base)),
+            false => None
+        }
+    }
+
+    /// Convert a region of the global offset space into a span.  This
+    /// fails if the region does not fall within a single file, or is
+    /// reversed.
+    pub fn span(&self, region: Region) -> Option<Span> {
+        if region.end < region.start { return None; }
+        let (file,start) = self.lookup(region.start)?;
+        let end = region.end - self.get(file).base;
+        match end <= self.get(file).len {
+            true => Some(Span::new(file,Region::new(start,end))),
+            false => None
+        }
+    }
+
+    /// Determine the location of a global offset.
+    pub fn global_location(&self, offset: usize) -> Option<Location<'_>> {
+        let (file,offset) = self.lookup(offset)?;
+        Some(self.get(file).location(offset))
+    }
+}
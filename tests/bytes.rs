@@ -0,0 +1,73 @@
+use lexington::{Lexer,Matcher,Scanner,Token,Unit};
+use lexington::bytes::*;
+use lexington::util::{SliceCursor};
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    Method,
+    Name,
+    Colon,
+    Space,
+    Value,
+    NewLine
+}
+
+use Kind::*;
+
+/// A (very) simplified lexer for HTTP request lines and headers.
+fn lex<C:lexington::util::Cursor<Item=u8>>(input: C) -> Vec<Token<Kind>> {
+    let method = Literal(b"GET").or(Literal(b"POST"));
+    let name = AsciiAlpha.then(AsciiAlphanumeric.or(b'-').zero_or_more());
+    let value = AsciiGraphic.then(Until(b'\r'));
+    let scanner = Unit(method,Method)
+        .or(Unit(name,Name))
+        .or(Unit(b':',Colon))
+        .or(Unit(b' '.one_or_more(),Space))
+        .or(Unit(Literal(b"\r\n"),NewLine))
+        .or(Unit(value,Value));
+    Lexer::with_cursor(input,scanner).collect()
+}
+
+#[test]
+fn test_bytes_01() {
+    let input = b"GET /index.html\r\nHost: 10.0.0.1\r\n";
+    let expected = [(Method,0..3),(Space,3..4),(Value,4..15),(NewLine,15..17),
+                    (Name,17..21),(Colon,21..22),(Space,22..23),(Value,23..31),(NewLine,31..33)];
+    // Fast path
+    assert_eq!(lex(SliceCursor::new(input)),expected);
+    // Slow path
+    assert_eq!(lex(lexington::util::ResetIterator::new(input.iter().copied())),expected);
+}
+
+#[test]
+fn test_bytes_02() {
+    let mut input = SliceCursor::new(b"Content-LENGTH: 10");
+    assert!(IgnoreCase(b"content-length").matches(&mut input));
+    assert!(!IgnoreCase(b": 11").matches(&mut input));
+    assert_eq!(input.rest(),b": 10");
+}
+
+#[test]
+fn test_bytes_03() {
+    let mut input = SliceCursor::new(b"a,b;c");
+    assert!(UntilAny([b';',b',']).matches(&mut input));
+    assert_eq!(input.rest(),b",b;c");
+}
+
+#[test]
+fn test_bytes_04() {
+    let mut input = SliceCursor::new(b"no delimiter here");
+    assert!(Until(b'\n').matches(&mut input));
+    assert_eq!(input.rest(),b"");
+}
+
+#[test]
+fn test_find_byte() {
+    let haystack : Vec<u8> = (0..100).collect();
+    for i in 0..100 {
+        assert_eq!(find_byte(i,&haystack),Some(i as usize));
+        assert_eq!(find_byte(i,&haystack[..i as usize]),None);
+    }
+    assert_eq!(find_byte(0x80,b"\x7F\x7F\x7F\x7F\x7F\x7F\x7F\x7F\x80"),Some(8));
+    assert_eq!(find_byte(1,b""),None);
+}
@@ -0,0 +1,163 @@
+use lexington::lang::c::{lex,CToken,Standard};
+use lexington::tree::Lexemes;
+
+use CToken::*;
+
+/// Lex some input, ignoring whitespace.
+fn tokens(input: &str, standard: Standard) -> Vec<(CToken,&str)> {
+    Lexemes::new(input,lex(input,standard)).map(|(t,s)| (t.kind,s)).filter(|(k,_)| *k != WhiteSpace).collect()
+}
+
+fn check(input: &str, expecting: &[(CToken,&str)]) {
+    assert_eq!(tokens(input,Standard::C17),expecting);
+}
+
+fn check_cpp(input: &str, expecting: &[(CToken,&str)]) {
+    assert_eq!(tokens(input,Standard::Cpp20),expecting);
+}
+
+/// Check a single literal lexes as a given kind.
+fn check_one(input: &str, kind: CToken) {
+    check_cpp(input,&[(kind,input)]);
+}
+
+#[test]
+fn c_01() {
+    check("int main(void) { return 0; }",&[
+        (Keyword,"int"),(Identifier,"main"),(LeftParen,"("),(Keyword,"void"),(RightParen,")"),
+        (LeftBrace,"{"),(Keyword,"return"),(Integer,"0"),(Semicolon,";"),(RightBrace,"}")]);
+    check("x->y.z[1]",&[(Identifier,"x"),(Arrow,"->"),(Identifier,"y"),(Dot,"."),(Identifier,"z"),
+                       (LeftSquare,"["),(Integer,"1"),(RightSquare,"]")]);
+}
+
+#[test]
+fn c_02() {
+    // Keywords depend on the standard
+    assert_eq!(tokens("restrict",Standard::C89),[(Identifier,"restrict")]);
+    assert_eq!(tokens("restrict",Standard::C99),[(Keyword,"restrict")]);
+    assert_eq!(tokens("_Atomic",Standard::C99),[(Identifier,"_Atomic")]);
+    assert_eq!(tokens("_Atomic",Standard::C11),[(Keyword,"_Atomic")]);
+    assert_eq!(tokens("bool",Standard::C17),[(Identifier,"bool")]);
+    assert_eq!(tokens("bool",Standard::C23),[(Keyword,"bool")]);
+    assert_eq!(tokens("class",Standard::C23),[(Identifier,"class")]);
+    assert_eq!(tokens("class",Standard::Cpp98),[(Keyword,"class")]);
+    assert_eq!(tokens("nullptr",Standard::Cpp98),[(Identifier,"nullptr")]);
+    assert_eq!(tokens("nullptr",Standard::Cpp11),[(Keyword,"nullptr")]);
+    assert_eq!(tokens("co_await",Standard::Cpp17),[(Identifier,"co_await")]);
+    assert_eq!(tokens("co_await",Standard::Cpp20),[(Keyword,"co_await")]);
+    assert!(Standard::C89.keywords().all(|k| Standard::C23.is_keyword(k)));
+    assert!(Standard::Cpp11.keywords().all(|k| Standard::Cpp23.is_keyword(k)));
+    assert_eq!(tokens("_x1 $y λ",Standard::C17),[(Identifier,"_x1"),(Identifier,"$y"),(Identifier,"λ")]);
+}
+
+#[test]
+fn c_03() {
+    // Integers
+    for i in ["0","42","017","0x1F","0XdeadBEEF","0b1010","1'000'000","0x7fff'ffff"] {
+        check_one(i,Integer);
+    }
+    for i in ["10u","10U","10l","10LL","10ull","10LLU","10uz","10wb","10_km"] {
+        check_one(i,Integer);
+    }
+    // Floats
+    for f in ["1.5","1.",".5","1e10","1E-3","1.5e+3","0x1.8p3","0x.8P-1","0x1p0","1'000.5"] {
+        check_one(f,Float);
+    }
+    for f in ["1.5f","1.5F","1.5L","1.0f32","2.0bf16","1.5dd","3.0_m"] {
+        check_one(f,Float);
+    }
+    // Numbers which are not quite
+    check("1e",&[(Integer,"1"),(Identifier,"e")]);
+    check("0x1.8",&[(Integer,"0x1"),(Float,".8")]);
+    check("1..2",&[(Float,"1."),(Float,".2")]);
+}
+
+#[test]
+fn c_04() {
+    // Character and string literals
+    for c in ["'a'","'\\n'","'\\''","'\\x41'","'\\0'","u8'x'","u'x'","U'x'","L'x'","'ab'"] {
+        check_one(c,Char);
+    }
+    for s in ["\"\"","\"hello\"","\"a\\\"b\"","\"\\\\\"","u8\"x\"","u\"x\"","U\"x\"","L\"x\"","\"a\\\nb\""] {
+        check_one(s,String);
+    }
+    check("f(\"a\", 'b')",&[(Identifier,"f"),(LeftParen,"("),(String,"\"a\""),(Comma,","),(Char,"'b'"),(RightParen,")")]);
+    check("\"abc\nx",&[(UnterminatedString,"\"abc"),(Identifier,"x")]);
+    check("'a\nx",&[(UnterminatedChar,"'a"),(Identifier,"x")]);
+}
+
+#[test]
+fn c_05() {
+    // Raw strings
+    check_one("R\"(a\\b)\"",RawString);
+    check_one("R\"x(a)\")x\"",RawString);
+    check_one("u8R\"--(multi\nline)-\")--\"",RawString);
+    check_one("LR\"()\"",RawString);
+    check_cpp("R\"(a\nb",&[(UnterminatedString,"R\"(a\nb")]);
+    check_cpp("R x",&[(Identifier,"R"),(Identifier,"x")]);
+    // Raw strings are not available in C, or before C++11
+    check("R\"(x)\"",&[(Identifier,"R"),(String,"\"(x)\"")]);
+    assert_eq!(tokens("R\"(x)\"",Standard::Cpp98),[(Identifier,"R"),(String,"\"(x)\"")]);
+}
+
+#[test]
+fn c_06() {
+    // Comments
+    check("a // line\nb",&[(Identifier,"a"),(LineComment,"// line"),(Identifier,"b")]);
+    check("// continued \\\nline\nb",&[(LineComment,"// continued \\\nline"),(Identifier,"b")]);
+    check("a /* block\n * comment **/ b",&[(Identifier,"a"),(BlockComment,"/* block\n * comment **/"),(Identifier,"b")]);
+    check("a /* open",&[(Identifier,"a"),(UnterminatedComment,"/* open")]);
+    check("a/ /b",&[(Identifier,"a"),(Slash,"/"),(Slash,"/"),(Identifier,"b")]);
+}
+
+#[test]
+fn c_07() {
+    // Preprocessor directives
+    check("#include <stdio.h>\nint x;",&[(Preprocessor,"#include <stdio.h>"),(Keyword,"int"),(Identifier,"x"),(Semicolon,";")]);
+    check("  # define MAX(a,b) \\\n    ((a) > (b) ? (a) : (b))\r\nx",&[
+        (Preprocessor,"# define MAX(a,b) \\\n    ((a) > (b) ? (a) : (b))"),(Identifier,"x")]);
+    check("#define X /* a\nb */ 1\nx",&[(Preprocessor,"#define X /* a\nb */ 1"),(Identifier,"x")]);
+    check("/* c */ #if 1\n",&[(BlockComment,"/* c */"),(Preprocessor,"#if 1")]);
+    check("%:pragma once",&[(Preprocessor,"%:pragma once")]);
+    // Comment openers within literals and line comments
+    check("#define S \"/*\"\nint x; /* c */",&[
+        (Preprocessor,"#define S \"/*\""),(Keyword,"int"),(Identifier,"x"),(Semicolon,";"),(BlockComment,"/* c */")]);
+    check("#define C '/' '*'\nx",&[(Preprocessor,"#define C '/' '*'"),(Identifier,"x")]);
+    check("#define X 1 // see /*\nint y; /* z */",&[
+        (Preprocessor,"#define X 1 // see /*"),(Keyword,"int"),(Identifier,"y"),(Semicolon,";"),(BlockComment,"/* z */")]);
+    // Only at the start of a line
+    check("x # y ## z",&[(Identifier,"x"),(Hash,"#"),(Identifier,"y"),(HashHash,"##"),(Identifier,"z")]);
+}
+
+#[test]
+fn c_08() {
+    // Punctuators by longest match
+    check("a<<=b>>=c",&[(Identifier,"a"),(ShiftLeftEqual,"<<="),(Identifier,"b"),(ShiftRightEqual,">>="),(Identifier,"c")]);
+    check("x+++y",&[(Identifier,"x"),(PlusPlus,"++"),(Plus,"+"),(Identifier,"y")]);
+    check("a&&b&c||d|e",&[(Identifier,"a"),(AmpAmp,"&&"),(Identifier,"b"),(Ampersand,"&"),(Identifier,"c"),
+                         (BarBar,"||"),(Identifier,"d"),(Bar,"|"),(Identifier,"e")]);
+    check("f(...)",&[(Identifier,"f"),(LeftParen,"("),(Ellipsis,"..."),(RightParen,")")]);
+    check("..",&[(Dot,"."),(Dot,".")]);
+    check_cpp("a<=>b::c->*d.*e",&[(Identifier,"a"),(Spaceship,"<=>"),(Identifier,"b"),(ColonColon,"::"),(Identifier,"c"),
+                                 (ArrowStar,"->*"),(Identifier,"d"),(DotStar,".*"),(Identifier,"e")]);
+    let all = "{ } [ ] ( ) ; : ... ? . -> ~ ! + - * / % ^ & | = += -= *= /= %= ^= &= |= == != < > <= >= && || << >> <<= >>= ++ -- ,";
+    assert!(tokens(all,Standard::C17).iter().all(|(k,_)| *k != Unknown));
+    assert_eq!(tokens(all,Standard::C17).len(),all.split(' ').count());
+    // Digraphs
+    check("<: :> <% %>",&[(LeftSquare,"<:"),(RightSquare,":>"),(LeftBrace,"<%"),(RightBrace,"%>")]);
+    // Since C++11, `<::` is `<` `::` unless followed by `:` or `>`
+    check_cpp("std::vector<::std::string>",&[(Identifier,"std"),(ColonColon,"::"),(Identifier,"vector"),(Less,"<"),
+                                             (ColonColon,"::"),(Identifier,"std"),(ColonColon,"::"),(Identifier,"string"),(Greater,">")]);
+    check_cpp("a<:::b<::>",&[(Identifier,"a"),(LeftSquare,"<:"),(ColonColon,"::"),(Identifier,"b"),(LeftSquare,"<:"),(RightSquare,":>")]);
+    check("a<::b",&[(Identifier,"a"),(LeftSquare,"<:"),(Colon,":"),(Identifier,"b")]);
+    assert_eq!(tokens("a<::b",Standard::Cpp98)[1],(LeftSquare,"<:"));
+    check("a @ b",&[(Identifier,"a"),(Unknown,"@"),(Identifier,"b")]);
+}
+
+#[test]
+fn c_09() {
+    // Regions are measured in characters
+    let ts = lex("/*λ*/ x",Standard::C17);
+    let regions : Vec<_> = ts.iter().map(|t| (t.start(),t.end())).collect();
+    assert_eq!(regions,[(0,5),(5,6),(6,7)]);
+}
@@ -0,0 +1,101 @@
+use std::io::Write;
+use std::process::{Command,Stdio};
+
+/// Run the command-line tool on some input, returning its exit code
+/// and output.
+fn run(args: &[&str], input: &str) -> (i32,String,String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lexington"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    (output.status.code().unwrap(),stdout,stderr)
+}
+
+#[test]
+fn cli_01() {
+    let (code,out,_) = run(&["--lang","ini"],"[a]\nk = v\n");
+    assert_eq!(code,0);
+    assert_eq!(out,"\
+1:1-1:4  Section     \"[a]\"
+1:4-2:1  Newline     \"\\n\"
+2:1-2:2  Key         \"k\"
+2:2-2:3  WhiteSpace  \" \"
+2:3-2:4  Equals      \"=\"
+2:4-2:5  WhiteSpace  \" \"
+2:5-2:6  Value       \"v\"
+2:6-3:1  Newline     \"\\n\"
+");
+}
+
+#[test]
+fn cli_02() {
+    // Error tokens give a non-zero exit code
+    let (code,out,_) = run(&["-s","tests/specs/calc.lex","--skip"],"x = 1\n  ; y");
+    assert_eq!(code,1);
+    assert_eq!(out,"\
+1:1-1:2  Identifier  \"x\"
+1:3-1:4  Equals      \"=\"
+1:5-1:6  Number      \"1\"
+2:3-2:4  <none>      \";\"  <- error
+2:5-2:6  Identifier  \"y\"
+");
+}
+
+#[test]
+fn cli_03() {
+    let (code,out,_) = run(&["-l","json","-f","json","--skip"],"[\"a\\n\", @]");
+    assert_eq!(code,1);
+    let lines : Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(),5);
+    assert_eq!(lines[1],r#"{"file":"-","kind":"String","text":"\"a\\n\"","start":{"line":1,"column":2},"end":{"line":1,"column":7},"error":false}"#);
+    assert_eq!(lines[3],r#"{"file":"-","kind":"Unknown","text":"@","start":{"line":1,"column":9},"end":{"line":1,"column":10},"error":true}"#);
+}
+
+#[test]
+fn cli_04() {
+    let (code,out,_) = run(&["-l","sexp","-f","colour"],"(a \"b\")");
+    assert_eq!(code,0);
+    assert!(out.contains("\x1b[32m\"b\"\x1b[0m"));
+    // Removing escape codes recovers the input
+    let mut plain = String::new();
+    let mut chars = out.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' { chars.by_ref().find(|c| *c == 'm'); } else { plain.push(c); }
+    }
+    assert_eq!(plain,"(a \"b\")\n");
+}
+
+#[test]
+fn cli_05() {
+    // Spec errors are reported with their line and column
+    let (code,_,err) = run(&["-s","tests/specs/calc.lex","-l","nope"],"");
+    assert_eq!(code,2);
+    assert!(err.contains("unknown language `nope`"));
+    let (code,_,err) = run(&["-s","Cargo.toml"],"");
+    assert_eq!(code,2);
+    assert!(err.contains("Cargo.toml:1:1"),"{err}");
+    let (code,_,err) = run(&["tests/cli.unknown"],"");
+    assert_eq!(code,2);
+    assert!(err.contains("cannot determine language"));
+}
+
+#[test]
+fn cli_06() {
+    // Comments are skipped along with whitespace
+    let (code,out,_) = run(&["-l","c","--skip"],"int x; // one\n/* two */");
+    assert_eq!(code,0);
+    assert_eq!(out,"\
+1:1-1:4  Keyword     \"int\"
+1:5-1:6  Identifier  \"x\"
+1:6-1:7  Semicolon   \";\"
+");
+    let (code,_,_) = run(&["-l","c"],"int x = \"y;");
+    assert_eq!(code,1);
+}
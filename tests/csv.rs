@@ -0,0 +1,102 @@
+use lexington::lang::csv::{lex,records,stream,Dialect,Error,Kind,Reader};
+use lexington::tree::Lexemes;
+use lexington::util::Region;
+
+use Kind::*;
+
+fn tokens(input: &str, dialect: Dialect) -> Vec<(Kind,&str)> {
+    Lexemes::new(input,lex(input,dialect)).map(|(t,s)| (t.kind,s)).collect()
+}
+
+fn check(input: &str, expecting: &[&[&str]]) {
+    check_dialect(input,Dialect::csv(),expecting);
+}
+
+fn check_dialect(input: &str, dialect: Dialect, expecting: &[&[&str]]) {
+    let actual = records(input,dialect).unwrap();
+    assert_eq!(actual,expecting);
+}
+
+#[test]
+fn csv_01() {
+    assert_eq!(tokens("a,b\r\n\"c,d\",e\n",Dialect::csv()),[
+        (Field,"a"),(Delimiter,","),(Field,"b"),(RecordSeparator,"\r\n"),
+        (QuotedField,"\"c,d\""),(Delimiter,","),(Field,"e"),(RecordSeparator,"\n")]);
+    assert_eq!(tokens(",,\r",Dialect::csv()),[(Delimiter,","),(Delimiter,","),(RecordSeparator,"\r")]);
+}
+
+#[test]
+fn csv_02() {
+    // Examples from RFC 4180
+    check("aaa,bbb,ccc\r\nzzz,yyy,xxx\r\n",&[&["aaa","bbb","ccc"],&["zzz","yyy","xxx"]]);
+    check("aaa,bbb,ccc\r\nzzz,yyy,xxx",&[&["aaa","bbb","ccc"],&["zzz","yyy","xxx"]]);
+    check("field_name,field_name,field_name\r\naaa,bbb,ccc",&[&["field_name","field_name","field_name"],&["aaa","bbb","ccc"]]);
+    check("\"aaa\",\"bbb\",\"ccc\"\r\nzzz,yyy,xxx",&[&["aaa","bbb","ccc"],&["zzz","yyy","xxx"]]);
+    check("\"aaa\",\"b\r\nbb\",\"ccc\"\r\nzzz,yyy,xxx",&[&["aaa","b\r\nbb","ccc"],&["zzz","yyy","xxx"]]);
+    check("\"aaa\",\"b\"\"bb\",\"ccc\"",&[&["aaa","b\"bb","ccc"]]);
+}
+
+#[test]
+fn csv_03() {
+    // Empty fields and records
+    check("a,,c",&[&["a","","c"]]);
+    check(",",&[&["",""]]);
+    check("a,\n\"\"",&[&["a",""],&[""]]);
+    check("a\n\n\nb\n",&[&["a"],&["b"]]);
+    check("",&[]);
+    check("  spaced  ,λ",&[&["  spaced  ","λ"]]);
+    check("a\"b,c",&[&["a\"b","c"]]);
+}
+
+#[test]
+fn csv_04() {
+    // Dialects
+    check_dialect("a\tb,c\n\"d\te\"\tf",Dialect::tsv(),&[&["a","b,c"],&["d\te","f"]]);
+    check_dialect("a;'b;''c'",Dialect::csv().delimiter(';').quote('\''),&[&["a","b;'c"]]);
+    check_dialect(r#""a\"b","c""d",e\f"#,Dialect::csv().escape('\\'),&[&["a\"b","c\"d","e\\f"]]);
+    assert_eq!(tokens(r#""a\",b""#,Dialect::csv().escape('\\')),[(QuotedField,r#""a\",b""#)]);
+    assert_eq!(tokens(r#""a\",b""#,Dialect::csv()),[(QuotedField,r#""a\""#),(Delimiter,","),(Field,"b\"")]);
+    assert_eq!(Dialect::default(),Dialect::csv());
+}
+
+#[test]
+fn csv_05() {
+    // Errors
+    assert_eq!(tokens("a,\"b\nc",Dialect::csv()),[(Field,"a"),(Delimiter,","),(UnterminatedQuote,"\"b\nc")]);
+    match records("a,\"b\nc",Dialect::csv()) {
+        Err(Error::UnterminatedQuote(r)) => assert_eq!(r,Region::new(2,6)),
+        r => panic!("unexpected {r:?}")
+    }
+    match records("x\n\"a\"b,c",Dialect::csv()) {
+        Err(e@Error::TrailingText(_)) => {
+            assert_eq!(e.region(),Some(Region::new(5,6)));
+            assert_eq!(e.to_string(),"unexpected text after quoted field");
+        }
+        r => panic!("unexpected {r:?}")
+    }
+    // Records before an error are still returned
+    let mut reader = Reader::new("a\nb\n\"c".as_bytes(),Dialect::csv());
+    assert_eq!(reader.next().unwrap().unwrap(),["a"]);
+    assert_eq!(reader.next().unwrap().unwrap(),["b"]);
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn csv_06() {
+    // Streaming
+    let mut input = String::new();
+    for i in 0..1000 { input.push_str(&format!("{i},\"row {i}\nline two\",x\r\n")); }
+    let tokens : Vec<_> = stream(input.as_bytes(),Dialect::csv()).map(|r| r.unwrap()).collect();
+    assert_eq!(tokens.len(),6000);
+    assert_eq!(tokens[2].1,"\"row 0\nline two\"");
+    let mut count = 0;
+    for (i,r) in Reader::new(input.as_bytes(),Dialect::csv()).enumerate() {
+        assert_eq!(r.unwrap(),[i.to_string(),format!("row {i}\nline two"),"x".to_string()]);
+        count += 1;
+    }
+    assert_eq!(count,1000);
+    // Invalid UTF-8 is reported
+    let bytes : &[u8] = b"a,\xFF";
+    assert!(matches!(Reader::new(bytes,Dialect::csv()).last(),Some(Err(Error::Read(_)))));
+}
@@ -0,0 +1,65 @@
+use lexington::{Any,Lexer,Matcher,Scanner,Token,Unit,Within};
+use lexington::util::{SliceCursor,StrCursor};
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    LeftBrace,
+    RightBrace,
+    Identifier
+}
+
+use Kind::*;
+
+/// Construct a simple scanner for identifiers and braces.
+fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    // [ \n\t]+
+    let whitespace = Any([' ','\n','\t']).one_or_more();
+    // [a..zλ]+
+    let identifier = Within('a'..='z').or('λ').one_or_more();
+    // Construct scanner
+    Unit(whitespace,WhiteSpace)
+        .or(Unit(identifier,Identifier))
+        .or(Unit('(',LeftBrace))
+        .or(Unit(')',RightBrace))
+}
+
+/// Lex the input in all three ways, and check they agree.
+fn check(input: &str, expected: &[(Kind,std::ops::Range<usize>)]) {
+    let chars : Vec<char> = input.chars().collect();
+    let t1 : Vec<Token<Kind>> = Lexer::new(input.chars(),scanner()).collect();
+    let t2 : Vec<Token<Kind>> = Lexer::with_cursor(SliceCursor::new(&chars),scanner()).collect();
+    let t3 : Vec<Token<Kind>> = Lexer::with_cursor(StrCursor::new(input),scanner()).collect();
+    assert_eq!(t1,expected);
+    assert_eq!(t2,expected);
+    assert_eq!(t3,expected);
+}
+
+#[test]
+fn test_cursor_01() {
+    check("",&[]);
+}
+
+#[test]
+fn test_cursor_02() {
+    check("(abc)",&[(LeftBrace,0..1),(Identifier,1..4),(RightBrace,4..5)]);
+}
+
+#[test]
+fn test_cursor_03() {
+    check("(λx λy)",&[(LeftBrace,0..1),(Identifier,1..3),(WhiteSpace,3..4),(Identifier,4..6),(RightBrace,6..7)]);
+}
+
+#[test]
+fn test_cursor_04() {
+    // Lexer stops at the first unrecognised character
+    check("ab?cd",&[(Identifier,0..2)]);
+}
+
+#[test]
+fn test_cursor_05() {
+    let bytes = b"aab";
+    let scanner = Unit(b'a'.one_or_more(),0).or(Unit(b'b',1));
+    let tokens : Vec<Token<u8>> = Lexer::with_cursor(SliceCursor::new(bytes),scanner).collect();
+    assert_eq!(tokens,&[(0,0..2),(1,2..3)]);
+}
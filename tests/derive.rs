@@ -0,0 +1,101 @@
+use lexington::{Lexington,Scanner,Token};
+use lexington::util::StrCursor;
+
+#[derive(Clone,Copy,Debug,PartialEq,Lexington)]
+enum Kind {
+    #[skip]
+    #[pattern(r"[ \t\n]+")]
+    WhiteSpace,
+    #[skip]
+    #[pattern("//[^\n]*")]
+    Comment,
+    #[token("if")]
+    If,
+    #[token("else")]
+    Else,
+    #[pattern("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Identifier,
+    #[pattern(r"\d+(\.\d+)?")]
+    Number,
+    #[pattern(r#""([^"\\]|\\.)*""#)]
+    String,
+    #[token('=')]
+    Equals,
+    #[token("==")]
+    EqualsEquals,
+    #[token("=>")]
+    Arrow,
+    #[token("(")]
+    LeftBrace,
+    #[token(")")]
+    RightBrace
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,Lexington)]
+enum Word {
+    #[pattern("[a-z]+")]
+    #[callback(keyword)]
+    Identifier,
+    Keyword,
+    #[pattern("[a-z]+[0-9]+")]
+    #[callback(|s: &str| (s.len() < 4).then_some(Word::Short))]
+    Short,
+    #[pattern("[a-z0-9]+")]
+    #[priority(-1)]
+    Other,
+    #[skip]
+    #[token(" ")]
+    Space
+}
+
+fn keyword(text: &str) -> Option<Word> {
+    match text {
+        "fn"|"let" => Some(Word::Keyword),
+        _ => Some(Word::Identifier)
+    }
+}
+
+#[test]
+fn derive_01() {
+    use Kind::*;
+    assert_eq!(Kind::lex(""),Vec::<Token<Kind>>::new());
+    assert_eq!(Kind::lex(" x  y12 "),&[(Identifier,1..2),(Identifier,4..7)]);
+    assert_eq!(Kind::lex("12 3.45 // comment\n6"),&[(Number,0..2),(Number,3..7),(Number,19..20)]);
+}
+
+#[test]
+fn derive_02() {
+    use Kind::*;
+    // Longest match, with ties going to tokens
+    assert_eq!(Kind::lex("if iffy else elsewhere"),&[(If,0..2),(Identifier,3..7),(Else,8..12),(Identifier,13..22)]);
+    assert_eq!(Kind::lex("= == => ==="),&[(Equals,0..1),(EqualsEquals,2..4),(Arrow,5..7),(EqualsEquals,8..10),(Equals,10..11)]);
+}
+
+#[test]
+fn derive_03() {
+    use Kind::*;
+    assert_eq!(Kind::lex(r#"("a\"b" "")"#),&[(LeftBrace,0..1),(String,1..7),(String,8..10),(RightBrace,10..11)]);
+    // Lexing stops at the first unmatched character
+    assert_eq!(Kind::lex("x # y"),&[(Identifier,0..1)]);
+}
+
+#[test]
+fn derive_04() {
+    use Kind::*;
+    // Skipped tokens are still produced by the lexer
+    let tokens : Vec<Token<Kind>> = Kind::lexer("if x").collect();
+    assert_eq!(tokens,&[(If,0..2),(WhiteSpace,2..3),(Identifier,3..4)]);
+    assert!(WhiteSpace.is_skipped() && Comment.is_skipped() && !If.is_skipped());
+    let mut input = StrCursor::new("else");
+    assert_eq!(Kind::scanner().scan(&mut input),Some(Else));
+}
+
+#[test]
+fn derive_05() {
+    use Word::*;
+    // Callbacks can reclassify matches
+    assert_eq!(Word::lex("let x fn"),&[(Keyword,0..3),(Identifier,4..5),(Keyword,6..8)]);
+    // Or reject them, leaving lower priority rules
+    assert_eq!(Word::lex("ab1 abc12 12"),&[(Short,0..3),(Other,4..9),(Other,10..12)]);
+    assert!(Space.is_skipped() && !Keyword.is_skipped());
+}
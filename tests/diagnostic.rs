@@ -0,0 +1,99 @@
+use lexington::diagnostic::{Diagnostic,Label,Severity};
+use lexington::util::Region;
+
+const SOURCE : &str = "(define (f x)\n\t(add x y))\n";
+
+#[test]
+fn test_diagnostic_01() {
+    let d = Diagnostic::error("unknown variable `y`")
+        .with_label(Region::new(22,23),"not found in this scope")
+        .with_secondary(Region::new(11,12),"did you mean `x`?")
+        .with_note("variables must be declared before use");
+    let expected = "\
+error: unknown variable `y`
+ --> test.lisp:2:9
+  |
+1 | (define (f x)
+  |            - did you mean `x`?
+2 |     (add x y))
+  |            ^ not found in this scope
+  |
+  = note: variables must be declared before use
+";
+    assert_eq!(d.render("test.lisp",SOURCE),expected);
+}
+
+#[test]
+fn test_diagnostic_02() {
+    // Multi-line region
+    let d = Diagnostic::warning("unused function").with_label(Region::new(8,24),"never called");
+    let expected = "\
+warning: unused function
+ --> test.lisp:1:9
+  |
+1 |   (define (f x)
+  |  _________^
+2 | |     (add x y))
+  | |_____________^ never called
+  |
+";
+    assert_eq!(d.render("test.lisp",SOURCE),expected);
+}
+
+#[test]
+fn test_diagnostic_03() {
+    // Empty region at end of file
+    let d = Diagnostic::new(Severity::Error,"unexpected end of file").with_label(Region::new(26,26),"");
+    let expected = "\
+error: unexpected end of file
+ --> test.lisp:3:1
+  |
+3 |
+  | ^
+  |
+";
+    assert_eq!(d.render("test.lisp",SOURCE),expected);
+}
+
+#[test]
+fn test_diagnostic_04() {
+    // Long regions elide the middle lines
+    let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+    let d = Diagnostic::error("bad").with_label(Region::new(2,19),"here");
+    let expected = "\
+error: bad
+  --> test:2:1
+   |
+ 2 |   b
+   |  _^
+ 3 | | c
+...
+ 9 | | i
+10 | | j
+   | |_^ here
+   |
+";
+    assert_eq!(d.render("test",source),expected);
+}
+
+#[test]
+fn test_diagnostic_05() {
+    let d = Diagnostic::error("oops").with_label(Region::new(1,7),"here");
+    let text = d.render_ansi("test.lisp",SOURCE);
+    assert!(text.starts_with("\x1b[1;31merror\x1b[1m: oops\x1b[0m\n"));
+    assert!(text.contains("\x1b[1;31m ^^^^^^ here\x1b[0m"));
+}
+
+#[test]
+fn test_diagnostic_06() {
+    // Reversed regions are normalised
+    let d = Diagnostic::error("oops").with_label(Region::new(7,1),"here").with_secondary(Region::new(23,8),"");
+    assert_eq!(d.labels[0].region,Region::new(1,7));
+    assert_eq!(d.labels[1].region,Region::new(8,23));
+    let expected = Diagnostic::error("oops").with_label(Region::new(1,7),"here").with_secondary(Region::new(8,23),"");
+    assert_eq!(d.render("test.lisp",SOURCE),expected.render("test.lisp",SOURCE));
+    // Including those constructed directly
+    let mut d = Diagnostic::error("oops");
+    d.labels.push(Label{region: Region::new(23,8), message: String::new(), primary: true});
+    assert!(d.render("test.lisp",SOURCE).contains("--> test.lisp:1:9"));
+}
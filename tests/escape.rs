@@ -0,0 +1,92 @@
+use lexington::escape::{unescape,Dialect,Error};
+use lexington::util::Region;
+
+fn check(text: &str, dialect: Dialect, expecting: &str) {
+    assert_eq!(unescape(text,0,dialect).unwrap(),expecting);
+}
+
+fn check_err(text: &str, dialect: Dialect, expecting: Error) {
+    assert_eq!(unescape(text,0,dialect).unwrap_err(),expecting);
+}
+
+#[test]
+fn escape_01() {
+    // Text without escapes is unchanged
+    for d in [Dialect::C,Dialect::Json,Dialect::Rust,Dialect::Python] {
+        check("",d,"");
+        check("hello λ world",d,"hello λ world");
+        check(r#"a\\b\"c"#,d,"a\\b\"c");
+        check(r"\n\r\t",d,"\n\r\t");
+    }
+}
+
+#[test]
+fn escape_02() {
+    // C
+    check(r"\a\b\f\v\?\'",Dialect::C,"\u{07}\u{08}\u{0C}\u{0B}?'");
+    check(r"\0\12\101\1012",Dialect::C,"\0\nAA2");
+    check(r"\x41\x0041g\xe9",Dialect::C,"AAgé");
+    check(r"\u00e9\U0001F600",Dialect::C,"é\u{1F600}");
+    check("a\\\nb\\\r\nc",Dialect::C,"abc");
+    check_err(r"\q",Dialect::C,Error::InvalidEscape(Region::new(0,2)));
+    check_err(r"ab\xg",Dialect::C,Error::InvalidEscape(Region::new(2,4)));
+    check_err(r"\u12",Dialect::C,Error::InvalidEscape(Region::new(0,4)));
+    check_err(r"\UFFFFFFFF",Dialect::C,Error::InvalidCodePoint(Region::new(0,10)));
+    check_err(r"\uD800",Dialect::C,Error::InvalidCodePoint(Region::new(0,6)));
+    check_err("x\\",Dialect::C,Error::InvalidEscape(Region::new(1,2)));
+}
+
+#[test]
+fn escape_03() {
+    // JSON
+    check(r"\/\b\f",Dialect::Json,"/\u{08}\u{0C}");
+    check(r"\u0041\u00e9\u4E2D",Dialect::Json,"Aé中");
+    check(r"\uD83D\uDE00!",Dialect::Json,"\u{1F600}!");
+    check_err(r"\'",Dialect::Json,Error::InvalidEscape(Region::new(0,2)));
+    check_err(r"\x41",Dialect::Json,Error::InvalidEscape(Region::new(0,2)));
+    check_err("\\\n",Dialect::Json,Error::InvalidEscape(Region::new(0,2)));
+    check_err(r"\uD83D",Dialect::Json,Error::UnpairedSurrogate(Region::new(0,6)));
+    check_err(r"\uD83Dx",Dialect::Json,Error::UnpairedSurrogate(Region::new(0,6)));
+    check_err(r"\uD83DA",Dialect::Json,Error::UnpairedSurrogate(Region::new(0,6)));
+    check_err(r"a\uDE00",Dialect::Json,Error::UnpairedSurrogate(Region::new(1,7)));
+    check_err(r"\uD83D\uZZ",Dialect::Json,Error::InvalidEscape(Region::new(6,8)));
+}
+
+#[test]
+fn escape_04() {
+    // Rust
+    check(r"\0\'\x41\x7F",Dialect::Rust,"\0'A\u{7F}");
+    check(r"\u{41}\u{e9}\u{1F_600}\u{10FFFF}",Dialect::Rust,"Aé\u{1F600}\u{10FFFF}");
+    check("a\\\n    b\\\r\n\n\tc",Dialect::Rust,"abc");
+    check_err(r"\a",Dialect::Rust,Error::InvalidEscape(Region::new(0,2)));
+    check_err(r"\x80",Dialect::Rust,Error::InvalidCodePoint(Region::new(0,4)));
+    check_err(r"\x4",Dialect::Rust,Error::InvalidEscape(Region::new(0,3)));
+    check_err(r"\u0041",Dialect::Rust,Error::InvalidEscape(Region::new(0,6)));
+    check_err(r"\u{}",Dialect::Rust,Error::InvalidEscape(Region::new(0,4)));
+    check_err(r"\u{1234567}",Dialect::Rust,Error::InvalidEscape(Region::new(0,11)));
+    check_err(r"\u{110000}",Dialect::Rust,Error::InvalidCodePoint(Region::new(0,10)));
+    check_err(r"\u{D800}",Dialect::Rust,Error::InvalidCodePoint(Region::new(0,8)));
+    check_err(r"\u{41",Dialect::Rust,Error::InvalidEscape(Region::new(0,5)));
+}
+
+#[test]
+fn escape_05() {
+    // Python
+    check(r"\a\v\101\7\x41",Dialect::Python,"\u{07}\u{0B}A\u{07}A");
+    check(r"\u00e9\U0001F600",Dialect::Python,"é\u{1F600}");
+    check("a\\\nb",Dialect::Python,"ab");
+    check(r"\q\d+\?",Dialect::Python,r"\q\d+\?");
+    check_err(r"\x4",Dialect::Python,Error::InvalidEscape(Region::new(0,3)));
+    check_err(r"\N{DASH}",Dialect::Python,Error::InvalidEscape(Region::new(0,2)));
+    check_err(r"\U00110000",Dialect::Python,Error::InvalidCodePoint(Region::new(0,10)));
+}
+
+#[test]
+fn escape_06() {
+    // Regions are relative to the original source, and in characters
+    let source = "x = \"λ\\q\";";
+    let e = unescape("λ\\q",5,Dialect::C).unwrap_err();
+    assert_eq!(e.region(),Region::new(6,8));
+    assert_eq!(source.chars().skip(6).take(2).collect::<String>(),"\\q");
+    assert_eq!(e.to_string(),"invalid escape sequence");
+}
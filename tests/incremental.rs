@@ -0,0 +1,106 @@
+use lexington::{Any,Edit,Lexer,Matcher,Scanner,Token,Unit,Within,relex};
+use lexington::util::Region;
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    LeftBrace,
+    RightBrace,
+    Identifier,
+    Number
+}
+
+use Kind::*;
+
+fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    let whitespace = Any([' ','\n','\t']).one_or_more();
+    let number = Within('0'..='9').one_or_more();
+    let identifier = Within('a'..='z').or('λ').then(Within('0'..='9').or(Within('a'..='z')).zero_or_more());
+    Unit(whitespace,WhiteSpace)
+        .or(Unit(number,Number))
+        .or(Unit(identifier,Identifier))
+        .or(Unit('(',LeftBrace))
+        .or(Unit(')',RightBrace))
+}
+
+fn lex(input: &str) -> Vec<Token<Kind>> {
+    Lexer::new(input.chars(),scanner()).collect()
+}
+
+/// Apply an edit incrementally, and check the result matches lexing
+/// from scratch.  Returns the range of changed tokens.
+fn check(input: &str, region: Region, text: &str) -> std::ops::Range<usize> {
+    let mut source = input.to_string();
+    let mut tokens = lex(input);
+    let changed = relex(&scanner(),&mut source,&mut tokens,Edit::new(region,text));
+    assert_eq!(tokens,lex(&source),"edit {region:?} => {text:?} on {input:?}");
+    changed
+}
+
+#[test]
+fn test_incremental_01() {
+    // Extend identifier
+    assert_eq!(check("(f x 12)",Region::new(2,2),"oo"),0..2);
+}
+
+#[test]
+fn test_incremental_02() {
+    // Split identifier
+    assert_eq!(check("(abc x)",Region::new(2,3)," "),0..4);
+}
+
+#[test]
+fn test_incremental_03() {
+    // Delete everything
+    assert_eq!(check("(abc x)",Region::new(0,7),""),0..0);
+}
+
+#[test]
+fn test_incremental_04() {
+    // Insert into empty
+    assert_eq!(check("",Region::new(0,0),"(x)"),0..3);
+}
+
+#[test]
+fn test_incremental_05() {
+    // Introduce an error then fix it
+    let mut source = "(abc x) (y z)".to_string();
+    let mut tokens = lex(&source);
+    relex(&scanner(),&mut source,&mut tokens,Edit::new(Region::new(4,5),"?"));
+    assert_eq!(tokens,lex(&source));
+    assert_eq!(tokens.len(),2);
+    relex(&scanner(),&mut source,&mut tokens,Edit::new(Region::new(4,5)," "));
+    assert_eq!(tokens,lex(&source));
+    assert_eq!(tokens.len(),11);
+}
+
+#[test]
+fn test_incremental_06() {
+    // Exhaustively check small edits at every position
+    let input = "(define (f x) (add x 12))\n(λ y)";
+    let n = input.chars().count();
+    for start in 0..=n {
+        for end in start..=(start+3).min(n) {
+            for text in ["", " ", "a", "9", "(", "z z", "?"] {
+                check(input,Region::new(start,end),text);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_incremental_07() {
+    // Reversed regions are normalised
+    let mut source = "(abc x)".to_string();
+    let mut tokens = lex(&source);
+    relex(&scanner(),&mut source,&mut tokens,Edit::new(Region::new(4,1),"y"));
+    assert_eq!(source,"(y x)");
+    assert_eq!(tokens,lex(&source));
+    // Regions past the end are clamped
+    relex(&scanner(),&mut source,&mut tokens,Edit::new(Region::new(4,100),"z)"));
+    assert_eq!(source,"(y xz)");
+    assert_eq!(tokens,lex(&source));
+    relex(&scanner(),&mut source,&mut tokens,Edit::new(Region::new(50,60)," w"));
+    assert_eq!(source,"(y xz) w");
+    assert_eq!(tokens,lex(&source));
+}
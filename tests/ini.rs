@@ -0,0 +1,58 @@
+use lexington::lang::ini::{lex,Kind};
+use lexington::tree::Lexemes;
+
+use Kind::*;
+
+/// Lex some input, ignoring whitespace.
+fn tokens(input: &str) -> Vec<(Kind,&str)> {
+    Lexemes::new(input,lex(input)).map(|(t,s)| (t.kind,s)).filter(|(k,_)| *k != WhiteSpace).collect()
+}
+
+fn check(input: &str, expecting: &[(Kind,&str)]) {
+    assert_eq!(tokens(input),expecting);
+}
+
+#[test]
+fn ini_01() {
+    check("; last modified 1 April 2001\n[owner]\nname = John Doe\norganization = Acme Widgets Inc.\n",&[
+        (Comment,"; last modified 1 April 2001"),(Newline,"\n"),
+        (Section,"[owner]"),(Newline,"\n"),
+        (Key,"name"),(Equals,"="),(Value,"John Doe"),(Newline,"\n"),
+        (Key,"organization"),(Equals,"="),(Value,"Acme Widgets Inc."),(Newline,"\n")]);
+}
+
+#[test]
+fn ini_02() {
+    check("[database]\r\n# use IP address\r\nserver=192.0.2.62\r\nport : 143\r\nfile = \"payroll.dat\"",&[
+        (Section,"[database]"),(Newline,"\r\n"),
+        (Comment,"# use IP address"),(Newline,"\r\n"),
+        (Key,"server"),(Equals,"="),(Value,"192.0.2.62"),(Newline,"\r\n"),
+        (Key,"port"),(Equals,":"),(Value,"143"),(Newline,"\r\n"),
+        (Key,"file"),(Equals,"="),(Value,"\"payroll.dat\"")]);
+}
+
+#[test]
+fn ini_03() {
+    // Keys with spaces and values containing separators
+    check("  file path = C:\\dir ; not a comment  \n",&[
+        (Key,"file path"),(Equals,"="),(Value,"C:\\dir ; not a comment"),(Newline,"\n")]);
+    check("url = http://x.org/?a=b",&[(Key,"url"),(Equals,"="),(Value,"http://x.org/?a=b")]);
+    check("empty =\nflag\n",&[(Key,"empty"),(Equals,"="),(Newline,"\n"),(Key,"flag"),(Newline,"\n")]);
+    check("  ; indented comment",&[(Comment,"; indented comment")]);
+}
+
+#[test]
+fn ini_04() {
+    // Continuation lines
+    check("list = a, \\\n  b, \\\r\n  c\nnext = 1",&[
+        (Key,"list"),(Equals,"="),(Value,"a, \\\n  b, \\\r\n  c"),(Newline,"\n"),
+        (Key,"next"),(Equals,"="),(Value,"1")]);
+    check("path = C:\\temp\\",&[(Key,"path"),(Equals,"="),(Value,"C:\\temp\\")]);
+}
+
+#[test]
+fn ini_05() {
+    // Unterminated section headers
+    check("[a\nb=1",&[(Unknown,"["),(Key,"a"),(Newline,"\n"),(Key,"b"),(Equals,"="),(Value,"1")]);
+    check("[a b.c]",&[(Section,"[a b.c]")]);
+}
@@ -0,0 +1,201 @@
+use std::borrow::Cow;
+use lexington::json::{lex,number_value,Error,Event,EventParser,Kind,Mode};
+use lexington::util::Region;
+
+use Event::*;
+
+/// Parse a document into a sequence of events (without regions).
+fn events(input: &str, mode: Mode) -> Result<Vec<Event<'_>>,Error> {
+    EventParser::new(input,mode).map(|r| r.map(|(e,_)| e)).collect()
+}
+
+fn check_ok(input: &str, expecting: &[Event]) {
+    assert_eq!(events(input,Mode::Strict).unwrap(),expecting);
+    // Anything strict is also lenient
+    assert_eq!(events(input,Mode::Lenient).unwrap(),expecting);
+}
+
+fn check_lenient(input: &str, expecting: &[Event]) {
+    assert!(events(input,Mode::Strict).is_err());
+    assert_eq!(events(input,Mode::Lenient).unwrap(),expecting);
+}
+
+fn check_err(input: &str, mode: Mode, expecting: Error) {
+    assert_eq!(events(input,mode).unwrap_err(),expecting);
+}
+
+/// Check a syntax error arises at a given region.
+fn check_syntax_err(input: &str, mode: Mode, found: Option<Kind>, region: Region) {
+    match events(input,mode).unwrap_err() {
+        Error::Syntax(e) => {
+            assert_eq!(e.found.map(|t| t.kind),found);
+            assert_eq!(e.region,region);
+        }
+        e => panic!("unexpected error {e:?}")
+    }
+}
+
+fn str(s: &str) -> Event<'_> {
+    String(Cow::Borrowed(s))
+}
+
+fn key(s: &str) -> Event<'_> {
+    Key(Cow::Borrowed(s))
+}
+
+#[test]
+fn json_01() {
+    check_ok("null",&[Null]);
+    check_ok(" true ",&[Bool(true)]);
+    check_ok("false",&[Bool(false)]);
+    check_ok("\"hi\"",&[str("hi")]);
+    check_ok("-1.5e3",&[Number("-1.5e3")]);
+    check_ok("[]",&[StartArray,EndArray]);
+    check_ok("{}",&[StartObject,EndObject]);
+}
+
+#[test]
+fn json_02() {
+    check_ok("[1, [2, []], {}]",&[StartArray,Number("1"),StartArray,Number("2"),StartArray,EndArray,EndArray,StartObject,EndObject,EndArray]);
+    check_ok("{\"a\": 1, \"b\": [true, null]}",&[StartObject,key("a"),Number("1"),key("b"),StartArray,Bool(true),Null,EndArray,EndObject]);
+    check_ok("{\"a\":{\"b\":{}}}",&[StartObject,key("a"),StartObject,key("b"),StartObject,EndObject,EndObject,EndObject]);
+}
+
+#[test]
+fn json_03() {
+    let input = "{\"λ\": [1, \"x\"]}";
+    let regions : Vec<_> = EventParser::new(input,Mode::Strict).map(|r| r.unwrap().1).collect();
+    assert_eq!(regions,[Region::new(0,1),Region::new(1,4),Region::new(6,7),Region::new(7,8),
+                        Region::new(10,13),Region::new(13,14),Region::new(14,15)]);
+    let mut parser = EventParser::new("[[",Mode::Strict);
+    parser.next();
+    parser.next();
+    assert_eq!(parser.depth(),2);
+}
+
+#[test]
+fn json_04() {
+    // Escapes
+    check_ok(r#""a\"b\\c\/d""#,&[String(Cow::Owned("a\"b\\c/d".to_string()))]);
+    check_ok(r#""\b\f\n\r\t""#,&[String(Cow::Owned("\u{08}\u{0C}\n\r\t".to_string()))]);
+    check_ok(r#""\u0041\u00e9\u4E2D""#,&[String(Cow::Owned("A\u{E9}\u{4E2D}".to_string()))]);
+    // Surrogate pairs
+    check_ok(r#""\uD83D\uDE00""#,&[String(Cow::Owned("\u{1F600}".to_string()))]);
+    check_ok(r#""x\ud834\udd1ey""#,&[String(Cow::Owned("x\u{1D11E}y".to_string()))]);
+    // Unescaped non-ASCII
+    check_ok("\"\u{1F600}\"",&[str("\u{1F600}")]);
+}
+
+#[test]
+fn json_05() {
+    check_err(r#"["\q"]"#,Mode::Strict,Error::InvalidEscape(Region::new(2,4)));
+    check_err(r#""ab\u12""#,Mode::Strict,Error::InvalidEscape(Region::new(3,7)));
+    check_err(r#""\u12G4""#,Mode::Strict,Error::InvalidEscape(Region::new(1,7)));
+    check_err(r#""\uD83D""#,Mode::Strict,Error::UnpairedSurrogate(Region::new(1,7)));
+    check_err(r#""\uD83Dx""#,Mode::Strict,Error::UnpairedSurrogate(Region::new(1,7)));
+    check_err(r#""\uD83DA""#,Mode::Strict,Error::UnpairedSurrogate(Region::new(1,7)));
+    check_err(r#""a\uDE00""#,Mode::Strict,Error::UnpairedSurrogate(Region::new(2,8)));
+    check_err("\"a\tb\"",Mode::Strict,Error::ControlCharacter(Region::new(2,3)));
+    check_err("[\"abc\n]",Mode::Strict,Error::UnterminatedString(Region::new(1,5)));
+}
+
+#[test]
+fn json_06() {
+    // Valid RFC 8259 numbers
+    for n in ["0","-0","12","-12","0.5","1.25","1e5","1E+5","1e-5","-0.0e0"] {
+        check_ok(n,&[Number(n)]);
+    }
+    // Invalid RFC 8259 numbers
+    for n in ["01","+1",".5","1.","0x1F","-Infinity","NaN","1.e3"] {
+        let region = Region::new(0,n.chars().count());
+        check_err(n,Mode::Strict,Error::InvalidNumber(region));
+        assert_eq!(events(n,Mode::Lenient).unwrap(),[Number(n)]);
+    }
+    check_err("-",Mode::Strict,Error::UnexpectedCharacter(Region::new(0,1)));
+    check_syntax_err("[1, 2e]",Mode::Strict,Some(Kind::Identifier),Region::new(5,6));
+}
+
+#[test]
+fn json_07() {
+    assert_eq!(number_value("-1.5e3"),Some(-1500.0));
+    assert_eq!(number_value("0x1F"),Some(31.0));
+    assert_eq!(number_value("-0x10"),Some(-16.0));
+    assert_eq!(number_value("+.5"),Some(0.5));
+    assert_eq!(number_value("5."),Some(5.0));
+    assert_eq!(number_value("-Infinity"),Some(f64::NEG_INFINITY));
+    assert!(number_value("NaN").unwrap().is_nan());
+    assert_eq!(number_value("x"),None);
+}
+
+#[test]
+fn json_08() {
+    // Comments
+    check_lenient("// leading\n[1, /* inner */ 2] // trailing",&[StartArray,Number("1"),Number("2"),EndArray]);
+    check_lenient("/* a ** b **/ null",&[Null]);
+    check_err("[1, /* x */ 2]",Mode::Strict,Error::NotPermitted(Region::new(4,11)));
+    check_err("[1 /* x",Mode::Lenient,Error::UnterminatedComment(Region::new(3,7)));
+    // Trailing commas
+    check_lenient("[1, 2,]",&[StartArray,Number("1"),Number("2"),EndArray]);
+    check_lenient("{\"a\": 1,}",&[StartObject,key("a"),Number("1"),EndObject]);
+    check_syntax_err("[1,]",Mode::Strict,Some(Kind::RightSquare),Region::new(3,4));
+    check_syntax_err("[,]",Mode::Lenient,Some(Kind::Comma),Region::new(1,2));
+}
+
+#[test]
+fn json_09() {
+    // Single quotes
+    check_lenient("'it\\'s'",&[String(Cow::Owned("it's".to_string()))]);
+    check_lenient("{'a': \"'\"}",&[StartObject,key("a"),str("'"),EndObject]);
+    check_err("'a'",Mode::Strict,Error::NotPermitted(Region::new(0,3)));
+    // Unquoted keys
+    check_lenient("{a: 1, $b_2: null, nullable: true}",&[StartObject,key("a"),Number("1"),key("$b_2"),Null,key("nullable"),Bool(true),EndObject]);
+    check_syntax_err("{a: 1}",Mode::Strict,Some(Kind::Identifier),Region::new(1,2));
+    // JSON5 escapes and line continuations
+    check_lenient(r#""\x41\v\0\'""#,&[String(Cow::Owned("A\u{0B}\0'".to_string()))]);
+    check_lenient("\"a\\\nb\"",&[String(Cow::Owned("ab".to_string()))]);
+    check_lenient("\"a\\\r\nb\"",&[String(Cow::Owned("ab".to_string()))]);
+    check_lenient("\"a\tb\"",&[String(Cow::Owned("a\tb".to_string()))]);
+    check_err(r#""\x4""#,Mode::Lenient,Error::InvalidEscape(Region::new(1,4)));
+    // Additional whitespace
+    check_lenient("\u{FEFF}\u{A0}[]",&[StartArray,EndArray]);
+    check_err(" \u{A0}1",Mode::Strict,Error::NotPermitted(Region::new(1,2)));
+}
+
+#[test]
+fn json_10() {
+    // Syntax errors
+    check_syntax_err("",Mode::Strict,None,Region::new(0,0));
+    check_syntax_err("[1",Mode::Strict,None,Region::new(2,2));
+    check_syntax_err("[1 2]",Mode::Strict,Some(Kind::Number),Region::new(3,4));
+    check_syntax_err("{\"a\" 1}",Mode::Strict,Some(Kind::Number),Region::new(5,6));
+    check_syntax_err("{\"a\": }",Mode::Strict,Some(Kind::RightBrace),Region::new(6,7));
+    check_syntax_err("{1: 2}",Mode::Strict,Some(Kind::Number),Region::new(1,2));
+    check_syntax_err("[1]]",Mode::Strict,Some(Kind::RightSquare),Region::new(3,4));
+    check_syntax_err("1 2",Mode::Strict,Some(Kind::Number),Region::new(2,3));
+    check_err("[@]",Mode::Strict,Error::UnexpectedCharacter(Region::new(1,2)));
+    check_err("nul",Mode::Strict,Error::Syntax(lexington::SyntaxError{
+        found: Some(lexington::Token::new(Kind::Identifier,0..3)),
+        expected: vec![Kind::LeftBrace,Kind::LeftSquare,Kind::String,Kind::Number,Kind::True,Kind::False,Kind::Null],
+        region: Region::new(0,3)
+    }));
+    let e = events("{\"a\" 1}",Mode::Strict).unwrap_err();
+    assert_eq!(e.to_string(),"expected Colon, found Number");
+}
+
+#[test]
+fn json_11() {
+    // Parsing stops at the first error
+    let mut parser = EventParser::new("[1, x, 2]",Mode::Strict);
+    assert_eq!(parser.next().unwrap().unwrap().0,StartArray);
+    assert_eq!(parser.next().unwrap().unwrap().0,Number("1"));
+    assert!(parser.next().unwrap().is_err());
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn json_12() {
+    let kinds : Vec<_> = lex("{\"a\": [1, true]} // x").iter().map(|t| t.kind).collect();
+    assert_eq!(kinds,[Kind::LeftBrace,Kind::String,Kind::Colon,Kind::WhiteSpace,Kind::LeftSquare,Kind::Number,
+                      Kind::Comma,Kind::WhiteSpace,Kind::True,Kind::RightSquare,Kind::RightBrace,Kind::WhiteSpace,
+                      Kind::LineComment]);
+}
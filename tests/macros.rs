@@ -0,0 +1,78 @@
+use lexington::{lexer,Lexer,Token};
+use lexington::number;
+
+lexer! {
+    /// As for `tests/simple.rs`.
+    enum Simple {
+        WhiteSpace = [' ','\n','\t']+;
+        Number = ['0'..='9']+;
+        Identifier = ['a'..='z','A'..='Z','_'] ['0'..='9','a'..='z','A'..='Z','_']*;
+        LeftBrace = '(';
+        RightBrace = ')';
+    }
+}
+
+lexer! {
+    pub enum Kind {
+        skip WhiteSpace = [' ','\t','\r','\n']+;
+        skip Comment = "//" [^'\n']*;
+        /// A keyword
+        Keyword = ("let" | "fn") ['a'..='z','_']*;
+        Hex = {number::hexadecimal(Some('_'))};
+        Binary = '0' ('b' | 'B') ['0','1']+;
+        Number = ['0'..='9']+ ('.' ['0'..='9']+)?;
+        String = '"' ([^'"','\\'] | '\\' any)* '"';
+        Identifier = ['a'..='z','_'] ['a'..='z','0'..='9','_']*;
+        Arrow = "->";
+        Minus = '-';
+        Equals = '=';
+        Semicolon = ';';
+    }
+}
+
+#[test]
+fn macros_01() {
+    use Simple::*;
+    let tokens : Vec<Token<Simple>> = Lexer::new("(hE110_w0R1d  12)".chars(),Simple::scanner()).collect();
+    assert_eq!(tokens,&[(LeftBrace,0..1),(Identifier,1..12),(WhiteSpace,12..14),(Number,14..16),(RightBrace,16..17)]);
+    assert!(!WhiteSpace.is_skipped());
+}
+
+#[test]
+fn macros_02() {
+    use Kind::*;
+    assert_eq!(Kind::lex(""),Vec::<Token<Kind>>::new());
+    assert_eq!(Kind::lex("  \n"),Vec::<Token<Kind>>::new());
+    assert_eq!(Kind::lex("x = 1; // one\n"),&[(Identifier,0..1),(Equals,2..3),(Number,4..5),(Semicolon,5..6)]);
+    assert!(WhiteSpace.is_skipped() && Comment.is_skipped() && !Number.is_skipped());
+}
+
+#[test]
+fn macros_03() {
+    use Kind::*;
+    // Rules are tried in order, so "letter" is a keyword
+    assert_eq!(Kind::lex("let fn letter"),&[(Keyword,0..3),(Keyword,4..6),(Keyword,7..13)]);
+    assert_eq!(Kind::lex("-> -"),&[(Arrow,0..2),(Minus,3..4)]);
+}
+
+#[test]
+fn macros_04() {
+    use Kind::*;
+    assert_eq!(Kind::lex("12 1.5 1."),&[(Number,0..2),(Number,3..6),(Number,7..8)]);
+    assert_eq!(Kind::lex("0xFF_FF 0Xa"),&[(Hex,0..7),(Hex,8..11)]);
+    assert_eq!(Kind::lex("0b101 0B1"),&[(Binary,0..5),(Binary,6..9)]);
+}
+
+#[test]
+fn macros_05() {
+    use Kind::*;
+    assert_eq!(Kind::lex(r#""a\"b" "" "\\""#),&[(String,0..6),(String,7..9),(String,10..14)]);
+}
+
+#[test]
+fn macros_06() {
+    use Kind::*;
+    // Lexing stops at the first unmatched character
+    assert_eq!(Kind::lex("x # y"),&[(Identifier,0..1)]);
+    assert_eq!(Kind::lex("\"abc"),Vec::<Token<Kind>>::new());
+}
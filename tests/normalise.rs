@@ -0,0 +1,93 @@
+use lexington::{Any,Lexer,Matcher,Scanner,Token,Unit,Within};
+use lexington::util::{Normalise,Region};
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    NewLine,
+    Word
+}
+
+use Kind::*;
+
+fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    Unit('\n',NewLine).or(Unit(Within('a'..='z').one_or_more(),Word))
+}
+
+/// Lex a given input after normalisation, returning the tokens along
+/// with their regions in the original input.
+fn lex(input: &str) -> Vec<(Token<Kind>,Region)> {
+    let mut lexer = Lexer::new(Normalise::new(input.chars(),true),scanner());
+    let mut tokens = Vec::new();
+    while let Some(t) = lexer.next() {
+        let map = lexer.get_ref().get_ref().map();
+        tokens.push((t,map.region(t.region)));
+    }
+    tokens
+}
+
+#[test]
+fn test_normalise_01() {
+    let tokens = lex("ab\ncd");
+    assert_eq!(tokens,vec![
+        (Token::new(Word,0..2),Region::new(0,2)),
+        (Token::new(NewLine,2..3),Region::new(2,3)),
+        (Token::new(Word,3..5),Region::new(3,5))]);
+}
+
+#[test]
+fn test_normalise_02() {
+    let tokens = lex("\u{FEFF}ab\r\ncd\rx");
+    assert_eq!(tokens,vec![
+        (Token::new(Word,0..2),Region::new(1,3)),
+        (Token::new(NewLine,2..3),Region::new(3,5)),
+        (Token::new(Word,3..5),Region::new(5,7)),
+        (Token::new(NewLine,5..6),Region::new(7,8)),
+        (Token::new(Word,6..7),Region::new(8,9))]);
+}
+
+#[test]
+fn test_normalise_03() {
+    // Line endings left alone
+    let text : String = Normalise::new("\u{FEFF}a\r\nb".chars(),false).collect();
+    assert_eq!(text,"a\r\nb");
+}
+
+#[test]
+fn test_normalise_04() {
+    // Byte streams map back to byte offsets
+    let input = "\u{FEFF}x\r\n\r\ny".as_bytes();
+    let mut norm = Normalise::new(input.iter().copied(),true);
+    let bytes : Vec<u8> = norm.by_ref().collect();
+    assert_eq!(bytes,b"x\n\ny");
+    let map = norm.into_map();
+    assert_eq!(map.original(0),3);
+    assert_eq!(map.region(Region::new(1,3)),Region::new(4,8));
+    assert_eq!(map.original(3),8);
+}
+
+#[test]
+fn test_normalise_05() {
+    // Partial byte order mark is not stripped
+    let bytes : Vec<u8> = Normalise::new(b"\xEF\xBBz".iter().copied(),true).collect();
+    assert_eq!(bytes,b"\xEF\xBBz");
+}
+
+#[test]
+fn test_normalise_06() {
+    let whitespace = Any([' ','\n']).one_or_more();
+    let lexer = Lexer::new(Normalise::new("a \r\n b".chars(),true),Unit(whitespace,0).or(Unit('a',1)));
+    let tokens : Vec<_> = lexer.collect();
+    assert_eq!(tokens,&[(1,0..1),(0,1..4)]);
+}
+
+#[test]
+fn test_normalise_07() {
+    // Runs of line endings, including straight after a byte order mark
+    let input = "\u{FEFF}\r\n\r\n\r\nx\r\ny".as_bytes();
+    let mut norm = Normalise::new(input.iter().copied(),true);
+    let bytes : Vec<u8> = norm.by_ref().collect();
+    assert_eq!(bytes,b"\n\n\nx\ny");
+    let map = norm.into_map();
+    let offsets : Vec<usize> = (0..=bytes.len()).map(|i| map.original(i)).collect();
+    assert_eq!(offsets,&[3,5,7,9,10,12,13]);
+}
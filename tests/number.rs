@@ -0,0 +1,139 @@
+use lexington::{Lexer,Matcher,Token,Unit};
+use lexington::number::{binary,decimal,float,hex_float,hexadecimal,integer,number,octal,Suffix};
+use lexington::number::{to_big,to_f64,to_i64,to_u128,BigInt,Error};
+use lexington::util::{Region,StrCursor};
+
+/// Determine how much of some input a matcher consumes (if any).
+fn matched<M:Matcher<Item=char>>(m: M, input: &str) -> Option<usize> {
+    let mut cursor = StrCursor::new(input);
+    m.matches(&mut cursor).then(|| cursor.byte_offset())
+}
+
+const R : Region = Region{start: 3, end: 8};
+
+#[test]
+fn number_01() {
+    assert_eq!(matched(decimal(None),"123+"),Some(3));
+    assert_eq!(matched(decimal(None),"1_000"),Some(1));
+    assert_eq!(matched(decimal(Some('_')),"1_000"),Some(5));
+    assert_eq!(matched(decimal(Some('_')),"1__0"),Some(1));
+    assert_eq!(matched(decimal(Some('_')),"1_"),Some(1));
+    assert_eq!(matched(decimal(Some('_')),"_1"),None);
+    assert_eq!(matched(decimal(Some('\'')),"1'000'000"),Some(9));
+    assert_eq!(matched(hexadecimal(Some('_')),"0xDEAD_beef"),Some(11));
+    assert_eq!(matched(hexadecimal(None),"0x"),None);
+    assert_eq!(matched(octal(None),"0o758"),Some(4));
+    assert_eq!(matched(binary(None),"0B1012"),Some(5));
+    assert_eq!(matched(integer(None),"0x1F"),Some(4));
+    assert_eq!(matched(integer(None),"0b"),Some(1));
+}
+
+#[test]
+fn number_02() {
+    for f in ["1.5","1.","0.5e10","1e10","1E-3","1.5e+3"] {
+        assert_eq!(matched(float(None),f),Some(f.len()),"{f}");
+    }
+    assert_eq!(matched(float(Some('_')),"1_000.000_1e1_0"),Some(15));
+    assert_eq!(matched(float(None),"12"),None);
+    assert_eq!(matched(float(None),"1e"),None);
+    assert_eq!(matched(float(None),"1.e"),Some(2));
+    for f in ["0x1.8p3","0x.8P-1","0x1p0","0XA.p+2"] {
+        assert_eq!(matched(hex_float(None),f),Some(f.len()),"{f}");
+    }
+    assert_eq!(matched(hex_float(None),"0x1.8"),None);
+    assert_eq!(matched(number(None),"0x1.8"),Some(3));
+    assert_eq!(matched(number(None),"0x1p4"),Some(5));
+    assert_eq!(matched(number(None),"42."),Some(3));
+}
+
+#[test]
+fn number_03() {
+    // Suffixes are matched by longest match
+    let int = integer(Some('_')).then(Suffix(&["u","u8","u16","i","i8","i128"]).optional());
+    assert_eq!(matched(int,"7u16"),Some(4));
+    let int = integer(Some('_')).then(Suffix(&["u","u8","u16","i","i8","i128"]).optional());
+    assert_eq!(matched(int,"7i12"),Some(2));
+    let tokens : Vec<Token<()>> = Lexer::new("1u8".chars(),Unit(number(None).then(Suffix(&["u8"])),())).collect();
+    assert_eq!(tokens,[Token::new((),0..3)]);
+    assert_eq!(matched(Suffix(&[]),"x"),None);
+}
+
+#[test]
+fn number_04() {
+    assert_eq!(to_i64("0",R),Ok(0));
+    assert_eq!(to_i64("-42",R),Ok(-42));
+    assert_eq!(to_i64("+1_000",R),Ok(1000));
+    assert_eq!(to_i64("0x7f",R),Ok(127));
+    assert_eq!(to_i64("-0o17",R),Ok(-15));
+    assert_eq!(to_i64("0b1010",R),Ok(10));
+    assert_eq!(to_i64("017",R),Ok(17));
+    assert_eq!(to_i64("9223372036854775807",R),Ok(i64::MAX));
+    assert_eq!(to_i64("-9223372036854775808",R),Ok(i64::MIN));
+    assert_eq!(to_i64("9223372036854775808",R),Err(Error::Overflow(R)));
+    assert_eq!(to_i64("0xFFFFFFFFFFFFFFFF",R),Err(Error::Overflow(R)));
+    assert_eq!(to_i64("",R),Err(Error::Malformed(R)));
+    assert_eq!(to_i64("0x",R),Err(Error::Malformed(R)));
+    assert_eq!(to_i64("12a",R),Err(Error::Malformed(R)));
+    assert_eq!(to_i64("0b102",R),Err(Error::Malformed(R)));
+    assert_eq!(to_i64("1.5",R),Err(Error::Malformed(R)));
+}
+
+#[test]
+fn number_05() {
+    assert_eq!(to_u128("340282366920938463463374607431768211455",R),Ok(u128::MAX));
+    assert_eq!(to_u128("0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ffff",R),Ok(u128::MAX));
+    assert_eq!(to_u128("340282366920938463463374607431768211456",R),Err(Error::Overflow(R)));
+    assert_eq!(to_u128("-1",R),Err(Error::Overflow(R)));
+    assert_eq!(to_u128("-0",R),Ok(0));
+}
+
+#[test]
+fn number_06() {
+    let n = to_big("-123456789012345678901234567890",R).unwrap();
+    assert!(n.negative);
+    assert_eq!(n.to_string(),"-123456789012345678901234567890");
+    assert_eq!(n.to_i64(),None);
+    assert_eq!(to_big("0x1_0000_0000_0000_0000",R).unwrap().to_string(),"18446744073709551616");
+    assert_eq!(to_big("1000000000",R).unwrap().to_string(),"1000000000");
+    assert_eq!(to_big("-0",R).unwrap(),BigInt::default());
+    assert_eq!(to_big("000",R).unwrap().to_string(),"0");
+    assert_eq!(to_big("-9223372036854775808",R).unwrap().to_i64(),Some(i64::MIN));
+    assert_eq!(to_big("255",R).unwrap().to_u128(),Some(255));
+    assert_eq!(to_big("-255",R).unwrap().to_u128(),None);
+    assert_eq!(BigInt::from(-5),to_big("-5",R).unwrap());
+    assert_eq!(BigInt::from(i64::MIN).to_string(),"-9223372036854775808");
+    assert_eq!(to_big("1e3",R),Err(Error::Malformed(R)));
+}
+
+#[test]
+fn number_07() {
+    assert_eq!(to_f64("1.5",R),Ok(1.5));
+    assert_eq!(to_f64("-.5",R),Ok(-0.5));
+    assert_eq!(to_f64("1.",R),Ok(1.0));
+    assert_eq!(to_f64("1_000.5e-1",R),Ok(100.05));
+    assert_eq!(to_f64("42",R),Ok(42.0));
+    assert_eq!(to_f64("0x10",R),Ok(16.0));
+    assert_eq!(to_f64("0x1.8p3",R),Ok(12.0));
+    assert_eq!(to_f64("0x.8P-1",R),Ok(0.25));
+    assert_eq!(to_f64("-0x1p-2",R),Ok(-0.25));
+    assert_eq!(to_f64("0x1.fffffffffffffp1023",R),Ok(f64::MAX));
+    assert_eq!(to_f64("1e400",R),Err(Error::Overflow(R)));
+    assert_eq!(to_f64("0x1p1024",R),Err(Error::Overflow(R)));
+    for s in ["","1.2.3","1e","inf","NaN","0x1.8","0xp1","e5"] {
+        assert_eq!(to_f64(s,R),Err(Error::Malformed(R)),"{s}");
+    }
+    assert_eq!(Error::Overflow(R).region(),R);
+    assert_eq!(Error::Malformed(R).to_string(),"malformed number");
+}
+
+#[test]
+fn number_08() {
+    // Long mantissas with extreme exponents
+    let tiny = to_f64("0x1p-1000",R).unwrap();
+    assert_eq!(tiny,2f64.powi(-500) * 2f64.powi(-500));
+    assert_eq!(to_f64("0x1.000000000000000000000000000000p-1000",R),Ok(tiny));
+    assert_eq!(to_f64("0x1000000000000000000000000000000p-1120",R),Ok(tiny));
+    assert_eq!(to_f64("0x0.000000000000000000000000000001p1000",R),Ok(2f64.powi(880)));
+    assert_eq!(to_f64("0x1p-1074",R),Ok(f64::from_bits(1)));
+    assert_eq!(to_f64("0x1.fffffffffffff8p1023",R),Err(Error::Overflow(R)));
+}
@@ -0,0 +1,145 @@
+use lexington::{Any,Lexer,Matcher,Scanner,SyntaxError,Token,TokenStream,Unit,Within};
+use lexington::parser::{Match,Parser,Tok};
+use lexington::util::Region;
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    Let,
+    Ident,
+    Number,
+    Equals,
+    Comma,
+    Semi,
+    LeftSquare,
+    RightSquare
+}
+
+use Kind::*;
+
+/// A simple expression language of numbers, variables and lists.
+#[derive(Clone,Debug,PartialEq)]
+enum Expr {
+    Number(u32,Region),
+    Var(Region),
+    List(Vec<Expr>,Region)
+}
+
+fn lex(input: &str) -> TokenStream<Kind> {
+    let whitespace = Any([' ','\n']).one_or_more();
+    let ident = Within('a'..='z').one_or_more();
+    let number = Within('0'..='9').one_or_more();
+    let scanner = Unit(whitespace,WhiteSpace)
+        .or(Unit('l'.then('e').then('t').then(' '),Let))
+        .or(Unit(ident,Ident))
+        .or(Unit(number,Number))
+        .or(Unit('=',Equals))
+        .or(Unit(',',Comma))
+        .or(Unit(';',Semi))
+        .or(Unit('[',LeftSquare))
+        .or(Unit(']',RightSquare));
+    let tokens : Vec<Token<Kind>> = Lexer::new(input.chars(),scanner).collect();
+    TokenStream::with_trivia(tokens,&[WhiteSpace])
+}
+
+/// Parse an expression (which is recursive).
+fn expr<'a>(input: &'a str) -> impl Fn(&mut TokenStream<Kind>) -> Result<Expr,SyntaxError<Kind>> + 'a {
+    move |stream| {
+        let number = Tok(Number).map(|t| Expr::Number(input[t.range()].parse().unwrap(),t.region));
+        let var = Tok(Ident).map(|t| Expr::Var(t.region));
+        let list = expr(input).separated_by(Tok(Comma))
+            .delimited(Tok(LeftSquare),Tok(RightSquare))
+            .spanned()
+            .map(|(es,r)| Expr::List(es,r));
+        number.or(var).or(list).parse(stream)
+    }
+}
+
+/// Parse a sequence of declarations `let x = e;`
+fn decls(input: &str) -> Result<Vec<(Region,Expr)>,SyntaxError<Kind>> {
+    let mut stream = lex(input);
+    let decl = Tok(Let).then(Tok(Ident)).then(Tok(Equals)).then(expr(input)).then(Tok(Semi))
+        .map(|((((_,x),_),e),_)| (x.region,e));
+    let result = decl.many().parse(&mut stream)?;
+    match stream.is_eof() {
+        true => Ok(result),
+        false => Err(stream.error())
+    }
+}
+
+#[test]
+fn test_parser_01() {
+    assert_eq!(decls("let x = 1;"),Ok(vec![(Region::new(4,5),Expr::Number(1,Region::new(8,9)))]));
+}
+
+#[test]
+fn test_parser_02() {
+    let expected = Expr::List(vec![
+        Expr::Var(Region::new(9,10)),
+        Expr::List(vec![],Region::new(12,14)),
+        Expr::List(vec![Expr::Number(22,Region::new(17,19))],Region::new(16,20))
+    ],Region::new(8,21));
+    assert_eq!(decls("let x = [y, [], [22]];\nlet y = 2;"),Ok(vec![
+        (Region::new(4,5),expected),
+        (Region::new(27,28),Expr::Number(2,Region::new(31,32)))]));
+}
+
+#[test]
+fn test_parser_03() {
+    // Error reports all alternatives
+    let e = decls("let x = ;").unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Semi,8..9)));
+    assert_eq!(e.expected,vec![Number,Ident,LeftSquare]);
+}
+
+#[test]
+fn test_parser_04() {
+    // Error deep inside a list
+    let e = decls("let x = [1, 2 3];").unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Number,14..15)));
+    assert_eq!(e.expected,vec![Comma,RightSquare]);
+}
+
+#[test]
+fn test_parser_05() {
+    // Trailing garbage
+    let e = decls("let x = 1; x").unwrap_err();
+    assert_eq!(e.region,Region::new(11,12));
+    assert_eq!(e.expected,vec![Let]);
+}
+
+#[test]
+fn test_parser_06() {
+    // Matchers over token kinds
+    let mut stream = lex("x = y = 1");
+    let chain = Match(Ident.then(Equals).one_or_more()).parse(&mut stream).unwrap();
+    assert_eq!(chain.len(),4);
+    assert!(Match(Ident).parse(&mut stream).is_err());
+    assert_eq!(Tok(Number).parse(&mut stream),Ok(Token::new(Number,8..9)));
+}
+
+#[test]
+fn test_parser_07() {
+    // Alternatives are not tried once the first has consumed a token
+    let pair = Tok(Ident).then(Tok(Equals)).map(|(x,_)| x);
+    let single = Tok(Ident).then(Tok(Semi)).map(|(x,_)| x);
+    let mut stream = lex("x;");
+    let e = pair.or(single).parse(&mut stream).unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Semi,1..2)));
+    assert_eq!(e.expected,vec![Equals]);
+}
+
+#[test]
+fn test_parser_08() {
+    // Matcher errors report only the token found
+    let mut stream = lex("x = ;");
+    let e = Match(Ident.then(Equals).then(Number)).parse(&mut stream).unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Ident,0..1)));
+    assert_eq!(e.expected,vec![]);
+    // Along with any kinds tested for beforehand
+    assert!(!stream.at(Number));
+    let e = Match(Ident.then(Semi)).parse(&mut stream).unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Ident,0..1)));
+    assert_eq!(e.expected,vec![Number]);
+    assert!(stream.at(Ident));
+}
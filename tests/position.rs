@@ -0,0 +1,66 @@
+use lexington::{Lexer,Matcher,Token,Unit,Within};
+use lexington::util::{Encoding,LineIndex,OffsetKind,Position,Region};
+
+use Encoding::*;
+
+const TEXT : &str = "let x = \"a😀b\";\r\nλ y\rz\n";
+
+#[test]
+fn test_position_01() {
+    let index = LineIndex::new(TEXT,OffsetKind::Chars);
+    assert_eq!(index.line_count(),4);
+    assert_eq!(index.line(0),"let x = \"a😀b\";");
+    assert_eq!(index.line(1),"λ y");
+    assert_eq!(index.line(2),"z");
+    assert_eq!(index.line(3),"");
+}
+
+#[test]
+fn test_position_02() {
+    // Position of 'b' in each encoding
+    let index = LineIndex::new(TEXT,OffsetKind::Chars);
+    assert_eq!(index.position(11,Utf8),Position::new(0,14));
+    assert_eq!(index.position(11,Utf16),Position::new(0,12));
+    assert_eq!(index.position(11,Utf32),Position::new(0,11));
+    assert_eq!(index.offset(Position::new(0,14),Utf8),11);
+    assert_eq!(index.offset(Position::new(0,12),Utf16),11);
+    assert_eq!(index.offset(Position::new(0,11),Utf32),11);
+}
+
+#[test]
+fn test_position_03() {
+    // Same again, but using byte offsets
+    let index = LineIndex::new(TEXT,OffsetKind::Bytes);
+    assert_eq!(index.position(14,Utf8),Position::new(0,14));
+    assert_eq!(index.position(14,Utf16),Position::new(0,12));
+    assert_eq!(index.offset(Position::new(0,12),Utf16),14);
+    // Position of 'y' on second line
+    assert_eq!(index.position(22,Utf16),Position::new(1,2));
+    assert_eq!(index.offset(Position::new(1,2),Utf16),22);
+}
+
+#[test]
+fn test_position_04() {
+    // Clamping of out-of-range positions
+    let index = LineIndex::new(TEXT,OffsetKind::Chars);
+    // Middle of surrogate pair
+    assert_eq!(index.offset(Position::new(0,11),Utf16),10);
+    // Beyond end of line
+    assert_eq!(index.offset(Position::new(1,100),Utf16),19);
+    assert_eq!(index.offset(Position::new(2,100),Utf16),21);
+    // Beyond end of file
+    assert_eq!(index.offset(Position::new(10,0),Utf16),22);
+    assert_eq!(index.position(100,Utf16),Position::new(3,0));
+}
+
+#[test]
+fn test_position_05() {
+    // Tokens converted straight into ranges
+    let scanner = Unit(Within('a'..='z').or('λ').one_or_more(),0);
+    let index = LineIndex::new(TEXT,OffsetKind::Chars);
+    let tokens : Vec<Token<i32>> = Lexer::new("λ y".chars(),scanner).collect();
+    let mut region = tokens[0].region;
+    region.shift(16);
+    assert_eq!(index.range(region,Utf16),(Position::new(1,0),Position::new(1,1)));
+    assert_eq!(index.region(Position::new(1,0),Position::new(1,1),Utf16),Region::new(16,17));
+}
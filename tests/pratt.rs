@@ -0,0 +1,154 @@
+use lexington::{Any,Lexer,Matcher,Scanner,SyntaxError,Token,TokenStream,Unit,Within};
+use lexington::pratt::{Assoc,Pratt};
+use lexington::util::Region;
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    Number,
+    Plus,
+    Minus,
+    Star,
+    Caret,
+    Bang,
+    LeftBrace,
+    RightBrace
+}
+
+use Kind::*;
+
+fn lex(input: &str) -> TokenStream<Kind> {
+    let whitespace = Any([' ','\n']).one_or_more();
+    let number = Within('0'..='9').one_or_more();
+    let scanner = Unit(whitespace,WhiteSpace)
+        .or(Unit(number,Number))
+        .or(Unit('+',Plus))
+        .or(Unit('-',Minus))
+        .or(Unit('*',Star))
+        .or(Unit('^',Caret))
+        .or(Unit('!',Bang))
+        .or(Unit('(',LeftBrace))
+        .or(Unit(')',RightBrace));
+    let tokens : Vec<Token<Kind>> = Lexer::new(input.chars(),scanner).collect();
+    TokenStream::with_trivia(tokens,&[WhiteSpace])
+}
+
+/// Expressions are printed as s-expressions, with the region of each
+/// node attached.
+fn table(input: &str) -> Pratt<'_,Kind,String> {
+    let op = move |o: Token<Kind>| &input[o.range()];
+    Pratt::new()
+        .prefix(Minus,3,move |o,e,r| format!("({} {e})@{}",op(o),r.start))
+        .infix(Plus,1,Assoc::Left,move |l,o,e,r| format!("({} {l} {e})@{}",op(o),r.start))
+        .infix(Minus,1,Assoc::Left,move |l,o,e,r| format!("({} {l} {e})@{}",op(o),r.start))
+        .infix(Star,2,Assoc::Left,move |l,o,e,r| format!("({} {l} {e})@{}",op(o),r.start))
+        .infix(Caret,5,Assoc::Right,move |l,o,e,r| format!("({} {l} {e})@{}",op(o),r.start))
+        .postfix(Bang,4,move |e,o,r| format!("({} {e})@{}",op(o),r.start))
+}
+
+fn expr(pratt: &Pratt<Kind,String>, input: &str, stream: &mut TokenStream<Kind>) -> Result<String,SyntaxError<Kind>> {
+    let atom = |s: &mut TokenStream<Kind>| {
+        if s.eat(LeftBrace).is_some() {
+            let e = expr(pratt,input,s)?;
+            s.expect(RightBrace)?;
+            Ok(e)
+        } else {
+            let t = s.expect(Number)?;
+            Ok(input[t.range()].to_string())
+        }
+    };
+    pratt.parse(stream,&atom)
+}
+
+fn parse(input: &str) -> Result<String,SyntaxError<Kind>> {
+    let mut stream = lex(input);
+    let e = expr(&table(input),input,&mut stream)?;
+    match stream.peek() {
+        Some(_) => Err(stream.error()),
+        None => Ok(e)
+    }
+}
+
+fn spanned(input: &str) -> (String,Region) {
+    let mut stream = lex(input);
+    let pratt = table(input);
+    let atom = |s: &mut TokenStream<Kind>| s.expect(Number).map(|t| input[t.range()].to_string());
+    pratt.parse_spanned(&mut stream,&atom).unwrap()
+}
+
+#[test]
+fn test_pratt_01() {
+    assert_eq!(parse("1").unwrap(),"1");
+    assert_eq!(parse("1 + 2").unwrap(),"(+ 1 2)@0");
+    assert_eq!(parse("1 + 2 * 3").unwrap(),"(+ 1 (* 2 3)@4)@0");
+    assert_eq!(parse("1 * 2 + 3").unwrap(),"(+ (* 1 2)@0 3)@0");
+}
+
+#[test]
+fn test_pratt_02() {
+    // Left associative
+    assert_eq!(parse("1 - 2 - 3").unwrap(),"(- (- 1 2)@0 3)@0");
+    // Right associative
+    assert_eq!(parse("1 ^ 2 ^ 3").unwrap(),"(^ 1 (^ 2 3)@4)@0");
+}
+
+#[test]
+fn test_pratt_03() {
+    assert_eq!(parse("-1").unwrap(),"(- 1)@0");
+    assert_eq!(parse("--1").unwrap(),"(- (- 1)@1)@0");
+    assert_eq!(parse("-1 * 2").unwrap(),"(* (- 1)@0 2)@0");
+    // Exponentiation binds more tightly than negation
+    assert_eq!(parse("-1 ^ 2").unwrap(),"(- (^ 1 2)@1)@0");
+    assert_eq!(parse("1 - -2").unwrap(),"(- 1 (- 2)@4)@0");
+}
+
+#[test]
+fn test_pratt_04() {
+    assert_eq!(parse("1!").unwrap(),"(! 1)@0");
+    assert_eq!(parse("1!!").unwrap(),"(! (! 1)@0)@0");
+    // Factorial binds more tightly than negation
+    assert_eq!(parse("-1!").unwrap(),"(- (! 1)@1)@0");
+    // But less tightly than exponentiation
+    assert_eq!(parse("1^2!").unwrap(),"(! (^ 1 2)@0)@0");
+    assert_eq!(parse("1 + 2! * 3").unwrap(),"(+ 1 (* (! 2)@4 3)@4)@0");
+}
+
+#[test]
+fn test_pratt_05() {
+    assert_eq!(parse("(1 + 2) * 3").unwrap(),"(* (+ 1 2)@1 3)@0");
+    assert_eq!(parse("-(1)").unwrap(),"(- 1)@0");
+    assert_eq!(parse("((1))").unwrap(),"1");
+}
+
+#[test]
+fn test_pratt_06() {
+    assert_eq!(spanned("1"),("1".to_string(),Region::new(0,1)));
+    assert_eq!(spanned(" 1 + 23 ").1,Region::new(1,7));
+    assert_eq!(spanned("-1 * 2!").1,Region::new(0,7));
+    assert_eq!(spanned("12 ^ -3 ").1,Region::new(0,7));
+}
+
+#[test]
+fn test_pratt_07() {
+    // Missing operand
+    let e = parse("1 +").unwrap_err();
+    assert_eq!(e.found,None);
+    assert_eq!(e.expected,[Minus,LeftBrace,Number]);
+    assert_eq!(e.region,Region::new(3,3));
+    // Unexpected token after expression
+    let e = parse("1 2").unwrap_err();
+    assert_eq!(e.found.map(|t| t.kind),Some(Number));
+    assert_eq!(e.expected,[Bang,Plus,Minus,Star,Caret]);
+    assert_eq!(e.region,Region::new(2,3));
+}
+
+#[test]
+fn test_pratt_08() {
+    // Missing closing brace
+    let e = parse("(1 + 2").unwrap_err();
+    assert_eq!(e.found,None);
+    assert_eq!(e.expected,[Bang,Plus,Minus,Star,Caret,RightBrace]);
+    let e = parse("*").unwrap_err();
+    assert_eq!(e.found.map(|t| t.kind),Some(Star));
+    assert_eq!(e.region,Region::new(0,1));
+}
@@ -0,0 +1,76 @@
+use std::io::{BufReader,Read};
+use lexington::{Any,Matcher,ReadLexer,Scanner,Token,Unit,Within};
+use lexington::util::{ReadError,Utf8Reader};
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    WhiteSpace,
+    Word
+}
+
+use Kind::*;
+
+fn scanner() -> impl Scanner<Item=char,Token=Kind> {
+    // [ \n\t]+
+    let whitespace = Any([' ','\n','\t']).one_or_more();
+    // [a..zé]+
+    let word = Within('a'..='z').or('é').one_or_more();
+    // Construct scanner
+    Unit(whitespace,WhiteSpace).or(Unit(word,Word))
+}
+
+fn lex(bytes: &[u8]) -> Vec<Result<(Token<Kind>,String),ReadError>> {
+    ReadLexer::new(bytes,scanner()).collect()
+}
+
+#[test]
+fn test_reader_01() {
+    assert!(lex(b"").is_empty());
+}
+
+#[test]
+fn test_reader_02() {
+    let tokens : Vec<_> = lex("hello café".as_bytes()).into_iter().map(|r| r.unwrap()).collect();
+    assert_eq!(tokens,vec![
+        (Token::new(Word,0..5),"hello".to_string()),
+        (Token::new(WhiteSpace,5..6)," ".to_string()),
+        (Token::new(Word,6..10),"café".to_string())
+    ]);
+}
+
+#[test]
+fn test_reader_03() {
+    let tokens = lex(b"ab \xFFcd");
+    assert_eq!(tokens.len(),3);
+    assert!(tokens[1].is_ok());
+    match &tokens[2] {
+        Err(ReadError::InvalidUtf8(3)) => {}
+        r => panic!("unexpected result {r:?}")
+    }
+}
+
+#[test]
+fn test_reader_04() {
+    // Truncated multibyte sequence at end of stream
+    let mut reader = Utf8Reader::new(&b"a\xC3"[..]);
+    assert_eq!(reader.next(),Some('a'));
+    assert_eq!(reader.next(),None);
+    assert!(matches!(reader.take_error(),Some(ReadError::InvalidUtf8(1))));
+}
+
+#[test]
+fn test_reader_05() {
+    // Overlong encoding of '/'
+    let mut reader = Utf8Reader::new(&b"\xC0\xAF"[..]);
+    assert_eq!(reader.next(),None);
+    assert!(matches!(reader.take_error(),Some(ReadError::InvalidUtf8(0))));
+}
+
+#[test]
+fn test_reader_06() {
+    // Lex a large stream without ever holding it in memory
+    let words = std::io::repeat(b'x').take(1_000_000);
+    let input = BufReader::new(words.chain(&b" y"[..]));
+    let tokens : Vec<_> = ReadLexer::from_buf_read(input,scanner()).map(|r| r.unwrap().0).collect();
+    assert_eq!(tokens,&[(Word,0..1_000_000),(WhiteSpace,1_000_000..1_000_001),(Word,1_000_001..1_000_002)]);
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::Path;
+use lexington::lang::rust::{lex,Kind};
+use lexington::tree::Lexemes;
+
+use Kind::*;
+
+/// Lex some input, ignoring whitespace.
+fn tokens(input: &str) -> Vec<(Kind,&str)> {
+    Lexemes::new(input,lex(input)).map(|(t,s)| (t.kind,s)).filter(|(k,_)| *k != WhiteSpace).collect()
+}
+
+fn check(input: &str, expecting: &[(Kind,&str)]) {
+    assert_eq!(tokens(input),expecting);
+}
+
+/// Check a single literal lexes as a given kind.
+fn check_one(input: &str, kind: Kind) {
+    check(input,&[(kind,input)]);
+}
+
+#[test]
+fn rust_01() {
+    check("fn main() { let _x = r#match; }",&[
+        (Keyword,"fn"),(Identifier,"main"),(LeftParen,"("),(RightParen,")"),(LeftBrace,"{"),
+        (Keyword,"let"),(Identifier,"_x"),(Equal,"="),(RawIdentifier,"r#match"),(Semicolon,";"),(RightBrace,"}")]);
+    check("let _ = union;",&[(Keyword,"let"),(Underscore,"_"),(Equal,"="),(Identifier,"union"),(Semicolon,";")]);
+    check("Self::λ",&[(Keyword,"Self"),(ColonColon,"::"),(Identifier,"λ")]);
+}
+
+#[test]
+fn rust_02() {
+    // Lifetimes versus characters
+    check("fn f<'a>(x: &'a str) -> char { 'a' }",&[
+        (Keyword,"fn"),(Identifier,"f"),(Less,"<"),(Lifetime,"'a"),(Greater,">"),(LeftParen,"("),
+        (Identifier,"x"),(Colon,":"),(Ampersand,"&"),(Lifetime,"'a"),(Identifier,"str"),(RightParen,")"),
+        (Arrow,"->"),(Identifier,"char"),(LeftBrace,"{"),(Char,"'a'"),(RightBrace,"}")]);
+    check("'outer: loop { break 'outer; }",&[(Lifetime,"'outer"),(Colon,":"),(Keyword,"loop"),(LeftBrace,"{"),
+                                          (Keyword,"break"),(Lifetime,"'outer"),(Semicolon,";"),(RightBrace,"}")]);
+    check_one("'static",Lifetime);
+    check_one("'r#fn",Lifetime);
+    for c in ["'x'","'\\n'","'\\''","'\\\\'","'\\x7F'","'\\u{1F600}'","'λ'","'\"'"] {
+        check_one(c,Char);
+    }
+    for b in ["b'x'","b'\\n'","b'\\x00'","b'\\''"] {
+        check_one(b,Byte);
+    }
+    check("'\nx",&[(UnterminatedChar,"'"),(Identifier,"x")]);
+}
+
+#[test]
+fn rust_03() {
+    // Strings
+    for s in ["\"\"","\"hello\"","\"a\\\"b\"","\"multi\nline\"","\"\\u{48}\\x41\\\\\""] {
+        check_one(s,String);
+    }
+    check_one("b\"bytes\\n\"",ByteString);
+    check_one("c\"hello\"",CString);
+    check("\"abc",&[(UnterminatedString,"\"abc")]);
+    check("b\"abc\\\"",&[(UnterminatedString,"b\"abc\\\"")]);
+}
+
+#[test]
+fn rust_04() {
+    // Raw strings
+    check_one("r\"C:\\dir\"",RawString);
+    check_one("r#\"say \"hi\"\"#",RawString);
+    check_one("r##\"a \"# b\"##",RawString);
+    check_one("r###\"\"##\"###",RawString);
+    check_one("br\"\\d+\"",RawByteString);
+    check_one("br#\"\"\"#",RawByteString);
+    check_one("cr\"x\"",RawCString);
+    check("r#\"x\" y",&[(UnterminatedString,"r#\"x\" y")]);
+    check("r#\"a\"##",&[(RawString,"r#\"a\"#"),(Hash,"#")]);
+    check("r #x",&[(Identifier,"r"),(Hash,"#"),(Identifier,"x")]);
+}
+
+#[test]
+fn rust_05() {
+    // Comments
+    check("a // line\nb",&[(Identifier,"a"),(LineComment,"// line"),(Identifier,"b")]);
+    check("/* a /* b */ c */ d",&[(BlockComment,"/* a /* b */ c */"),(Identifier,"d")]);
+    check("/* /* */",&[(UnterminatedComment,"/* /* */")]);
+    check("/*/ x */",&[(BlockComment,"/*/ x */")]);
+    // Doc comments
+    check_one("/// outer",DocComment);
+    check_one("//! inner",InnerDocComment);
+    check_one("/** outer */",DocComment);
+    check_one("/*! inner */",InnerDocComment);
+    check_one("//// not doc",LineComment);
+    check_one("/*** not doc */",BlockComment);
+    check_one("/**/",BlockComment);
+    check_one("/***/",BlockComment);
+}
+
+#[test]
+fn rust_06() {
+    // Integers
+    for i in ["0","42","1_000","0xFF","0x_ff_u8","0o755","0b1010_1010","7u8","1i128","0xffusize","1_","1f32"] {
+        check_one(i,Integer);
+    }
+    // Floats
+    for f in ["1.5","0.1","1e10","1E-3","2.5e+3_f64","1.0f32","2.","1_000.000_1","1e_3"] {
+        check_one(f,Float);
+    }
+    // Ranges, fields and methods
+    check("1..2",&[(Integer,"1"),(DotDot,".."),(Integer,"2")]);
+    check("0..=9",&[(Integer,"0"),(DotDotEqual,"..="),(Integer,"9")]);
+    check("1.max(2)",&[(Integer,"1"),(Dot,"."),(Identifier,"max"),(LeftParen,"("),(Integer,"2"),(RightParen,")")]);
+    check("x.0",&[(Identifier,"x"),(Dot,"."),(Integer,"0")]);
+    check("1._x",&[(Integer,"1"),(Dot,"."),(Identifier,"_x")]);
+}
+
+#[test]
+fn rust_07() {
+    // Shebang lines
+    check("#!/usr/bin/env run-cargo-script\nfn",&[(Shebang,"#!/usr/bin/env run-cargo-script"),(Keyword,"fn")]);
+    check("#![allow(unused)]",&[(Hash,"#"),(Bang,"!"),(LeftSquare,"["),(Identifier,"allow"),(LeftParen,"("),
+                               (Identifier,"unused"),(RightParen,")"),(RightSquare,"]")]);
+    check("#! \n [x]",&[(Hash,"#"),(Bang,"!"),(LeftSquare,"["),(Identifier,"x"),(RightSquare,"]")]);
+    check("x\n#!/bin",&[(Identifier,"x"),(Hash,"#"),(Bang,"!"),(Slash,"/"),(Identifier,"bin")]);
+}
+
+#[test]
+fn rust_08() {
+    // Punctuators by longest match
+    check("a<<=b>>=c",&[(Identifier,"a"),(ShiftLeftEqual,"<<="),(Identifier,"b"),(ShiftRightEqual,">>="),(Identifier,"c")]);
+    check("x => y -> z <- w",&[(Identifier,"x"),(FatArrow,"=>"),(Identifier,"y"),(Arrow,"->"),(Identifier,"z"),
+                              (LeftArrow,"<-"),(Identifier,"w")]);
+    check("$x:expr @ ...",&[(Dollar,"$"),(Identifier,"x"),(Colon,":"),(Identifier,"expr"),(At,"@"),(DotDotDot,"...")]);
+    check("a?.b",&[(Identifier,"a"),(Question,"?"),(Dot,"."),(Identifier,"b")]);
+    check("a ` b",&[(Identifier,"a"),(Unknown,"`"),(Identifier,"b")]);
+}
+
+/// Lex every Rust file within a given directory (recursively),
+/// checking the lexemes cover the file exactly and that no errors are
+/// reported.
+fn check_corpus(dir: &Path, count: &mut usize) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            check_corpus(&path,count);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            let input = fs::read_to_string(&path).unwrap();
+            let mut text = std::string::String::new();
+            for (t,s) in Lexemes::new(&input,lex(&input)) {
+                assert!(!matches!(t.kind,Unknown|UnterminatedChar|UnterminatedString|UnterminatedComment),
+                        "{} at {:?} in {}",s,t.region,path.display());
+                text.push_str(s);
+            }
+            assert_eq!(text,input);
+            *count += 1;
+        }
+    }
+}
+
+#[test]
+fn rust_09() {
+    // Validate against this crate's own sources
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut count = 0;
+    check_corpus(&root.join("src"),&mut count);
+    check_corpus(&root.join("tests"),&mut count);
+    assert!(count > 20);
+}
@@ -0,0 +1,216 @@
+use lexington::sexp::{parse,parse_all,Error,Kind,SExp,Value};
+use lexington::util::Region;
+
+/// Check an S-expression parses, and prints as expected.
+fn check_ok(input: &str, expecting: &str) {
+    let actual = parse(input).unwrap();
+    assert_eq!(actual.to_string(),expecting);
+    // Check printing round trips
+    let reparsed = parse(&actual.to_string()).unwrap();
+    assert_eq!(reparsed.to_string(),expecting);
+}
+
+fn check_err(input: &str, expecting: Error) {
+    assert_eq!(parse(input).unwrap_err(),expecting);
+}
+
+/// Check a syntax error arises at a given region.
+fn check_syntax_err(input: &str, found: Option<Kind>, region: Region) {
+    match parse(input).unwrap_err() {
+        Error::Syntax(e) => {
+            assert_eq!(e.found.map(|t| t.kind),found);
+            assert_eq!(e.region,region);
+        }
+        e => panic!("unexpected error {e:?}")
+    }
+}
+
+fn value(input: &str) -> Value {
+    parse(input).unwrap().value
+}
+
+#[test]
+fn lisp_01() {
+    check_ok("x","x");
+}
+
+#[test]
+fn lisp_02() {
+    check_ok("901","901");
+    assert_eq!(value("901"),Value::Integer(901));
+}
+
+#[test]
+fn lisp_03() {
+    check_ok("0x01","0x01");
+    assert_eq!(value("0x01"),Value::Symbol("0x01".to_string()));
+}
+
+#[test]
+fn lisp_04() {
+    check_ok("_hello","_hello");
+}
+
+#[test]
+fn lisp_05() {
+    check_ok(" xyz","xyz");
+}
+
+#[test]
+fn lisp_06() {
+    check_ok("()","()");
+}
+
+#[test]
+fn lisp_07() {
+    check_ok("(x)","(x)");
+}
+
+#[test]
+fn lisp_08() {
+    check_ok("( x)","(x)");
+}
+
+#[test]
+fn lisp_09() {
+    check_ok("(x )","(x)");
+}
+
+#[test]
+fn lisp_10() {
+    check_ok("(x y)","(x y)");
+}
+
+#[test]
+fn lisp_11() {
+    check_ok("(())","(())");
+}
+
+#[test]
+fn lisp_12() {
+    check_ok("((x))","((x))");
+}
+
+#[test]
+fn lisp_13() {
+    check_ok("(x (y))","(x (y))");
+}
+
+#[test]
+fn lisp_14() {
+    check_ok("((x) y)","((x) y)");
+}
+
+#[test]
+fn lisp_15() {
+    check_syntax_err("",None,Region::new(0,0));
+}
+
+#[test]
+fn lisp_16() {
+    check_syntax_err(" ",None,Region::new(1,1));
+}
+
+#[test]
+fn lisp_17() {
+    check_syntax_err("(",None,Region::new(1,1));
+}
+
+#[test]
+fn lisp_18() {
+    check_syntax_err("(x ; comment",None,Region::new(12,12));
+}
+
+#[test]
+fn lisp_19() {
+    check_syntax_err("(()",None,Region::new(3,3));
+}
+
+#[test]
+fn lisp_20() {
+    check_syntax_err(")",Some(Kind::RightBrace),Region::new(0,1));
+}
+
+#[test]
+fn lisp_21() {
+    // Numbers
+    assert_eq!(value("-12"),Value::Integer(-12));
+    assert_eq!(value("+7"),Value::Integer(7));
+    assert_eq!(value("1.5"),Value::Float(1.5));
+    assert_eq!(value("-.5"),Value::Float(-0.5));
+    assert_eq!(value("2e3"),Value::Float(2000.0));
+    assert_eq!(value("1e-2"),Value::Float(0.01));
+    // Things which are not numbers
+    assert_eq!(value("-"),Value::Symbol("-".to_string()));
+    assert_eq!(value("1+"),Value::Symbol("1+".to_string()));
+    assert_eq!(value("inf"),Value::Symbol("inf".to_string()));
+    assert_eq!(value("a.b"),Value::Symbol("a.b".to_string()));
+    check_ok("(+ 1 2.0 -3e10)","(+ 1 2.0 -30000000000.0)");
+}
+
+#[test]
+fn lisp_22() {
+    // Strings
+    assert_eq!(value("\"hello world\""),Value::String("hello world".to_string()));
+    assert_eq!(value(r#""a\n\t\"b\\""#),Value::String("a\n\t\"b\\".to_string()));
+    assert_eq!(value("\"(not a list) ; or comment\""),Value::String("(not a list) ; or comment".to_string()));
+    assert_eq!(value("\"λ\nx\""),Value::String("λ\nx".to_string()));
+    check_ok(r#"("x y" "\"" "\r\0")"#,r#"("x y" "\"" "\r\0")"#);
+    check_ok("\"line\nbreak\"","\"line\\nbreak\"");
+}
+
+#[test]
+fn lisp_23() {
+    // Quotes
+    check_ok("'x","'x");
+    check_ok("' (a 'b)","'(a 'b)");
+    check_ok("''()","''()");
+    let e = parse("'(a)").unwrap();
+    match &e.value {
+        Value::Quote(q) => assert_eq!(q.region,Region::new(1,4)),
+        _ => panic!("expected quote")
+    }
+    assert_eq!(e.region,Region::new(0,4));
+}
+
+#[test]
+fn lisp_24() {
+    // Comments
+    check_ok("; leading\n(a ; inner\n b) ; trailing","(a b)");
+    let es = parse_all("; nothing here\n").unwrap();
+    assert!(es.is_empty());
+}
+
+#[test]
+fn lisp_25() {
+    // Regions
+    let e = parse(" (define (f x) \"λ\" 12)").unwrap();
+    assert_eq!(e.region,Region::new(1,22));
+    let es = e.as_list().unwrap();
+    assert_eq!(es[0].as_symbol(),Some("define"));
+    assert_eq!(es[0].region,Region::new(2,8));
+    assert_eq!(es[1].region,Region::new(9,14));
+    assert_eq!(es[1].as_list().unwrap()[1].region,Region::new(12,13));
+    assert_eq!(es[2].region,Region::new(15,18));
+    assert_eq!(es[3].region,Region::new(19,21));
+}
+
+#[test]
+fn lisp_26() {
+    let es : Vec<SExp> = parse_all("a (b) 'c").unwrap();
+    let regions : Vec<_> = es.iter().map(|e| e.region).collect();
+    assert_eq!(regions,[Region::new(0,1),Region::new(2,5),Region::new(6,8)]);
+    check_syntax_err("a b",Some(Kind::Atom),Region::new(2,3));
+}
+
+#[test]
+fn lisp_27() {
+    // Errors
+    check_err("(\"abc",Error::UnterminatedString(Region::new(1,5)));
+    check_err("\"ab\\",Error::UnterminatedString(Region::new(0,4)));
+    check_err("(\"a\\qb\")",Error::InvalidEscape(Region::new(3,5)));
+    check_err("(1 99999999999999999999)",Error::IntegerOutOfRange(Region::new(3,23)));
+    assert_eq!(parse("'").unwrap_err().region(),Region::new(1,1));
+    let e = parse("(a").unwrap_err();
+    assert_eq!(e.to_string(),"expected one of RightBrace, Atom, String, Quote, LeftBrace, found end of file");
+}
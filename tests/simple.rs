@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrow)]
+
 use lexington::{Any,Lexer,Matcher,Scanner,Token,Unit,Within};
 
 #[derive(Copy,Clone,Debug,PartialEq)]    
@@ -0,0 +1,67 @@
+use lexington::{Any,Lexer,Matcher,Scanner,Token,Unit,Within};
+use lexington::util::{Region,SourceMap,Span};
+
+fn lex(input: &str) -> Vec<Token<u8>> {
+    let whitespace = Any([' ','\n']).one_or_more();
+    let word = Within('a'..='z').or('é').one_or_more();
+    Lexer::new(input.chars(),Unit(whitespace,0).or(Unit(word,1))).collect()
+}
+
+fn map() -> SourceMap {
+    let mut map = SourceMap::new();
+    map.add("a.txt","hello world\nbye");
+    map.add("b.txt","café\nau lait\n");
+    map
+}
+
+#[test]
+fn test_source_01() {
+    let map = map();
+    let ids : Vec<_> = map.ids().collect();
+    assert_eq!(ids.len(),2);
+    assert_eq!(map.name(ids[0]),"a.txt");
+    assert_eq!(map.text(ids[1]),"café\nau lait\n");
+    assert_eq!(map.get(ids[1]).line_count(),3);
+}
+
+#[test]
+fn test_source_02() {
+    // Token text and locations
+    let map = map();
+    let b = map.ids().nth(1).unwrap();
+    let spans : Vec<Span> = lex(map.text(b)).iter().map(|t| Span::new(b,t.region)).collect();
+    let words : Vec<&str> = spans.iter().map(|s| map.slice(*s)).collect();
+    assert_eq!(words,["café","\n","au"," ","lait","\n"]);
+    assert_eq!(map.location(spans[4]).to_string(),"b.txt:2:4");
+}
+
+#[test]
+fn test_source_03() {
+    // Global offsets
+    let map = map();
+    let (a,b) = (map.ids().next().unwrap(),map.ids().nth(1).unwrap());
+    let span = Span::new(b,Region::new(5,7));
+    let global = map.global(span);
+    assert_eq!(global,Region::new(21,23));
+    assert_eq!(map.span(global),Some(span));
+    assert_eq!(map.lookup(0),Some((a,0)));
+    assert_eq!(map.lookup(15),Some((a,15)));
+    assert_eq!(map.lookup(16),Some((b,0)));
+    assert_eq!(map.lookup(100),None);
+    assert_eq!(map.span(Region::new(10,20)),None);
+    // Reversed regions
+    assert_eq!(map.span(Region::new(21,3)),None);
+    assert_eq!(map.span(Region::new(23,21)),None);
+    assert_eq!(map.global_location(13).unwrap().to_string(),"a.txt:2:2");
+}
+
+#[test]
+fn test_source_04() {
+    let map = map();
+    let a = map.ids().next().unwrap();
+    let s1 = Span::new(a,Region::new(0,5));
+    let s2 = Span::new(a,Region::new(6,11));
+    assert_eq!(map.slice(s1.cover(s2)),"hello world");
+    // Reversed regions are normalised
+    assert_eq!(map.slice(Span::new(a,Region::new(11,6))),"world");
+}
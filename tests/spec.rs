@@ -0,0 +1,88 @@
+use lexington::Token;
+use lexington::spec::{Error,Spec};
+
+mod calc {
+    include!("specs/calc.rs");
+}
+
+use calc::Kind::*;
+
+fn error(spec: &str) -> (usize,usize,String) {
+    let Error{line,column,message,..} = Spec::parse(spec).unwrap_err();
+    (line,column,message)
+}
+
+#[test]
+fn spec_01() {
+    // Generated code must be kept up to date
+    let spec = Spec::parse(include_str!("specs/calc.lex")).unwrap();
+    assert_eq!(spec.to_rust(),include_str!("specs/calc.rs"));
+    assert_eq!(spec.modes.len(),2);
+    assert_eq!(spec.kinds().len(),12);
+    assert!(spec.is_skipped("Comment") && !spec.is_skipped("Quote"));
+}
+
+#[test]
+fn spec_02() {
+    assert_eq!(calc::lex(""),Vec::<Token<calc::Kind>>::new());
+    assert_eq!(calc::lex("let x = 1.5 // one\n+ y2"),&[(Let,0..3),(Identifier,4..5),(Equals,6..7),(Number,8..11),(Plus,19..20),(Identifier,21..23)]);
+    // Longest match, with ties going to the earlier rule
+    assert_eq!(calc::lex("letter - ->"),&[(Identifier,0..6),(Minus,7..8),(Arrow,9..11)]);
+}
+
+#[test]
+fn spec_03() {
+    // Modes
+    assert_eq!(calc::lex(r#"x "a\"b " y"#),&[(Identifier,0..1),(Quote,2..3),(Text,3..4),(Escape,4..6),(Text,6..8),(Quote,8..9),(Identifier,10..11)]);
+    let mut lexer = calc::Lexer::new("\"abc");
+    assert_eq!(lexer.mode(),calc::Mode::Main);
+    lexer.next();
+    assert_eq!(lexer.mode(),calc::Mode::String);
+    // Lexing stops at the first unmatched character
+    assert_eq!(calc::lex("x ; y"),&[(Identifier,0..1)]);
+}
+
+#[test]
+fn spec_04() {
+    assert_eq!(error("A : \"a\"\nB = [a-z\n"),(2,5,"unclosed `[`".to_string()));
+    assert_eq!(error("A : \"a\"\n  B : C\n"),(2,7,"unknown definition `C`".to_string()));
+    assert_eq!(error("A : \"a\" -> other"),(1,12,"unknown mode `other`".to_string()));
+    assert_eq!(error("A : \"a\"?"),(1,5,"pattern can match the empty string".to_string()));
+    assert_eq!(error("A : (\"a\"?)+"),(1,5,"repetition of a pattern which can match the empty string".to_string()));
+    assert_eq!(error("A \"a\""),(1,3,"expected `:` or `=`".to_string()));
+    assert_eq!(error("A : [z-a]"),(1,6,"invalid range (start exceeds end)".to_string()));
+}
+
+#[test]
+fn spec_05() {
+    assert_eq!(error("# nothing\n"),(2,1,"specification has no rules".to_string()));
+    assert_eq!(error("A : \"a\"\nmode x\n"),(2,6,"mode `x` has no rules".to_string()));
+    assert_eq!(error("mode x\nmode y\nA : \"a\"\n"),(1,6,"mode `x` has no rules".to_string()));
+    assert_eq!(error("mode m\nmode m\n"),(2,6,"duplicate mode `m`".to_string()));
+    // Names which generate the same variant
+    assert_eq!(error("mode foo_bar\nA : \"a\"\nmode fooBar\n"),(3,6,"`fooBar` and `foo_bar` both generate `Mode::FooBar`".to_string()));
+    assert_eq!(error("A : \"a\"\nmode Main\n"),(2,6,"`Main` and `main` both generate `Mode::Main`".to_string()));
+    assert_eq!(error("fn : \"fn\""),(1,1,"`fn` is a reserved word".to_string()));
+    assert_eq!(error("skip A : \"a\"\nA : \"b\""),(2,1,"`A` is skipped by some rules but not others".to_string()));
+    assert_eq!(error("A : \"a\" \"b"),(1,9,"unterminated string".to_string()));
+    // Errors can be rendered as diagnostics
+    let spec = "A : \"a\"\n  B : C\n";
+    let e = Spec::parse(spec).unwrap_err();
+    assert!(e.to_diagnostic().render("calc.lex",spec).contains("unknown definition `C`"));
+    assert_eq!(e.to_string(),"2:7: unknown definition `C`");
+}
+
+#[test]
+fn spec_06() {
+    // Interpreting a specification matches the generated lexer
+    let spec = Spec::parse(include_str!("specs/calc.lex")).unwrap();
+    let input = "let s = \"a\\\"b\" + x2 // done\n";
+    let expected : Vec<(Option<String>,std::ops::Range<usize>)> = calc::Lexer::new(input).map(|t| (Some(format!("{:?}",t.kind)),t.range())).collect();
+    let actual : Vec<(Option<String>,std::ops::Range<usize>)> = spec.lexer(input).map(|t| (t.kind.map(str::to_string),t.range())).collect();
+    assert_eq!(actual,expected);
+    // Unmatched characters produce tokens without a kind
+    let mut lexer = spec.lexer("\"a;b\n;");
+    let tokens : Vec<Token<Option<&str>>> = lexer.by_ref().collect();
+    assert_eq!(tokens,&[(Some("Quote"),0..1),(Some("Text"),1..4),(None,4..5),(Some("Text"),5..6)]);
+    assert_eq!(lexer.mode(),"string");
+}
@@ -0,0 +1,97 @@
+// Generated by lexington from a lexer specification.  Do not edit.
+
+/// Identifies the kinds of token.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Kind {
+    WhiteSpace,
+    Comment,
+    Number,
+    Let,
+    Identifier,
+    Plus,
+    Minus,
+    Arrow,
+    Equals,
+    Quote,
+    Escape,
+    Text
+}
+
+impl Kind {
+    /// Determine whether tokens of this kind are skipped.
+    pub fn is_skipped(self) -> bool {
+        matches!(self,Kind::WhiteSpace|Kind::Comment)
+    }
+}
+
+/// Identifies the modes of the lexer.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Mode {
+    Main,
+    String
+}
+
+impl Mode {
+    /// Scan the next token in this mode, along with the mode to
+    /// continue in.
+    pub fn scan<C: ::lexington::util::Cursor<Item=char>>(self, input: &mut C) -> Option<(Kind,Mode)> {
+        let mut longest = ::lexington::Longest::new();
+        match self {
+            Mode::Main => {
+                longest.rule(input,::lexington::Matcher::one_or_more(::lexington::Class([(' ',' '),('\t','\t'),('\r','\r'),('\n','\n')])),0,(Kind::WhiteSpace,Mode::Main));
+                longest.rule(input,::lexington::Matcher::then(::lexington::Text("//"),::lexington::Matcher::zero_or_more(::lexington::NotClass([('\n','\n')]))),0,(Kind::Comment,Mode::Main));
+                longest.rule(input,::lexington::Matcher::then(::lexington::Matcher::one_or_more(::lexington::Class([('0','9')])),::lexington::Matcher::optional(::lexington::Matcher::then('.',::lexington::Matcher::one_or_more(::lexington::Class([('0','9')]))))),0,(Kind::Number,Mode::Main));
+                longest.rule(input,::lexington::Text("let"),0,(Kind::Let,Mode::Main));
+                longest.rule(input,::lexington::Matcher::then(::lexington::Class([('a','z'),('A','Z'),('_','_')]),::lexington::Matcher::zero_or_more(::lexington::Matcher::or(::lexington::Class([('a','z'),('A','Z'),('_','_')]),::lexington::Class([('0','9')])))),0,(Kind::Identifier,Mode::Main));
+                longest.rule(input,'+',0,(Kind::Plus,Mode::Main));
+                longest.rule(input,'-',0,(Kind::Minus,Mode::Main));
+                longest.rule(input,::lexington::Text("->"),0,(Kind::Arrow,Mode::Main));
+                longest.rule(input,'=',0,(Kind::Equals,Mode::Main));
+                longest.rule(input,'"',0,(Kind::Quote,Mode::String));
+            }
+            Mode::String => {
+                longest.rule(input,'"',0,(Kind::Quote,Mode::Main));
+                longest.rule(input,::lexington::Matcher::then('\\',::lexington::NotClass([('\n','\n')])),0,(Kind::Escape,Mode::String));
+                longest.rule(input,::lexington::Matcher::one_or_more(::lexington::NotClass([('"','"'),('\\','\\'),('\n','\n')])),0,(Kind::Text,Mode::String));
+            }
+        }
+        longest.finish(input)
+    }
+}
+
+/// Splits text into tokens, switching modes as directed.  Lexing
+/// stops at the first character not matched by any rule.
+pub struct Lexer<'a> {
+    input: ::lexington::util::StrCursor<'a>,
+    mode: Mode
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self{input: ::lexington::util::StrCursor::new(input), mode: Mode::Main}
+    }
+
+    /// Get the current mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = ::lexington::Token<Kind>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use ::lexington::util::Cursor;
+        let start = self.input.offset();
+        let (kind,mode) = self.mode.scan(&mut self.input)?;
+        let end = self.input.offset();
+        self.input.reset();
+        self.mode = mode;
+        Some(::lexington::Token::new(kind,start..end))
+    }
+}
+
+/// Split text into tokens, dropping those which are skipped.
+pub fn lex(input: &str) -> Vec<::lexington::Token<Kind>> {
+    Lexer::new(input).filter(|t| !t.kind.is_skipped()).collect()
+}
@@ -0,0 +1,88 @@
+use lexington::{Token,TokenStream};
+use lexington::util::Region;
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Kind {
+    Space,
+    Comment,
+    Ident,
+    Equals,
+    Number,
+    Semi
+}
+
+use Kind::*;
+
+/// Tokens for `x = /* c */ 1;`
+fn tokens() -> Vec<Token<Kind>> {
+    vec![Token::new(Ident,0..1),Token::new(Space,1..2),Token::new(Equals,2..3),
+         Token::new(Space,3..4),Token::new(Comment,4..11),Token::new(Space,11..12),
+         Token::new(Number,12..13),Token::new(Semi,13..14)]
+}
+
+fn stream() -> TokenStream<Kind> {
+    TokenStream::with_trivia(tokens(),&[Space,Comment])
+}
+
+#[test]
+fn test_stream_01() {
+    let mut s = stream();
+    assert_eq!(s.peek(),Some(Token::new(Ident,0..1)));
+    assert_eq!(s.peek_nth(1),Some(Token::new(Equals,2..3)));
+    assert_eq!(s.peek_nth(2),Some(Token::new(Number,12..13)));
+    assert_eq!(s.peek_nth(4),None);
+    assert_eq!(s.next(),Some(Token::new(Ident,0..1)));
+    assert_eq!(s.next(),Some(Token::new(Equals,2..3)));
+    assert_eq!(s.next(),Some(Token::new(Number,12..13)));
+    assert_eq!(s.next(),Some(Token::new(Semi,13..14)));
+    assert!(s.is_eof());
+}
+
+#[test]
+fn test_stream_02() {
+    let mut s = stream();
+    assert!(s.eat(Number).is_none());
+    assert_eq!(s.expect(Ident),Ok(Token::new(Ident,0..1)));
+    assert_eq!(s.expect_one_of(&[Semi,Equals]),Ok(Token::new(Equals,2..3)));
+    assert!(s.expect(Number).is_ok());
+    assert!(s.eat(Semi).is_some());
+    assert!(s.eat(Semi).is_none());
+}
+
+#[test]
+fn test_stream_03() {
+    // Errors report everything tried at the current position
+    let mut s = stream();
+    s.expect(Ident).unwrap();
+    assert!(s.eat(Semi).is_none());
+    let e = s.expect_one_of(&[Number,Ident]).unwrap_err();
+    assert_eq!(e.found,Some(Token::new(Equals,2..3)));
+    assert_eq!(e.expected,vec![Semi,Number,Ident]);
+    assert_eq!(e.region,Region::new(2,3));
+    assert_eq!(e.to_string(),"expected one of Semi, Number, Ident, found Equals");
+}
+
+#[test]
+fn test_stream_04() {
+    // Errors at end of file
+    let mut s = stream();
+    while s.next().is_some() {}
+    let e = s.expect(Semi).unwrap_err();
+    assert_eq!(e.found,None);
+    assert_eq!(e.region,Region::new(14,14));
+    assert_eq!(e.to_string(),"expected Semi, found end of file");
+}
+
+#[test]
+fn test_stream_05() {
+    // Checkpoints
+    let mut s = stream();
+    let cp = s.checkpoint();
+    s.expect(Ident).unwrap();
+    s.expect(Equals).unwrap();
+    assert_eq!(s.since(cp).len(),3);
+    assert!(s.expect(Ident).is_err());
+    s.rewind(cp);
+    assert_eq!(s.expect(Ident),Ok(Token::new(Ident,0..1)));
+    assert_eq!(s.expect(Number).unwrap_err().expected,vec![Number]);
+}
@@ -0,0 +1,146 @@
+use lexington::lang::toml::{lex,Kind};
+use lexington::tree::Lexemes;
+
+use Kind::*;
+
+/// Lex some input, ignoring whitespace.
+fn tokens(input: &str) -> Vec<(Kind,&str)> {
+    Lexemes::new(input,lex(input)).map(|(t,s)| (t.kind,s)).filter(|(k,_)| *k != WhiteSpace).collect()
+}
+
+fn check(input: &str, expecting: &[(Kind,&str)]) {
+    assert_eq!(tokens(input),expecting);
+}
+
+/// Check a single value lexes as a given kind.
+fn check_value(value: &str, kind: Kind) {
+    let input = format!("x = {value}");
+    check(&input,&[(BareKey,"x"),(Equals,"="),(kind,value)]);
+}
+
+#[test]
+fn toml_01() {
+    check("# This is a TOML document\n\ntitle = \"TOML Example\"\n",&[
+        (Comment,"# This is a TOML document"),(Newline,"\n"),(Newline,"\n"),
+        (BareKey,"title"),(Equals,"="),(BasicString,"\"TOML Example\""),(Newline,"\n")]);
+    check("key = \"value\" # comment\r\n",&[(BareKey,"key"),(Equals,"="),(BasicString,"\"value\""),(Comment,"# comment"),(Newline,"\r\n")]);
+}
+
+#[test]
+fn toml_02() {
+    // Keys
+    check("bare_key = 1",&[(BareKey,"bare_key"),(Equals,"="),(Integer,"1")]);
+    check("bare-key = 1",&[(BareKey,"bare-key"),(Equals,"="),(Integer,"1")]);
+    check("1234 = 1",&[(BareKey,"1234"),(Equals,"="),(Integer,"1")]);
+    check("\"127.0.0.1\" = 1",&[(BasicString,"\"127.0.0.1\""),(Equals,"="),(Integer,"1")]);
+    check("'quoted \"value\"' = 1",&[(LiteralString,"'quoted \"value\"'"),(Equals,"="),(Integer,"1")]);
+    check("physical.color = \"orange\"",&[(BareKey,"physical"),(Dot,"."),(BareKey,"color"),(Equals,"="),(BasicString,"\"orange\"")]);
+    check("site.\"google.com\" = true",&[(BareKey,"site"),(Dot,"."),(BasicString,"\"google.com\""),(Equals,"="),(Boolean,"true")]);
+    // Keys which look like values
+    check("3.14159 = \"pi\"",&[(BareKey,"3"),(Dot,"."),(BareKey,"14159"),(Equals,"="),(BasicString,"\"pi\"")]);
+    check("true = false",&[(BareKey,"true"),(Equals,"="),(Boolean,"false")]);
+    check("1979-05-27 = 1979-05-27",&[(BareKey,"1979-05-27"),(Equals,"="),(LocalDate,"1979-05-27")]);
+}
+
+#[test]
+fn toml_03() {
+    // Strings
+    check_value(r#""I'm a string. \"You can quote me\". Name\tJos\u00E9\nLocation\tSF.""#,BasicString);
+    check_value(r"'C:\Users\nodejs\templates'",LiteralString);
+    check_value(r"'<\i\c*\s*>'",LiteralString);
+    check_value("\"\"",BasicString);
+    check_value("''",LiteralString);
+    check_value("\"\"\"\nRoses are red\nViolets are blue\"\"\"",MultilineBasicString);
+    check_value("\"\"\"\nThe quick brown \\\n\n  fox jumps over \\\n    the lazy dog.\"\"\"",MultilineBasicString);
+    check_value(r#""""Here are two quotation marks: "". Simple enough.""""#,MultilineBasicString);
+    check_value(r#""""Here are fifteen quotation marks: ""\"""\"""\"""\"""\".""""#,MultilineBasicString);
+    check_value(r#""""This," she said, "is just a pointless statement.""""#,MultilineBasicString);
+    check_value("'''\nThe first newline is\ntrimmed in raw strings.\n'''",MultilineLiteralString);
+    check_value("''''That,' she said, 'is still pointless.''''",MultilineLiteralString);
+    check_value("''''''",MultilineLiteralString);
+}
+
+#[test]
+fn toml_04() {
+    // Unterminated strings
+    check("x = \"abc\ny = 1",&[(BareKey,"x"),(Equals,"="),(UnterminatedString,"\"abc"),(Newline,"\n"),(BareKey,"y"),(Equals,"="),(Integer,"1")]);
+    check("x = 'abc",&[(BareKey,"x"),(Equals,"="),(UnterminatedString,"'abc")]);
+    check("x = \"\"\"abc\n\"\"",&[(BareKey,"x"),(Equals,"="),(UnterminatedString,"\"\"\"abc\n\"\"")]);
+    check("x = @",&[(BareKey,"x"),(Equals,"="),(Unknown,"@")]);
+}
+
+#[test]
+fn toml_05() {
+    // Integers
+    for i in ["+99","42","0","-17","1_000","5_349_221","53_49_221","1_2_3_4_5"] {
+        check_value(i,Integer);
+    }
+    for i in ["0xDEADBEEF","0xdeadbeef","0xdead_beef","0o01234567","0o755","0b11010110"] {
+        check_value(i,Integer);
+    }
+    // Floats
+    for f in ["+1.0","3.1415","-0.01","5e+22","1e06","-2E-2","6.626e-34","224_617.445_991_228"] {
+        check_value(f,Float);
+    }
+    for f in ["inf","+inf","-inf","nan","+nan","-nan"] {
+        check_value(f,Float);
+    }
+    // Booleans
+    check_value("true",Boolean);
+    check_value("false",Boolean);
+}
+
+#[test]
+fn toml_06() {
+    // Dates and times
+    for d in ["1979-05-27T07:32:00Z","1979-05-27T00:32:00-07:00","1979-05-27T00:32:00.999999-07:00","1979-05-27 07:32:00Z"] {
+        check_value(d,OffsetDateTime);
+    }
+    check_value("1979-05-27T07:32:00",LocalDateTime);
+    check_value("1979-05-27T00:32:00.999999",LocalDateTime);
+    check_value("1979-05-27",LocalDate);
+    check_value("07:32:00",LocalTime);
+    check_value("00:32:00.999999",LocalTime);
+}
+
+#[test]
+fn toml_07() {
+    // Arrays
+    check("integers = [ 1, 2, 3 ]",&[(BareKey,"integers"),(Equals,"="),(LeftSquare,"["),(Integer,"1"),(Comma,","),
+                                    (Integer,"2"),(Comma,","),(Integer,"3"),(RightSquare,"]")]);
+    check("x = [\n  [ 1, 2 ],\n  ['a'], # c\n]\ny = 0",&[
+        (BareKey,"x"),(Equals,"="),(LeftSquare,"["),(Newline,"\n"),
+        (LeftSquare,"["),(Integer,"1"),(Comma,","),(Integer,"2"),(RightSquare,"]"),(Comma,","),(Newline,"\n"),
+        (LeftSquare,"["),(LiteralString,"'a'"),(RightSquare,"]"),(Comma,","),(Comment,"# c"),(Newline,"\n"),
+        (RightSquare,"]"),(Newline,"\n"),(BareKey,"y"),(Equals,"="),(Integer,"0")]);
+    // Inline tables
+    check("point = { x = 1, y.z = true }",&[(BareKey,"point"),(Equals,"="),(LeftBrace,"{"),
+                                          (BareKey,"x"),(Equals,"="),(Integer,"1"),(Comma,","),
+                                          (BareKey,"y"),(Dot,"."),(BareKey,"z"),(Equals,"="),(Boolean,"true"),(RightBrace,"}")]);
+    check("a = [{ b = 1 }, { c = [2] }]",&[(BareKey,"a"),(Equals,"="),(LeftSquare,"["),
+                                          (LeftBrace,"{"),(BareKey,"b"),(Equals,"="),(Integer,"1"),(RightBrace,"}"),(Comma,","),
+                                          (LeftBrace,"{"),(BareKey,"c"),(Equals,"="),(LeftSquare,"["),(Integer,"2"),(RightSquare,"]"),(RightBrace,"}"),
+                                          (RightSquare,"]")]);
+}
+
+#[test]
+fn toml_08() {
+    // Tables
+    check("[table]\nkey = 1",&[(LeftSquare,"["),(BareKey,"table"),(RightSquare,"]"),(Newline,"\n"),(BareKey,"key"),(Equals,"="),(Integer,"1")]);
+    check("[ dog . \"tater.man\" ]",&[(LeftSquare,"["),(BareKey,"dog"),(Dot,"."),(BasicString,"\"tater.man\""),(RightSquare,"]")]);
+    check("[2024-01-01]",&[(LeftSquare,"["),(BareKey,"2024-01-01"),(RightSquare,"]")]);
+    // Array of tables
+    check("[[products]]\nname = \"Hammer\"\n\n[[products]]",&[
+        (DoubleLeftSquare,"[["),(BareKey,"products"),(DoubleRightSquare,"]]"),(Newline,"\n"),
+        (BareKey,"name"),(Equals,"="),(BasicString,"\"Hammer\""),(Newline,"\n"),(Newline,"\n"),
+        (DoubleLeftSquare,"[["),(BareKey,"products"),(DoubleRightSquare,"]]")]);
+    check("[[fruits.varieties]]",&[(DoubleLeftSquare,"[["),(BareKey,"fruits"),(Dot,"."),(BareKey,"varieties"),(DoubleRightSquare,"]]")]);
+}
+
+#[test]
+fn toml_09() {
+    // Regions are measured in characters
+    let ts = lex("é = \"ü\"");
+    let regions : Vec<_> = ts.iter().map(|t| (t.start(),t.end())).collect();
+    assert_eq!(regions,[(0,1),(1,2),(2,3),(3,4),(4,7)]);
+}
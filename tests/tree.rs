@@ -0,0 +1,179 @@
+use std::rc::Rc;
+use lexington::{Any,Lexer,Matcher,Scanner,Token,Unit,Within};
+use lexington::tree::{GreenElement,GreenNode,Lexemes,SyntaxNode,TreeBuilder};
+use lexington::util::Region;
+
+#[derive(Copy,Clone,Debug,PartialEq,Eq,Hash)]
+enum Kind {
+    // Tokens
+    WhiteSpace,
+    Comment,
+    Symbol,
+    LeftBrace,
+    RightBrace,
+    // Nodes
+    Root,
+    List
+}
+
+use Kind::*;
+
+fn lex(input: &str) -> Vec<Token<Kind>> {
+    let whitespace = Any([' ','\n']).one_or_more();
+    let comment = ';'.then(Within(' '..='~').zero_or_more());
+    let symbol = Within('a'..='z').or(Within('0'..='9')).or('+').or('é').one_or_more();
+    let scanner = Unit(whitespace,WhiteSpace)
+        .or(Unit(comment,Comment))
+        .or(Unit(symbol,Symbol))
+        .or(Unit('(',LeftBrace))
+        .or(Unit(')',RightBrace));
+    Lexer::new(input.chars(),scanner).collect()
+}
+
+/// Parse a sequence of (possibly nested) lists into a concrete syntax
+/// tree, retaining all whitespace and comments.
+fn parse(input: &str) -> Rc<GreenNode<Kind>> {
+    let mut builder = TreeBuilder::new();
+    builder.start_node(Root);
+    for (token,text) in Lexemes::new(input,lex(input)) {
+        match token.kind {
+            LeftBrace => {
+                builder.start_node(List);
+                builder.lexeme(token,text);
+            }
+            RightBrace => {
+                builder.lexeme(token,text);
+                builder.finish_node();
+            }
+            _ => builder.lexeme(token,text)
+        }
+    }
+    builder.finish_node();
+    builder.finish()
+}
+
+#[test]
+fn test_tree_01() {
+    let inputs = ["", "(+ 1 2)", "(a (b c)) ; comment\n(d)", "  (f  (g) ) ", "(é (é))"];
+    for input in inputs {
+        let green = parse(input);
+        assert_eq!(green.to_string(),input);
+        assert_eq!(green.len(),input.chars().count());
+        let root = SyntaxNode::new_root(green);
+        assert_eq!(root.text(),input);
+        let text : String = root.tokens().map(|t| t.text().to_string()).collect();
+        assert_eq!(text,input);
+    }
+}
+
+#[test]
+fn test_tree_02() {
+    let root = SyntaxNode::new_root(parse("(a (b c))"));
+    assert_eq!(root.kind(),Root);
+    assert_eq!(root.region(),Region::new(0,9));
+    let outer = root.first_child().unwrap();
+    assert_eq!(outer.kind(),List);
+    assert_eq!(outer.region(),Region::new(0,9));
+    assert_eq!(outer.parent(),Some(root.clone()));
+    let inner = outer.first_child().unwrap();
+    assert_eq!(inner.region(),Region::new(3,8));
+    assert_eq!(inner.text(),"(b c)");
+    assert_eq!(inner.ancestors().map(|n| n.kind()).collect::<Vec<_>>(),[List,List,Root]);
+    let kinds : Vec<_> = inner.children_with_tokens().map(|e| e.kind()).collect();
+    assert_eq!(kinds,[LeftBrace,Symbol,WhiteSpace,Symbol,RightBrace]);
+}
+
+#[test]
+fn test_tree_03() {
+    let root = SyntaxNode::new_root(parse("(a) (b) ; c\n(d)"));
+    let lists : Vec<_> = root.children().collect();
+    assert_eq!(lists.len(),3);
+    assert_eq!(lists[0].next_sibling(),Some(lists[1].clone()));
+    assert_eq!(lists[2].prev_sibling(),Some(lists[1].clone()));
+    assert_eq!(lists[0].prev_sibling(),None);
+    assert_eq!(lists[2].next_sibling(),None);
+    assert_eq!(lists[2].region(),Region::new(12,15));
+    assert_ne!(lists[0],lists[1]);
+}
+
+#[test]
+fn test_tree_04() {
+    let green = parse("(x) (x)");
+    let children = green.children();
+    match (&children[0],&children[2]) {
+        (GreenElement::Node(a),GreenElement::Node(b)) => assert!(Rc::ptr_eq(a,b)),
+        _ => panic!("expected nodes")
+    }
+    // But their syntax nodes are distinct
+    let root = SyntaxNode::new_root(green);
+    let lists : Vec<_> = root.children().collect();
+    assert_ne!(lists[0],lists[1]);
+    assert_eq!(lists[1].region(),Region::new(4,7));
+}
+
+#[test]
+fn test_tree_05() {
+    let root = SyntaxNode::new_root(parse("(ab (cd))"));
+    let t = root.token_at(0).unwrap();
+    assert_eq!((t.kind(),t.text()),(LeftBrace,"("));
+    let t = root.token_at(2).unwrap();
+    assert_eq!((t.kind(),t.text()),(Symbol,"ab"));
+    assert_eq!(t.region(),Region::new(1,3));
+    assert_eq!(t.to_token(),(Symbol,1..3));
+    let t = root.token_at(6).unwrap();
+    assert_eq!(t.text(),"cd");
+    assert_eq!(t.parent().region(),Region::new(4,8));
+    assert!(root.token_at(9).is_none());
+    let n = root.covering_node(Region::new(5,7)).unwrap();
+    assert_eq!(n.text(),"(cd)");
+    let n = root.covering_node(Region::new(2,5)).unwrap();
+    assert_eq!(n.text(),"(ab (cd))");
+}
+
+#[test]
+fn test_tree_06() {
+    let root = SyntaxNode::new_root(parse("(a (b) (c (d)))"));
+    let texts : Vec<_> = root.descendants().skip(1).map(|n| n.text()).collect();
+    assert_eq!(texts,["(a (b) (c (d)))","(b)","(c (d))","(d)"]);
+    let tokens : Vec<_> = root.tokens().filter(|t| t.kind() == Symbol).map(|t| t.region().start).collect();
+    assert_eq!(tokens,[1,4,8,11]);
+}
+
+#[test]
+fn test_tree_07() {
+    // Starting a node retrospectively, as for a binary expression
+    let mut builder = TreeBuilder::new();
+    builder.start_node(Root);
+    let checkpoint = builder.checkpoint();
+    builder.token(Symbol,"1");
+    builder.token(Symbol,"+");
+    builder.start_node_at(checkpoint,List);
+    builder.token(Symbol,"2");
+    builder.finish_node();
+    builder.finish_node();
+    let root = SyntaxNode::new_root(builder.finish());
+    let list = root.first_child().unwrap();
+    assert_eq!(list.kind(),List);
+    assert_eq!(list.text(),"1+2");
+    assert_eq!(root.children_with_tokens().count(),1);
+}
+
+#[test]
+fn test_tree_08() {
+    // Adjacent empty nodes of the same kind share a green node and an
+    // offset, but are distinct nodes.
+    let mut builder = TreeBuilder::new();
+    builder.start_node(Root);
+    builder.start_node(List);
+    builder.finish_node();
+    builder.start_node(List);
+    builder.finish_node();
+    builder.finish_node();
+    let root = SyntaxNode::new_root(builder.finish());
+    let children : Vec<_> = root.children().collect();
+    assert_eq!(children.len(),2);
+    assert_eq!(children[0].region(),children[1].region());
+    assert!(children[0] != children[1]);
+    assert!(children[0] == root.first_child().unwrap());
+    assert!(children[0].parent().unwrap() == root);
+}